@@ -291,10 +291,12 @@ impl OpenBookClient {
                         bids: market.market.bids,
                         asks: market.market.asks,
                         event_queue: market.market.event_queue,
-                        oracle: market.market.oracle,
+                        oracle: market.market.oracle(),
                         token_deposit_account,
                         base_vault,
                         quote_vault,
+                        base_mint: market.market.base_mint,
+                        quote_mint: market.market.quote_mint,
                         system_program: System::id(),
                         token_program: Token::id(),
                     },
@@ -349,10 +351,12 @@ impl OpenBookClient {
                         bids: market.market.bids,
                         asks: market.market.asks,
                         event_queue: market.market.event_queue,
-                        oracle: market.market.oracle,
+                        oracle: market.market.oracle(),
                         token_deposit_account,
                         base_vault,
                         quote_vault,
+                        base_mint: market.market.base_mint,
+                        quote_mint: market.market.quote_mint,
                         system_program: System::id(),
                         token_program: Token::id(),
                     },
@@ -397,10 +401,12 @@ impl OpenBookClient {
                         open_orders_account: self.open_orders_account,
                         owner: self.owner(),
                         market: market.address,
-                        token_base_account,
-                        token_quote_account,
+                        token_base_account: Some(token_base_account),
+                        token_quote_account: Some(token_quote_account),
                         base_vault,
                         quote_vault,
+                        base_mint: market.market.base_mint,
+                        quote_mint: market.market.quote_mint,
                         system_program: System::id(),
                         token_program: Token::id(),
                     },
@@ -434,10 +440,15 @@ impl OpenBookClient {
                         owner: self.owner(),
                         open_orders_account: self.open_orders_account,
                         market: market.address,
-                        token_base_account,
-                        token_quote_account,
+                        token_base_account: Some(token_base_account),
+                        token_quote_account: Some(token_quote_account),
                         base_vault,
                         quote_vault,
+                        base_mint: market.market.base_mint,
+                        quote_mint: market.market.quote_mint,
+                        native_unwrap_account: None,
+                        referrer: None,
+                        referrer_account: None,
                         system_program: System::id(),
                         token_program: Token::id(),
                     },
@@ -449,6 +460,29 @@ impl OpenBookClient {
         self.send_and_confirm_owner_tx(vec![ix]).await
     }
 
+    /// Resize the open orders account to hold `open_orders_count` orders, the fee payer
+    /// covers or receives the rent difference
+    pub async fn expand_open_orders(&self, open_orders_count: u8) -> anyhow::Result<Signature> {
+        let ix = Instruction {
+            program_id: openbook_v2::id(),
+            accounts: {
+                anchor_lang::ToAccountMetas::to_account_metas(
+                    &openbook_v2::accounts::ExpandOpenOrders {
+                        owner: self.owner(),
+                        payer: self.client.fee_payer.pubkey(),
+                        open_orders_account: self.open_orders_account,
+                        system_program: System::id(),
+                    },
+                    None,
+                )
+            },
+            data: anchor_lang::InstructionData::data(&openbook_v2::instruction::ExpandOpenOrders {
+                open_orders_count,
+            }),
+        };
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
     pub async fn send_and_confirm_owner_tx(
         &self,
         instructions: Vec<Instruction>,
@@ -0,0 +1,40 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct BuybackFees<'info> {
+    pub owner: Signer<'info>,
+    #[account(mut, has_one = market)]
+    pub open_orders_account: AccountLoader<'info, OpenOrdersAccountFixed>,
+    #[account(
+        mut,
+        has_one = quote_vault,
+        has_one = quote_mint,
+        has_one = buyback_mint,
+        has_one = buyback_oracle,
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut)]
+    pub quote_vault: InterfaceAccount<'info, TokenAccount>,
+    pub quote_mint: InterfaceAccount<'info, Mint>,
+    pub buyback_mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: The oracle can be one of several different account types and the pubkey is checked above
+    pub buyback_oracle: UncheckedAccount<'info>,
+
+    /// Pays the buyback tokens
+    #[account(mut)]
+    pub buyback_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// Buyback tokens go to the collect fee admin
+    #[account(
+        mut,
+        constraint = buyback_receiver_account.owner == market.load()?.collect_fee_admin,
+    )]
+    pub buyback_receiver_account: InterfaceAccount<'info, TokenAccount>,
+    /// Receives the bought back fees
+    #[account(mut, constraint = token_quote_account.mint == quote_mint.key())]
+    pub token_quote_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
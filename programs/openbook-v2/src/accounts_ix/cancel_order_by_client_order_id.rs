@@ -5,6 +5,7 @@ use anchor_lang::prelude::*;
 pub struct CancelOrderByClientOrderId<'info> {
     #[account(
         mut,
+        has_one = market,
         // owner is checked at #1
     )]
     pub open_orders_account: AccountLoader<'info, OpenOrdersAccountFixed>,
@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{OpenOrdersAccountFixed, TriggerOrdersAccount};
+
+#[derive(Accounts)]
+pub struct CancelTriggerOrder<'info> {
+    #[account(mut, has_one = open_orders_account)]
+    pub trigger_orders_account: AccountLoader<'info, TriggerOrdersAccount>,
+    #[account(mut)]
+    pub open_orders_account: AccountLoader<'info, OpenOrdersAccountFixed>,
+    pub owner: Signer<'info>,
+    #[account(mut, address = open_orders_account.load()?.owner)]
+    /// CHECK: receives the bounty back and is the owner of the open orders account
+    pub sol_destination: UncheckedAccount<'info>,
+}
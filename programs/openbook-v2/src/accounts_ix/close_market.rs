@@ -1,6 +1,6 @@
 use crate::state::*;
 use anchor_lang::prelude::*;
-use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct CloseMarket<'info> {
@@ -11,6 +11,10 @@ pub struct CloseMarket<'info> {
         has_one = bids,
         has_one = asks,
         has_one = event_queue,
+        has_one = base_vault,
+        has_one = quote_vault,
+        has_one = base_mint,
+        has_one = quote_mint,
         close = sol_destination
     )]
     pub market: AccountLoader<'info, Market>,
@@ -33,9 +37,25 @@ pub struct CloseMarket<'info> {
     )]
     pub event_queue: AccountLoader<'info, EventQueue>,
 
+    #[account(mut)]
+    pub base_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub quote_vault: InterfaceAccount<'info, TokenAccount>,
+    /// Withheld transfer fees of Token-2022 vaults are harvested to the mints
+    #[account(mut)]
+    pub base_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub quote_mint: InterfaceAccount<'info, Mint>,
+
     #[account(mut)]
     /// CHECK: target for account rent needs no checks
     pub sol_destination: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// Receive the tokens sent straight to the vaults, outside of any balance
+    #[account(mut, constraint = token_base_receiver_account.owner == sol_destination.key())]
+    pub token_base_receiver_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = token_quote_receiver_account.owner == sol_destination.key())]
+    pub token_quote_receiver_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
 }
@@ -0,0 +1,27 @@
+use crate::state::OpenOrdersAccountFixed;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseOpenOrdersAccount<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        close = sol_destination
+    )]
+    pub open_orders_account: AccountLoader<'info, OpenOrdersAccountFixed>,
+
+    /// CHECK: the trigger orders account of the open orders account, if it was created
+    #[account(
+        seeds = [b"TriggerOrders".as_ref(), open_orders_account.key().as_ref()],
+        bump,
+    )]
+    pub trigger_orders_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: target for account rent needs no checks
+    pub sol_destination: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
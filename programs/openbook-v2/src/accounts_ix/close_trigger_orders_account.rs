@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{OpenOrdersAccountFixed, TriggerOrdersAccount};
+
+#[derive(Accounts)]
+pub struct CloseTriggerOrdersAccount<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = open_orders_account,
+        close = sol_destination
+    )]
+    pub trigger_orders_account: AccountLoader<'info, TriggerOrdersAccount>,
+
+    #[account(has_one = owner)]
+    pub open_orders_account: AccountLoader<'info, OpenOrdersAccountFixed>,
+
+    #[account(mut)]
+    /// CHECK: target for account rent needs no checks
+    pub sol_destination: UncheckedAccount<'info>,
+}
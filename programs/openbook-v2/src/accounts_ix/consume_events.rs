@@ -12,4 +12,7 @@ pub struct ConsumeEvents<'info> {
 
     #[account(mut)]
     pub event_queue: AccountLoader<'info, EventQueue>,
+
+    /// CHECK: The oracle can be one of several different account types and the pubkey is checked against the market
+    pub oracle: Option<UncheckedAccount<'info>>,
 }
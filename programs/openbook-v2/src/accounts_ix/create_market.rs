@@ -1,6 +1,7 @@
+use crate::error::OpenBookError;
 use crate::state::*;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount};
 
 #[derive(Accounts)]
 #[instruction(market_index: MarketIndex)]
@@ -27,14 +28,19 @@ pub struct CreateMarket<'info> {
     pub payer: Signer<'info>,
 
     #[account(mut, token::mint = base_mint, token::authority = market)]
-    pub base_vault: Account<'info, TokenAccount>,
+    pub base_vault: InterfaceAccount<'info, TokenAccount>,
     #[account(mut, token::mint = quote_mint, token::authority = market)]
-    pub quote_vault: Account<'info, TokenAccount>,
+    pub quote_vault: InterfaceAccount<'info, TokenAccount>,
 
-    pub base_mint: Account<'info, Mint>,
-    pub quote_mint: Account<'info, Mint>,
+    pub base_mint: InterfaceAccount<'info, Mint>,
+    /// Both mints have to belong to the same token program, either SPL Token or Token-2022
+    #[account(
+        constraint = quote_mint.to_account_info().owner == base_mint.to_account_info().owner
+            @ OpenBookError::MixedTokenPrograms
+    )]
+    pub quote_mint: InterfaceAccount<'info, Mint>,
 
     pub system_program: Program<'info, System>,
-    /// CHECK: The oracle can be one of several different account types and the pubkey is checked above
-    pub oracle: UncheckedAccount<'info>,
+    /// CHECK: The oracle can be one of several different account types, markets without one don't support oracle pegged orders
+    pub oracle: Option<UncheckedAccount<'info>>,
 }
@@ -0,0 +1,22 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+#[instruction(referrer_id: u64)]
+pub struct CreateReferrer<'info> {
+    #[account(
+        init,
+        seeds = [b"Referrer".as_ref(), &referrer_id.to_le_bytes()],
+        bump,
+        payer = payer,
+        space = 8 + std::mem::size_of::<Referrer>(),
+    )]
+    pub referrer: AccountLoader<'info, Referrer>,
+    pub authority: Signer<'info>,
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Market, OpenOrdersAccountFixed, TriggerOrdersAccount};
+
+#[derive(Accounts)]
+pub struct CreateTriggerOrdersAccount<'info> {
+    #[account(
+        init,
+        seeds = [b"TriggerOrders".as_ref(), open_orders_account.key().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + std::mem::size_of::<TriggerOrdersAccount>(),
+    )]
+    pub trigger_orders_account: AccountLoader<'info, TriggerOrdersAccount>,
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        has_one = market,
+        constraint = open_orders_account.load()?.is_owner_or_delegate(owner.key()),
+    )]
+    pub open_orders_account: AccountLoader<'info, OpenOrdersAccountFixed>,
+    pub market: AccountLoader<'info, Market>,
+    pub system_program: Program<'info, System>,
+}
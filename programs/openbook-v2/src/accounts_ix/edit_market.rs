@@ -0,0 +1,20 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct EditMarket<'info> {
+    pub market_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = bids,
+        has_one = asks,
+        has_one = event_queue
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    /// Positive maker fees can only change while no orders rest on the book
+    pub bids: AccountLoader<'info, BookSide>,
+    pub asks: AccountLoader<'info, BookSide>,
+    pub event_queue: AccountLoader<'info, EventQueue>,
+}
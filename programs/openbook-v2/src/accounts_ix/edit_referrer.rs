@@ -0,0 +1,11 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct EditReferrer<'info> {
+    #[account(mut, has_one = authority)]
+    pub referrer: AccountLoader<'info, Referrer>,
+    pub authority: Signer<'info>,
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ExecuteTriggerOrder<'info> {
+    /// Receives the bounty of the trigger order
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = open_orders_account,
+        has_one = market,
+    )]
+    pub trigger_orders_account: AccountLoader<'info, TriggerOrdersAccount>,
+    #[account(mut, has_one = market)]
+    pub open_orders_account: AccountLoader<'info, OpenOrdersAccountFixed>,
+
+    #[account(
+        mut,
+        has_one = bids,
+        has_one = asks,
+        has_one = event_queue,
+    )]
+    pub market: AccountLoader<'info, Market>,
+    #[account(mut)]
+    pub bids: AccountLoader<'info, BookSide>,
+    #[account(mut)]
+    pub asks: AccountLoader<'info, BookSide>,
+    #[account(mut)]
+    pub event_queue: AccountLoader<'info, EventQueue>,
+
+    /// CHECK: The oracle can be one of several different account types and the pubkey is checked against the market
+    pub oracle: Option<UncheckedAccount<'info>>,
+}
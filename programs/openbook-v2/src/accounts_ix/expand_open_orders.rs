@@ -0,0 +1,15 @@
+use crate::state::OpenOrdersAccountFixed;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ExpandOpenOrders<'info> {
+    pub owner: Signer<'info>,
+    /// Pays the rent of added slots, and receives the rent of removed ones
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut, has_one = owner)]
+    pub open_orders_account: AccountLoader<'info, OpenOrdersAccountFixed>,
+
+    pub system_program: Program<'info, System>,
+}
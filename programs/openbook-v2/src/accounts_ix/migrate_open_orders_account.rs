@@ -0,0 +1,10 @@
+use crate::state::{Market, OpenOrdersAccountFixed};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct MigrateOpenOrdersAccount<'info> {
+    /// The address is checked against the account seeds with `market`
+    #[account(mut)]
+    pub open_orders_account: AccountLoader<'info, OpenOrdersAccountFixed>,
+    pub market: AccountLoader<'info, Market>,
+}
@@ -1,32 +1,64 @@
+pub use buyback_fees::*;
 pub use cancel_all_orders::*;
 pub use cancel_all_orders_by_side::*;
 pub use cancel_order::*;
 pub use cancel_order_by_client_order_id::*;
+pub use cancel_trigger_order::*;
 pub use close_market::*;
+pub use close_open_orders_account::*;
+pub use close_trigger_orders_account::*;
 pub use consume_events::*;
 pub use create_market::*;
+pub use create_referrer::*;
+pub use create_trigger_orders_account::*;
 pub use deposit::*;
+pub use edit_market::*;
+pub use edit_referrer::*;
+pub use execute_trigger_order::*;
+pub use expand_open_orders::*;
 pub use init_open_orders::*;
+pub use migrate_open_orders_account::*;
 pub use place_order::*;
+pub use place_orders::*;
 pub use place_take_order::*;
+pub use place_trigger_order::*;
+pub use prune_orders::*;
+pub use set_delegate::*;
 pub use settle_funds::*;
+pub use settle_funds_permissionless::*;
 pub use stub_oracle_close::*;
 pub use stub_oracle_create::*;
 pub use stub_oracle_set::*;
 pub use sweep_fees::*;
 
+mod buyback_fees;
 mod cancel_all_orders;
 mod cancel_all_orders_by_side;
 mod cancel_order;
 mod cancel_order_by_client_order_id;
+mod cancel_trigger_order;
 mod close_market;
+mod close_open_orders_account;
+mod close_trigger_orders_account;
 mod consume_events;
 mod create_market;
+mod create_referrer;
+mod create_trigger_orders_account;
 mod deposit;
+mod edit_market;
+mod edit_referrer;
+mod execute_trigger_order;
+mod expand_open_orders;
 mod init_open_orders;
+mod migrate_open_orders_account;
 mod place_order;
+mod place_orders;
 mod place_take_order;
+mod place_trigger_order;
+mod prune_orders;
+mod set_delegate;
 mod settle_funds;
+mod settle_funds_permissionless;
 mod stub_oracle_close;
 mod stub_oracle_create;
 mod stub_oracle_set;
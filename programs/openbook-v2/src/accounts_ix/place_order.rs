@@ -1,10 +1,10 @@
 use crate::state::*;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct PlaceOrder<'info> {
-    #[account(mut)]
+    #[account(mut, has_one = market)]
     pub open_orders_account: AccountLoader<'info, OpenOrdersAccountFixed>,
     pub owner: Signer<'info>,
     pub open_orders_admin: Option<Signer<'info>>,
@@ -14,7 +14,10 @@ pub struct PlaceOrder<'info> {
         has_one = bids,
         has_one = asks,
         has_one = event_queue,
-        has_one = oracle,
+        has_one = base_vault,
+        has_one = quote_vault,
+        has_one = base_mint,
+        has_one = quote_mint,
     )]
     pub market: AccountLoader<'info, Market>,
     #[account(mut)]
@@ -22,16 +25,18 @@ pub struct PlaceOrder<'info> {
     #[account(mut)]
     pub asks: AccountLoader<'info, BookSide>,
     #[account(mut, constraint = token_deposit_account.owner == owner.key())]
-    pub token_deposit_account: Account<'info, TokenAccount>,
+    pub token_deposit_account: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
-    pub base_vault: Account<'info, TokenAccount>,
+    pub base_vault: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
-    pub quote_vault: Account<'info, TokenAccount>,
+    pub quote_vault: InterfaceAccount<'info, TokenAccount>,
+    pub base_mint: InterfaceAccount<'info, Mint>,
+    pub quote_mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
     pub event_queue: AccountLoader<'info, EventQueue>,
 
-    /// CHECK: The oracle can be one of several different account types and the pubkey is checked above
-    pub oracle: UncheckedAccount<'info>,
-    pub token_program: Program<'info, Token>,
+    /// CHECK: The oracle can be one of several different account types and the pubkey is checked against the market
+    pub oracle: Option<UncheckedAccount<'info>>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
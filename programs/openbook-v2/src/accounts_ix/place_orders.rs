@@ -0,0 +1,43 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct PlaceOrders<'info> {
+    #[account(mut, has_one = market)]
+    pub open_orders_account: AccountLoader<'info, OpenOrdersAccountFixed>,
+    pub owner: Signer<'info>,
+    pub open_orders_admin: Option<Signer<'info>>,
+
+    #[account(
+        mut,
+        has_one = bids,
+        has_one = asks,
+        has_one = event_queue,
+        has_one = base_vault,
+        has_one = quote_vault,
+        has_one = base_mint,
+        has_one = quote_mint,
+    )]
+    pub market: AccountLoader<'info, Market>,
+    #[account(mut)]
+    pub bids: AccountLoader<'info, BookSide>,
+    #[account(mut)]
+    pub asks: AccountLoader<'info, BookSide>,
+    #[account(mut, constraint = base_deposit_account.owner == owner.key())]
+    pub base_deposit_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = quote_deposit_account.owner == owner.key())]
+    pub quote_deposit_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub base_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub quote_vault: InterfaceAccount<'info, TokenAccount>,
+    pub base_mint: InterfaceAccount<'info, Mint>,
+    pub quote_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub event_queue: AccountLoader<'info, EventQueue>,
+
+    /// CHECK: The oracle can be one of several different account types and the pubkey is checked against the market
+    pub oracle: Option<UncheckedAccount<'info>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
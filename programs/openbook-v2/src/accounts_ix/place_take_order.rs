@@ -1,9 +1,10 @@
 use crate::state::*;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct PlaceTakeOrder<'info> {
+    #[account(mut)]
     pub owner: Signer<'info>,
 
     #[account(
@@ -11,7 +12,10 @@ pub struct PlaceTakeOrder<'info> {
         has_one = bids,
         has_one = asks,
         has_one = event_queue,
-        has_one = oracle,
+        has_one = base_vault,
+        has_one = quote_vault,
+        has_one = base_mint,
+        has_one = quote_mint,
     )]
     pub market: AccountLoader<'info, Market>,
     #[account(mut)]
@@ -19,19 +23,29 @@ pub struct PlaceTakeOrder<'info> {
     #[account(mut)]
     pub asks: AccountLoader<'info, BookSide>,
     #[account(mut, constraint = token_deposit_account.owner == owner.key())]
-    pub token_deposit_account: Box<Account<'info, TokenAccount>>,
+    pub token_deposit_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// Can be left out when receiving a wrapped SOL mint, the owner then receives native SOL
+    #[account(mut)]
+    pub token_receiver_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// CHECK: Temporary token account used to unwrap SOL, the address is checked when used
+    #[account(mut)]
+    pub native_unwrap_account: Option<UncheckedAccount<'info>>,
+    /// Referrer rebates go to the token account registered by the referrer
+    pub referrer: Option<AccountLoader<'info, Referrer>>,
     #[account(mut)]
-    pub token_receiver_account: Box<Account<'info, TokenAccount>>,
+    pub referrer_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
     #[account(mut)]
-    pub base_vault: Account<'info, TokenAccount>,
+    pub base_vault: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
-    pub quote_vault: Account<'info, TokenAccount>,
+    pub quote_vault: InterfaceAccount<'info, TokenAccount>,
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(mut)]
     pub event_queue: AccountLoader<'info, EventQueue>,
 
-    /// CHECK: The oracle can be one of several different account types and the pubkey is checked above
-    pub oracle: UncheckedAccount<'info>,
-    pub token_program: Program<'info, Token>,
+    /// CHECK: The oracle can be one of several different account types and the pubkey is checked against the market
+    pub oracle: Option<UncheckedAccount<'info>>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub open_orders_admin: Option<Signer<'info>>,
 }
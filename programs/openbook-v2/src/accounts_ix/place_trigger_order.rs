@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct PlaceTriggerOrder<'info> {
+    #[account(
+        mut,
+        has_one = open_orders_account,
+        has_one = market,
+    )]
+    pub trigger_orders_account: AccountLoader<'info, TriggerOrdersAccount>,
+    #[account(mut, has_one = market)]
+    pub open_orders_account: AccountLoader<'info, OpenOrdersAccountFixed>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub open_orders_admin: Option<Signer<'info>>,
+
+    #[account(
+        mut,
+        has_one = base_vault,
+        has_one = quote_vault,
+        has_one = base_mint,
+        has_one = quote_mint,
+    )]
+    pub market: AccountLoader<'info, Market>,
+    #[account(mut, constraint = token_deposit_account.owner == owner.key())]
+    pub token_deposit_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub base_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub quote_vault: InterfaceAccount<'info, TokenAccount>,
+    pub base_mint: InterfaceAccount<'info, Mint>,
+    pub quote_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,14 @@
+use crate::state::OpenOrdersAccountFixed;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetDelegate<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        has_one = owner,
+    )]
+    pub open_orders_account: AccountLoader<'info, OpenOrdersAccountFixed>,
+    /// CHECK: Delegate can be any account, the delegate is revoked when not passed
+    pub delegate_account: Option<UncheckedAccount<'info>>,
+}
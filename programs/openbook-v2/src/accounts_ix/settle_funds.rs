@@ -1,25 +1,50 @@
 use crate::state::*;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct SettleFunds<'info> {
+    #[account(mut)]
     pub owner: Signer<'info>,
-    #[account(mut, has_one = owner)]
+    #[account(mut, has_one = market)]
     pub open_orders_account: AccountLoader<'info, OpenOrdersAccountFixed>,
-    #[account(mut)]
+    #[account(
+        mut,
+        has_one = base_vault,
+        has_one = quote_vault,
+        has_one = base_mint,
+        has_one = quote_mint,
+    )]
     pub market: AccountLoader<'info, Market>,
 
     #[account(mut)]
-    pub base_vault: Account<'info, TokenAccount>,
+    pub base_vault: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
-    pub quote_vault: Account<'info, TokenAccount>,
+    pub quote_vault: InterfaceAccount<'info, TokenAccount>,
+    pub base_mint: InterfaceAccount<'info, Mint>,
+    pub quote_mint: InterfaceAccount<'info, Mint>,
 
+    /// Token accounts can be left out for a wrapped SOL mint, the owner then receives native SOL
+    #[account(
+        mut,
+        constraint = token_base_account.owner == open_orders_account.load()?.owner,
+        constraint = token_base_account.mint == base_mint.key(),
+    )]
+    pub token_base_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = token_quote_account.owner == open_orders_account.load()?.owner,
+        constraint = token_quote_account.mint == quote_mint.key(),
+    )]
+    pub token_quote_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: Temporary token account used to unwrap SOL, the address is checked when used
     #[account(mut)]
-    pub token_base_account: Account<'info, TokenAccount>,
+    pub native_unwrap_account: Option<UncheckedAccount<'info>>,
+    /// Referrer rebates go to the token account registered by the referrer
+    pub referrer: Option<AccountLoader<'info, Referrer>>,
     #[account(mut)]
-    pub token_quote_account: Account<'info, TokenAccount>,
+    pub referrer_account: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,51 @@
+use crate::error::OpenBookError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct SettleFundsPermissionless<'info> {
+    pub caller: Signer<'info>,
+    #[account(mut, has_one = market)]
+    pub open_orders_account: AccountLoader<'info, OpenOrdersAccountFixed>,
+    #[account(
+        mut,
+        has_one = base_vault,
+        has_one = quote_vault,
+        has_one = base_mint,
+        has_one = quote_mint,
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut)]
+    pub base_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub quote_vault: InterfaceAccount<'info, TokenAccount>,
+    pub base_mint: InterfaceAccount<'info, Mint>,
+    pub quote_mint: InterfaceAccount<'info, Mint>,
+
+    /// Funds only go to the associated token accounts of the open orders account owner
+    #[account(
+        mut,
+        constraint = owner_base_account.key() == get_associated_token_address_with_program_id(
+            &open_orders_account.load()?.owner,
+            &base_mint.key(),
+            &token_program.key(),
+        ) @ OpenBookError::InvalidOwnerTokenAccount
+    )]
+    pub owner_base_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = owner_quote_account.key() == get_associated_token_address_with_program_id(
+            &open_orders_account.load()?.owner,
+            &quote_mint.key(),
+            &token_program.key(),
+        ) @ OpenBookError::InvalidOwnerTokenAccount
+    )]
+    pub owner_quote_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub caller_quote_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
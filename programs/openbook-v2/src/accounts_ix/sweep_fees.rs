@@ -1,20 +1,33 @@
 use crate::state::*;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct SweepFees<'info> {
     pub collect_fee_admin: Signer<'info>,
 
-    #[account(mut, has_one = collect_fee_admin)]
+    #[account(
+        mut,
+        has_one = collect_fee_admin,
+        has_one = base_vault,
+        has_one = quote_vault,
+        has_one = base_mint,
+        has_one = quote_mint,
+    )]
     pub market: AccountLoader<'info, Market>,
 
-    #[account(mut)]
-    pub token_receiver_account: Account<'info, TokenAccount>,
+    #[account(mut, constraint = token_base_receiver_account.owner == collect_fee_admin.key())]
+    pub token_base_receiver_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, constraint = token_receiver_account.owner == collect_fee_admin.key())]
+    pub token_receiver_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(mut)]
-    pub quote_vault: Account<'info, TokenAccount>,
+    pub base_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub quote_vault: InterfaceAccount<'info, TokenAccount>,
+    pub base_mint: InterfaceAccount<'info, Mint>,
+    pub quote_mint: InterfaceAccount<'info, Mint>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
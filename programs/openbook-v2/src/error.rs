@@ -77,7 +77,9 @@ pub enum OpenBookError {
     EventQueueContainsElements,
     #[msg("Taker fees should be positive and if maker fees are negative, greater or equal to their abs value")]
     InvalidFeesError,
-    #[msg("The order type is invalid. A taker order must be Market or ImmediateOrCancel")]
+    #[msg(
+        "The order type is invalid. A taker order must be Market, ImmediateOrCancel or FillOrKill"
+    )]
     InvalidOrderType,
     #[msg("The receiver is invalid. Makes sure the receiver's owner is the market admin")]
     InvalidFundsReceiver,
@@ -99,6 +101,64 @@ pub enum OpenBookError {
         "The `consume_events_admin` passed does not match this market's `consume_events_admin`."
     )]
     InvalidConsumeEventsAdmin,
+    #[msg("This market does not have a `market_admin` and thus cannot be edited.")]
+    NoMarketAdmin,
+    #[msg("The signer of this transaction is not this market's `market_admin`.")]
+    InvalidMarketAdmin,
+    #[msg("Cannot close an open orders account that still has funds or open orders.")]
+    NonEmptyOpenOrdersPosition,
+    #[msg("The order price is too far from the oracle and stable prices.")]
+    PriceOutsideBand,
+    #[msg("This market has an oracle, but the oracle account is missing.")]
+    MissingOracle,
+    #[msg("Oracle peg orders are not supported on markets without an oracle.")]
+    DisabledOraclePeg,
+    #[msg("A FillOrKill order could not be filled completely.")]
+    WouldNotFill,
+    #[msg("All trigger order slots are in use.")]
+    TooManyTriggerOrders,
+    #[msg("No active trigger order in this slot.")]
+    TriggerOrderNotFound,
+    #[msg("The trigger condition of the order is not met.")]
+    TriggerConditionNotMet,
+    #[msg("The market has expired, new orders are rejected.")]
+    MarketHasExpired,
+    #[msg("The market has not expired yet.")]
+    MarketHasNotExpired,
+    #[msg("The expiry time of the market must be in the future.")]
+    InvalidTimeExpiry,
+    #[msg("The orderbook still has orders and the market can't be closed.")]
+    OrderbookNotEmpty,
+    #[msg("The market still holds deposits, fees or rebates and can't be closed.")]
+    MarketHasOutstandingBalances,
+    #[msg("The base and quote mints must belong to the same token program.")]
+    MixedTokenPrograms,
+    #[msg("The vault did not receive the full amount after the mint's transfer fee.")]
+    TransferFeeNotCovered,
+    #[msg("Native SOL can only be used for the wrapped SOL side of a market.")]
+    NativeSolNotSupported,
+    #[msg("Only the owner of the open orders account can settle into native SOL.")]
+    NativeSolOwnerOnly,
+    #[msg("Funds can only be settled permissionlessly once the market expired or the account is idle.")]
+    OpenOrdersAccountNotIdle,
+    #[msg(
+        "The token account is not the associated token account of the open orders account owner."
+    )]
+    InvalidOwnerTokenAccount,
+    #[msg("The referrer and its registered token account have to be passed together, for the market quote mint.")]
+    InvalidReferrer,
+    #[msg("Fee tiers need increasing volume thresholds, no maker fees and maker rebates covered by every taker fee.")]
+    InvalidFeeTiers,
+    #[msg("The fees buyback is not enabled on this market.")]
+    BuybackDisabled,
+    #[msg("Only free open order slots can be removed from an open orders account.")]
+    OpenOrdersSlotInUse,
+    #[msg("The triggered order neither matched nor rested on the book.")]
+    TriggerOrderNotExecuted,
+    #[msg("Only version 1 open orders accounts derived from the passed market can be migrated.")]
+    OpenOrdersAccountNotMigratable,
+    #[msg("The trigger orders account still has active trigger orders.")]
+    ActiveTriggerOrders,
 }
 
 impl OpenBookError {
@@ -0,0 +1,84 @@
+use std::cmp;
+
+use anchor_lang::prelude::*;
+use fixed::types::I80F48;
+
+use crate::accounts_ix::*;
+use crate::accounts_zerocopy::*;
+use crate::error::*;
+use crate::logs::BuybackFeesLog;
+use crate::state::*;
+use crate::token_utils::*;
+
+pub fn buyback_fees(ctx: Context<BuybackFees>, max_buyback_fees: u64) -> Result<()> {
+    let mut open_orders_account = ctx.accounts.open_orders_account.load_mut()?;
+    require_keys_eq!(
+        open_orders_account.owner,
+        ctx.accounts.owner.key(),
+        OpenBookError::SomeError
+    );
+
+    let mut market = ctx.accounts.market.load_mut()?;
+    require!(market.is_buyback_enabled(), OpenBookError::BuybackDisabled);
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    let now_slot = Clock::get()?.slot;
+    open_orders_account.last_activity_timestamp = now_ts;
+    open_orders_account.expire_buyback_fees(now_ts, market.buyback_fees_expiry_interval);
+
+    let oracle_acc = AccountInfoRef::borrow(ctx.accounts.buyback_oracle.as_ref())?;
+    let price = oracle_state(
+        &oracle_acc,
+        &market.oracle_config,
+        ctx.accounts.buyback_mint.decimals,
+        market.quote_decimals,
+        now_slot,
+    )?
+    .price;
+    require_gt!(price, I80F48::ZERO, OpenBookError::UnexpectedOracle);
+
+    // get/update all values from market and drop reference to it before cpi
+    let quote_amount = cmp::min(
+        cmp::min(max_buyback_fees, open_orders_account.buyback_fees_accrued()),
+        market.quote_fees_accrued,
+    );
+    let buyback_amount = (I80F48::from(quote_amount) / price).ceil().to_num::<u64>();
+
+    open_orders_account.reduce_buyback_fees_accrued(quote_amount);
+    market.quote_fees_accrued -= quote_amount;
+
+    let seeds = market_seeds!(market);
+    let signer = &[&seeds[..]];
+
+    drop(market);
+    drop(open_orders_account);
+
+    token_transfer_to_vault(
+        buyback_amount,
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.buyback_token_account.to_account_info(),
+        &ctx.accounts.buyback_receiver_account.to_account_info(),
+        &ctx.accounts.buyback_mint,
+        &ctx.accounts.owner.to_account_info(),
+    )?;
+
+    token_transfer_from_vault(
+        quote_amount,
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.quote_vault.to_account_info(),
+        &ctx.accounts.token_quote_account.to_account_info(),
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.market.to_account_info(),
+        signer,
+    )?;
+
+    emit!(BuybackFeesLog {
+        market: ctx.accounts.market.key(),
+        open_orders_account: ctx.accounts.open_orders_account.key(),
+        quote_amount,
+        buyback_amount,
+        price: price.to_num::<f64>(),
+    });
+
+    Ok(())
+}
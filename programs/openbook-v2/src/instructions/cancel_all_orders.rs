@@ -12,13 +12,22 @@ pub fn cancel_all_orders(ctx: Context<CancelAllOrders>, limit: u8) -> Result<()>
         OpenBookError::SomeError
     );
 
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    account.fixed.last_activity_timestamp = now_ts;
+
     let market = ctx.accounts.market.load()?;
     let mut book = Orderbook {
         bids: ctx.accounts.bids.load_mut()?,
         asks: ctx.accounts.asks.load_mut()?,
     };
 
-    book.cancel_all_orders(&mut account.borrow_mut(), *market, limit, None)?;
+    book.cancel_all_orders(
+        &mut account.borrow_mut(),
+        ctx.accounts.open_orders_account.key(),
+        *market,
+        limit,
+        None,
+    )?;
 
     Ok(())
 }
@@ -16,13 +16,22 @@ pub fn cancel_all_orders_by_side(
         OpenBookError::SomeError
     );
 
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    account.fixed.last_activity_timestamp = now_ts;
+
     let market = ctx.accounts.market.load()?;
     let mut book = Orderbook {
         bids: ctx.accounts.bids.load_mut()?,
         asks: ctx.accounts.asks.load_mut()?,
     };
 
-    book.cancel_all_orders(&mut account.borrow_mut(), *market, limit, side_option)?;
+    book.cancel_all_orders(
+        &mut account.borrow_mut(),
+        ctx.accounts.open_orders_account.key(),
+        *market,
+        limit,
+        side_option,
+    )?;
 
     Ok(())
 }
@@ -15,6 +15,9 @@ pub fn cancel_order_by_client_order_id(
         OpenBookError::SomeError
     );
 
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    account.fixed.last_activity_timestamp = now_ts;
+
     let market = ctx.accounts.market.load()?;
     let mut book = Orderbook {
         bids: ctx.accounts.bids.load_mut()?,
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::state::*;
+
+/// Maximum number of order ids and client order ids passed to `cancel_orders`
+pub const MAX_CANCEL_ORDERS: usize = 24;
+
+pub fn cancel_orders(
+    ctx: Context<CancelOrder>,
+    order_ids: Vec<u128>,
+    client_order_ids: Vec<u64>,
+) -> Result<u8> {
+    require_gte!(MAX_CANCEL_ORDERS, order_ids.len() + client_order_ids.len());
+
+    let mut account = ctx.accounts.open_orders_account.load_full_mut()?;
+    // account constraint #1
+    require!(
+        account.fixed.is_owner_or_delegate(ctx.accounts.owner.key()),
+        OpenBookError::SomeError
+    );
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    account.fixed.last_activity_timestamp = now_ts;
+
+    let market = ctx.accounts.market.load()?;
+    let mut book = Orderbook {
+        bids: ctx.accounts.bids.load_mut()?,
+        asks: ctx.accounts.asks.load_mut()?,
+    };
+
+    let mut orders = Vec::with_capacity(order_ids.len() + client_order_ids.len());
+    for order_id in order_ids {
+        match account.find_order_with_order_id(order_id) {
+            Some(oo) => orders.push((oo.id, oo.side_and_tree())),
+            None => msg!("could not find order with id {order_id} in user account"),
+        }
+    }
+    for client_order_id in client_order_ids {
+        match account.find_order_with_client_order_id(client_order_id) {
+            Some(oo) => orders.push((oo.id, oo.side_and_tree())),
+            None => {
+                msg!("could not find order with client order id {client_order_id} in user account")
+            }
+        }
+    }
+
+    let mut cancelled = 0;
+    for (order_id, order_side_and_tree) in orders {
+        let cancel_result = book.cancel_order(
+            &mut account.borrow_mut(),
+            order_id,
+            order_side_and_tree,
+            *market,
+            Some(ctx.accounts.open_orders_account.key()),
+        );
+        if cancel_result.is_anchor_error_with_code(OpenBookError::OrderIdNotFound.into()) {
+            // The order may be filled or expired already, or listed twice
+            msg!(
+                "order {} was not found on orderbook, expired or filled already",
+                order_id
+            );
+        } else {
+            cancel_result?;
+            cancelled += 1;
+        }
+    }
+
+    Ok(cancelled)
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::state::*;
+
+pub fn cancel_trigger_order(ctx: Context<CancelTriggerOrder>, slot: u8) -> Result<()> {
+    let mut open_orders_account = ctx.accounts.open_orders_account.load_mut()?;
+    // account constraint #1
+    require!(
+        open_orders_account.is_owner_or_delegate(ctx.accounts.owner.key()),
+        OpenBookError::SomeError
+    );
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    open_orders_account.last_activity_timestamp = now_ts;
+
+    let mut trigger_orders_account = ctx.accounts.trigger_orders_account.load_mut()?;
+    let trigger_order = *trigger_orders_account.active_trigger_order(slot)?;
+    trigger_orders_account.trigger_orders[slot as usize] = TriggerOrder::default();
+
+    // The locked funds become free funds, ready to be settled
+    let position = &mut open_orders_account.position;
+    match trigger_order.side() {
+        Side::Bid => position.quote_free_native += trigger_order.locked_native,
+        Side::Ask => position.base_free_native += trigger_order.locked_native,
+    }
+
+    // Return the bounty to the owner, who paid it
+    **ctx
+        .accounts
+        .trigger_orders_account
+        .to_account_info()
+        .try_borrow_mut_lamports()? -= trigger_order.bounty_lamports;
+    **ctx.accounts.sol_destination.try_borrow_mut_lamports()? += trigger_order.bounty_lamports;
+
+    Ok(())
+}
@@ -1,6 +1,9 @@
 use crate::accounts_ix::*;
 use crate::error::OpenBookError;
+use crate::state::market_seeds;
+use crate::token_utils::*;
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount};
 
 pub fn close_market(ctx: Context<CloseMarket>) -> Result<()> {
     let market = ctx.accounts.market.load()?;
@@ -16,5 +19,66 @@ pub fn close_market(ctx: Context<CloseMarket>) -> Result<()> {
         event_queue.is_empty(),
         OpenBookError::EventQueueContainsElements
     );
+
+    require!(
+        ctx.accounts.bids.load()?.is_empty() && ctx.accounts.asks.load()?.is_empty(),
+        OpenBookError::OrderbookNotEmpty
+    );
+
+    // All user funds must be settled and all fees swept before the vaults can be closed
+    require!(
+        market.base_deposit_total == 0
+            && market.quote_deposit_total == 0
+            && market.base_fees_accrued == 0
+            && market.quote_fees_accrued == 0
+            && market.referrer_rebates_accrued == 0,
+        OpenBookError::MarketHasOutstandingBalances
+    );
+
+    let seeds = market_seeds!(market);
+    let signer = &[&seeds[..]];
+
+    drop(market);
+
+    for (vault, mint, receiver) in [
+        (
+            &ctx.accounts.base_vault,
+            &ctx.accounts.base_mint,
+            &ctx.accounts.token_base_receiver_account,
+        ),
+        (
+            &ctx.accounts.quote_vault,
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.token_quote_receiver_account,
+        ),
+    ] {
+        // Nothing is owed anymore, anything left was sent straight to the vault
+        token_transfer_from_vault(
+            vault.amount,
+            &ctx.accounts.token_program.to_account_info(),
+            &vault.to_account_info(),
+            &receiver.to_account_info(),
+            mint,
+            &ctx.accounts.market.to_account_info(),
+            signer,
+        )?;
+
+        harvest_withheld_fees(
+            &ctx.accounts.token_program.to_account_info(),
+            &vault.to_account_info(),
+            mint,
+        )?;
+
+        let cpi_context = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: vault.to_account_info(),
+                destination: ctx.accounts.sol_destination.to_account_info(),
+                authority: ctx.accounts.market.to_account_info(),
+            },
+        );
+        token_interface::close_account(cpi_context.with_signer(signer))?;
+    }
+
     Ok(())
 }
@@ -0,0 +1,30 @@
+use crate::accounts_ix::*;
+use crate::error::OpenBookError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+pub fn close_open_orders_account(ctx: Context<CloseOpenOrdersAccount>) -> Result<()> {
+    let open_orders_account = ctx.accounts.open_orders_account.load_full()?;
+
+    require!(
+        open_orders_account.fixed().position.is_empty(),
+        OpenBookError::NonEmptyOpenOrdersPosition
+    );
+    require!(
+        open_orders_account.all_orders().all(|oo| oo.is_free()),
+        OpenBookError::NonEmptyOpenOrdersPosition
+    );
+
+    // Trigger orders hold funds outside of the position
+    let trigger_orders_account = ctx.accounts.trigger_orders_account.to_account_info();
+    if trigger_orders_account.owner == &crate::id() {
+        let trigger_orders_account =
+            AccountLoader::<TriggerOrdersAccount>::try_from(&trigger_orders_account)?;
+        require!(
+            !trigger_orders_account.load()?.has_active_trigger_orders(),
+            OpenBookError::ActiveTriggerOrders
+        );
+    }
+
+    Ok(())
+}
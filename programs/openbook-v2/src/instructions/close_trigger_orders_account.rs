@@ -0,0 +1,14 @@
+use crate::accounts_ix::*;
+use crate::error::OpenBookError;
+use anchor_lang::prelude::*;
+
+pub fn close_trigger_orders_account(ctx: Context<CloseTriggerOrdersAccount>) -> Result<()> {
+    let trigger_orders_account = ctx.accounts.trigger_orders_account.load()?;
+
+    require!(
+        !trigger_orders_account.has_active_trigger_orders(),
+        OpenBookError::ActiveTriggerOrders
+    );
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use bytemuck::cast_ref;
 
+use crate::accounts_zerocopy::*;
 use crate::error::OpenBookError;
 use crate::state::*;
 
@@ -63,6 +64,21 @@ pub fn consume_events(ctx: Context<ConsumeEvents>, limit: usize) -> Result<()> {
             OpenBookError::InvalidConsumeEventsAdmin
         );
     }
+
+    // Event processing must not be blocked by oracle issues, only update the
+    // stable price when the oracle is usable
+    let clock = Clock::get()?;
+    let oracle_acc = ctx
+        .accounts
+        .oracle
+        .as_ref()
+        .map(|oracle| AccountInfoRef::borrow(oracle.as_ref()))
+        .transpose()?;
+    if let Ok(Some(oracle_price)) = market.oracle_price(oracle_acc.as_ref(), clock.slot) {
+        let now_ts: u64 = clock.unix_timestamp.try_into().unwrap();
+        market.update_stable_price(ctx.accounts.market.key(), oracle_price, now_ts);
+    }
+
     let mut event_queue = ctx.accounts.event_queue.load_mut()?;
     let remaining_accs = &ctx.remaining_accounts;
 
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use fixed::types::I80F48;
 
+use crate::accounts_zerocopy::*;
 use crate::error::*;
 use crate::state::*;
 use crate::util::fill_from_str;
@@ -23,21 +24,43 @@ pub fn create_market(
     open_orders_admin: Option<Pubkey>,
     consume_events_admin: Option<Pubkey>,
     close_market_admin: Option<Pubkey>,
+    market_admin: Option<Pubkey>,
+    time_expiry: i64,
 ) -> Result<()> {
-    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    let clock = Clock::get()?;
+    let now_ts: u64 = clock.unix_timestamp.try_into().unwrap();
 
     require!(
-        taker_fee.is_sign_positive()
-            && (maker_fee.is_sign_positive() || maker_fee.abs() <= taker_fee),
-        OpenBookError::InvalidFeesError
+        time_expiry == 0 || time_expiry > clock.unix_timestamp,
+        OpenBookError::InvalidTimeExpiry
     );
 
+    let maker_fee = I80F48::from_num(maker_fee);
+    let taker_fee = I80F48::from_num(taker_fee);
+    Market::validate_fees(maker_fee, taker_fee)?;
+
     let mut openbook_market = ctx.accounts.market.load_init()?;
     *openbook_market = Market {
         collect_fee_admin,
         open_orders_admin: open_orders_admin.into(),
         consume_events_admin: consume_events_admin.into(),
         close_market_admin: close_market_admin.into(),
+        market_admin: market_admin.into(),
+        taker_price_band_bps: 0,
+        maker_price_band_bps: 0,
+        disabled_instructions: 0,
+        padding2: Default::default(),
+        last_trade_price_lots: 0,
+        time_expiry,
+        fee_tiers: [bytemuck::Zeroable::zeroed(); MAX_FEE_TIERS],
+        fee_tiers_count: 0,
+        padding3: Default::default(),
+        buyback_mint: Pubkey::default(),
+        buyback_oracle: Pubkey::default(),
+        buyback_fees_expiry_interval: 0,
+        base_fees_swept: 0,
+        quote_fees_swept: 0,
+        fee_tier_volume_interval: 0,
         market_index,
         bump: *ctx.bumps.get("market").ok_or(OpenBookError::SomeError)?,
         base_decimals: ctx.accounts.base_mint.decimals,
@@ -47,7 +70,11 @@ pub fn create_market(
         bids: ctx.accounts.bids.key(),
         asks: ctx.accounts.asks.key(),
         event_queue: ctx.accounts.event_queue.key(),
-        oracle: ctx.accounts.oracle.key(),
+        oracle: ctx
+            .accounts
+            .oracle
+            .as_ref()
+            .map_or(Pubkey::default(), |oracle| oracle.key()),
         oracle_config: oracle_config.to_oracle_config(),
         stable_price_model: StablePriceModel::default(),
         quote_lot_size,
@@ -55,8 +82,8 @@ pub fn create_market(
         seq_num: 0,
         registration_time: now_ts,
 
-        maker_fee: I80F48::from_num(maker_fee),
-        taker_fee: I80F48::from_num(taker_fee),
+        maker_fee,
+        taker_fee,
         fee_penalty,
 
         fees_accrued: 0,
@@ -72,9 +99,21 @@ pub fn create_market(
         quote_fees_accrued: 0,
         referrer_rebates_accrued: 0,
 
-        reserved: [0; 1768],
+        reserved: [0; 1440],
     };
 
+    let oracle_acc = ctx
+        .accounts
+        .oracle
+        .as_ref()
+        .map(|oracle| AccountInfoRef::borrow(oracle.as_ref()))
+        .transpose()?;
+    if let Some(oracle_price) = openbook_market.oracle_price(oracle_acc.as_ref(), clock.slot)? {
+        openbook_market
+            .stable_price_model
+            .reset_to_price(oracle_price.to_num(), now_ts);
+    }
+
     let mut orderbook = Orderbook {
         bids: ctx.accounts.bids.load_init()?,
         asks: ctx.accounts.asks.load_init()?,
@@ -88,7 +127,7 @@ pub fn create_market(
         quote_decimals: ctx.accounts.quote_mint.decimals,
         base_lot_size,
         quote_lot_size,
-        oracle: ctx.accounts.oracle.key(),
+        oracle: ctx.accounts.oracle.as_ref().map(|oracle| oracle.key()),
     });
 
     Ok(())
@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+
+pub fn create_referrer(ctx: Context<CreateReferrer>, referrer_id: u64) -> Result<()> {
+    let mut referrer = ctx.accounts.referrer.load_init()?;
+    referrer.referrer_id = referrer_id;
+    referrer.authority = ctx.accounts.authority.key();
+    referrer.token_account = ctx.accounts.token_account.key();
+    referrer.mint = ctx.accounts.token_account.mint;
+    referrer.bump = *ctx.bumps.get("referrer").unwrap();
+
+    Ok(())
+}
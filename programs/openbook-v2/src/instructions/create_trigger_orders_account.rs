@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::CreateTriggerOrdersAccount;
+
+pub fn create_trigger_orders_account(ctx: Context<CreateTriggerOrdersAccount>) -> Result<()> {
+    let mut account = ctx.accounts.trigger_orders_account.load_init()?;
+
+    account.open_orders_account = ctx.accounts.open_orders_account.key();
+    account.market = ctx.accounts.market.key();
+    account.bump = *ctx.bumps.get("trigger_orders_account").unwrap();
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    ctx.accounts
+        .open_orders_account
+        .load_mut()?
+        .last_activity_timestamp = now_ts;
+
+    Ok(())
+}
@@ -1,24 +1,37 @@
 use crate::accounts_ix::Deposit;
 use crate::logs::DepositLog;
 use crate::state::*;
+use crate::token_utils::*;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Transfer};
 
 pub fn deposit(ctx: Context<Deposit>, base_amount_lots: u64, quote_amount_lots: u64) -> Result<()> {
     let mut open_orders_account = ctx.accounts.open_orders_account.load_full_mut()?;
     let mut market = ctx.accounts.market.load_mut()?;
+    market.check_instruction_enabled(MarketInstruction::Deposit)?;
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    open_orders_account.fixed.last_activity_timestamp = now_ts;
 
     if base_amount_lots != 0 {
         let base_amount_native = base_amount_lots * (market.base_lot_size as u64);
-        let cpi_context = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.token_base_account.to_account_info(),
-                to: ctx.accounts.base_vault.to_account_info(),
-                authority: ctx.accounts.owner.to_account_info(),
-            },
-        );
-        token::transfer(cpi_context, base_amount_native)?;
+        match &ctx.accounts.token_base_account {
+            Some(token_base_account) => token_transfer_to_vault(
+                base_amount_native,
+                &ctx.accounts.token_program.to_account_info(),
+                &token_base_account.to_account_info(),
+                &ctx.accounts.base_vault.to_account_info(),
+                &ctx.accounts.base_mint,
+                &ctx.accounts.owner.to_account_info(),
+            )?,
+            None => native_transfer_to_vault(
+                base_amount_native,
+                &ctx.accounts.token_program.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                &ctx.accounts.owner.to_account_info(),
+                &ctx.accounts.base_vault.to_account_info(),
+                &ctx.accounts.base_mint,
+            )?,
+        }
         open_orders_account.fixed.position.base_free_native += base_amount_native;
         market.base_deposit_total += base_amount_native;
 
@@ -31,15 +44,24 @@ pub fn deposit(ctx: Context<Deposit>, base_amount_lots: u64, quote_amount_lots:
 
     if quote_amount_lots != 0 {
         let quote_amount_native = quote_amount_lots * (market.quote_lot_size as u64);
-        let cpi_context = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.token_quote_account.to_account_info(),
-                to: ctx.accounts.quote_vault.to_account_info(),
-                authority: ctx.accounts.owner.to_account_info(),
-            },
-        );
-        token::transfer(cpi_context, quote_amount_native)?;
+        match &ctx.accounts.token_quote_account {
+            Some(token_quote_account) => token_transfer_to_vault(
+                quote_amount_native,
+                &ctx.accounts.token_program.to_account_info(),
+                &token_quote_account.to_account_info(),
+                &ctx.accounts.quote_vault.to_account_info(),
+                &ctx.accounts.quote_mint,
+                &ctx.accounts.owner.to_account_info(),
+            )?,
+            None => native_transfer_to_vault(
+                quote_amount_native,
+                &ctx.accounts.token_program.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                &ctx.accounts.owner.to_account_info(),
+                &ctx.accounts.quote_vault.to_account_info(),
+                &ctx.accounts.quote_mint,
+            )?,
+        }
 
         open_orders_account.fixed.position.quote_free_native += quote_amount_native;
         market.quote_deposit_total += quote_amount_native;
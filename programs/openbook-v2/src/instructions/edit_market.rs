@@ -0,0 +1,219 @@
+use anchor_lang::prelude::*;
+use fixed::types::I80F48;
+
+use crate::accounts_ix::*;
+use crate::error::OpenBookError;
+use crate::pod_option::PodOption;
+use crate::state::*;
+
+#[allow(clippy::too_many_arguments)]
+pub fn edit_market(
+    ctx: Context<EditMarket>,
+    maker_fee_opt: Option<f32>,
+    taker_fee_opt: Option<f32>,
+    fee_penalty_opt: Option<u64>,
+    oracle_config_opt: Option<OracleConfigParams>,
+    taker_price_band_bps_opt: Option<u16>,
+    maker_price_band_bps_opt: Option<u16>,
+    collect_fee_admin_opt: Option<Pubkey>,
+    open_orders_admin_opt: Option<Pubkey>,
+    consume_events_admin_opt: Option<Pubkey>,
+    close_market_admin_opt: Option<Pubkey>,
+    market_admin_opt: Option<Pubkey>,
+    disabled_instructions_opt: Option<u8>,
+    time_expiry_opt: Option<i64>,
+    fee_tiers_opt: Option<Vec<FeeTierParams>>,
+    fee_tier_volume_interval_opt: Option<u64>,
+    buyback_opt: Option<BuybackParams>,
+) -> Result<()> {
+    let mut market = ctx.accounts.market.load_mut()?;
+    let market_admin = Option::from(market.market_admin).ok_or(OpenBookError::NoMarketAdmin)?;
+    require!(
+        ctx.accounts.market_admin.key() == market_admin,
+        OpenBookError::InvalidMarketAdmin
+    );
+
+    if let Some(maker_fee) = maker_fee_opt {
+        msg!("Maker fee: old - {}, new - {}", market.maker_fee, maker_fee);
+        let maker_fee = I80F48::from_num(maker_fee);
+        // Bids reserve positive maker fees at placement and fills charge them when
+        // consumed, so they may only change once no order can still be filled
+        if maker_fee != market.maker_fee
+            && (maker_fee.is_positive() || market.maker_fee.is_positive())
+        {
+            require!(
+                ctx.accounts.bids.load()?.is_empty() && ctx.accounts.asks.load()?.is_empty(),
+                OpenBookError::OrderbookNotEmpty
+            );
+            require!(
+                ctx.accounts.event_queue.load()?.is_empty(),
+                OpenBookError::EventQueueContainsElements
+            );
+        }
+        market.maker_fee = maker_fee;
+    }
+    if let Some(taker_fee) = taker_fee_opt {
+        msg!("Taker fee: old - {}, new - {}", market.taker_fee, taker_fee);
+        market.taker_fee = I80F48::from_num(taker_fee);
+    }
+    if let Some(fee_tiers) = fee_tiers_opt {
+        require_gte!(
+            MAX_FEE_TIERS,
+            fee_tiers.len(),
+            OpenBookError::InvalidFeeTiers
+        );
+        msg!(
+            "Fee tiers: old - {:?}, new - {:?}",
+            market.active_fee_tiers(),
+            fee_tiers
+        );
+        market.fee_tiers = [bytemuck::Zeroable::zeroed(); MAX_FEE_TIERS];
+        for (tier, params) in market.fee_tiers.iter_mut().zip(fee_tiers.iter()) {
+            *tier = params.to_fee_tier();
+        }
+        market.fee_tiers_count = fee_tiers.len() as u8;
+    }
+    Market::validate_fees(market.maker_fee, market.taker_fee)?;
+    market.validate_fee_tiers()?;
+
+    if let Some(fee_tier_volume_interval) = fee_tier_volume_interval_opt {
+        msg!(
+            "Fee tier volume interval: old - {}, new - {}",
+            market.fee_tier_volume_interval,
+            fee_tier_volume_interval
+        );
+        market.fee_tier_volume_interval = fee_tier_volume_interval;
+    }
+
+    if let Some(fee_penalty) = fee_penalty_opt {
+        msg!(
+            "Fee penalty: old - {}, new - {}",
+            market.fee_penalty,
+            fee_penalty
+        );
+        market.fee_penalty = fee_penalty;
+    }
+
+    if let Some(oracle_config) = oracle_config_opt {
+        msg!(
+            "Oracle config: old - conf_filter {} max_staleness_slots {}, new - conf_filter {} max_staleness_slots {:?}",
+            market.oracle_config.conf_filter,
+            market.oracle_config.max_staleness_slots,
+            oracle_config.conf_filter,
+            oracle_config.max_staleness_slots
+        );
+        market.oracle_config = oracle_config.to_oracle_config();
+    }
+
+    if let Some(taker_price_band_bps) = taker_price_band_bps_opt {
+        require_gte!(10_000, taker_price_band_bps);
+        msg!(
+            "Taker price band bps: old - {}, new - {}",
+            market.taker_price_band_bps,
+            taker_price_band_bps
+        );
+        market.taker_price_band_bps = taker_price_band_bps;
+    }
+    if let Some(maker_price_band_bps) = maker_price_band_bps_opt {
+        require_gte!(10_000, maker_price_band_bps);
+        msg!(
+            "Maker price band bps: old - {}, new - {}",
+            market.maker_price_band_bps,
+            maker_price_band_bps
+        );
+        market.maker_price_band_bps = maker_price_band_bps;
+    }
+
+    if let Some(collect_fee_admin) = collect_fee_admin_opt {
+        require_keys_neq!(collect_fee_admin, Pubkey::default());
+        msg!(
+            "Collect fee admin: old - {}, new - {}",
+            market.collect_fee_admin,
+            collect_fee_admin
+        );
+        market.collect_fee_admin = collect_fee_admin;
+    }
+    if let Some(open_orders_admin) = open_orders_admin_opt {
+        msg!(
+            "Open orders admin: old - {:?}, new - {}",
+            Option::<Pubkey>::from(market.open_orders_admin),
+            open_orders_admin
+        );
+        market.open_orders_admin = optional_admin(open_orders_admin);
+    }
+    if let Some(consume_events_admin) = consume_events_admin_opt {
+        msg!(
+            "Consume events admin: old - {:?}, new - {}",
+            Option::<Pubkey>::from(market.consume_events_admin),
+            consume_events_admin
+        );
+        market.consume_events_admin = optional_admin(consume_events_admin);
+    }
+    if let Some(close_market_admin) = close_market_admin_opt {
+        msg!(
+            "Close market admin: old - {:?}, new - {}",
+            Option::<Pubkey>::from(market.close_market_admin),
+            close_market_admin
+        );
+        market.close_market_admin = optional_admin(close_market_admin);
+    }
+    if let Some(market_admin) = market_admin_opt {
+        msg!(
+            "Market admin: old - {:?}, new - {}",
+            Option::<Pubkey>::from(market.market_admin),
+            market_admin
+        );
+        market.market_admin = optional_admin(market_admin);
+    }
+
+    if let Some(disabled_instructions) = disabled_instructions_opt {
+        require_eq!(disabled_instructions & !MarketInstruction::ALL, 0);
+        msg!(
+            "Disabled instructions: old - {:#06b}, new - {:#06b}",
+            market.disabled_instructions,
+            disabled_instructions
+        );
+        market.disabled_instructions = disabled_instructions;
+    }
+
+    if let Some(time_expiry) = time_expiry_opt {
+        require!(
+            time_expiry == 0 || time_expiry > Clock::get()?.unix_timestamp,
+            OpenBookError::InvalidTimeExpiry
+        );
+        msg!(
+            "Time expiry: old - {}, new - {}",
+            market.time_expiry,
+            time_expiry
+        );
+        market.time_expiry = time_expiry;
+    }
+
+    if let Some(buyback) = buyback_opt {
+        require!(
+            buyback.mint == Pubkey::default() || buyback.fees_expiry_interval > 0,
+            OpenBookError::SomeError
+        );
+        msg!(
+            "Buyback: old - mint {} oracle {} fees_expiry_interval {}, new - {:?}",
+            market.buyback_mint,
+            market.buyback_oracle,
+            market.buyback_fees_expiry_interval,
+            buyback
+        );
+        market.buyback_mint = buyback.mint;
+        market.buyback_oracle = buyback.oracle;
+        market.buyback_fees_expiry_interval = buyback.fees_expiry_interval;
+    }
+
+    Ok(())
+}
+
+/// The default pubkey removes an optional admin
+fn optional_admin(admin: Pubkey) -> PodOption<Pubkey> {
+    if admin == Pubkey::default() {
+        None.into()
+    } else {
+        Some(admin).into()
+    }
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::state::*;
+
+pub fn edit_order(
+    ctx: Context<PlaceOrder>,
+    cancel_order_id: Option<u128>,
+    cancel_client_order_id: Option<u64>,
+    order: Order,
+    limit: u8,
+) -> Result<Option<u128>> {
+    {
+        let mut account = ctx.accounts.open_orders_account.load_full_mut()?;
+        // account constraint #1
+        require!(
+            account.fixed.is_owner_or_delegate(ctx.accounts.owner.key()),
+            OpenBookError::SomeError
+        );
+
+        let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+        account.fixed.last_activity_timestamp = now_ts;
+
+        let oo = match (cancel_order_id, cancel_client_order_id) {
+            (Some(order_id), None) => account.find_order_with_order_id(order_id).ok_or_else(|| {
+                error_msg!("could not find order with id {order_id} in user account")
+            })?,
+            (None, Some(client_order_id)) => account
+                .find_order_with_client_order_id(client_order_id)
+                .ok_or_else(|| {
+                    error_msg!(
+                        "could not find order with client order id {client_order_id} in user account"
+                    )
+                })?,
+            _ => {
+                return Err(error_msg!(
+                    "exactly one of cancel_order_id and cancel_client_order_id must be set"
+                ))
+            }
+        };
+        let order_id = oo.id;
+        let order_side_and_tree = oo.side_and_tree();
+
+        let mut book = Orderbook {
+            bids: ctx.accounts.bids.load_mut()?,
+            asks: ctx.accounts.asks.load_mut()?,
+        };
+        let market = ctx.accounts.market.load()?;
+
+        // Shrinking a fixed order without changing anything but its size keeps its place on the book
+        if let OrderParams::Fixed {
+            price_lots,
+            order_type: PostOrderType::Limit | PostOrderType::PostOnly,
+            display_base_lots: 0,
+        } = order.params
+        {
+            let quantity = match order.side {
+                Side::Bid => market.max_base_lots_to_post(
+                    order.max_base_lots,
+                    order.max_quote_lots_including_fees,
+                    price_lots.max(1),
+                ),
+                Side::Ask => order.max_base_lots,
+            };
+            if order_side_and_tree.side() == order.side
+                && quantity > 0
+                && book.reduce_order(
+                    &mut account.borrow_mut(),
+                    order_id,
+                    order_side_and_tree,
+                    &order,
+                    price_lots,
+                    quantity,
+                    now_ts,
+                    *market,
+                    ctx.accounts.open_orders_account.key(),
+                )?
+            {
+                return Ok(Some(order_id));
+            }
+        }
+
+        book.cancel_order(
+            &mut account.borrow_mut(),
+            order_id,
+            order_side_and_tree,
+            *market,
+            Some(ctx.accounts.open_orders_account.key()),
+        )?;
+    }
+
+    crate::instructions::place_order(ctx, order, limit)
+}
@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+
+pub fn edit_referrer(ctx: Context<EditReferrer>) -> Result<()> {
+    let mut referrer = ctx.accounts.referrer.load_mut()?;
+    referrer.token_account = ctx.accounts.token_account.key();
+    referrer.mint = ctx.accounts.token_account.mint;
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::accounts_zerocopy::*;
+use crate::error::*;
+use crate::state::*;
+
+use super::lock_order_funds;
+
+pub fn execute_trigger_order(ctx: Context<ExecuteTriggerOrder>, slot: u8) -> Result<Option<u128>> {
+    let mut open_orders_account = ctx.accounts.open_orders_account.load_full_mut()?;
+    let open_orders_account_pk = ctx.accounts.open_orders_account.key();
+
+    let mut market = ctx.accounts.market.load_mut()?;
+    let mut book = Orderbook {
+        bids: ctx.accounts.bids.load_mut()?,
+        asks: ctx.accounts.asks.load_mut()?,
+    };
+    let mut event_queue = ctx.accounts.event_queue.load_mut()?;
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    let now_slot = Clock::get()?.slot;
+    let oracle_acc = ctx
+        .accounts
+        .oracle
+        .as_ref()
+        .map(|oracle| AccountInfoRef::borrow(oracle.as_ref()))
+        .transpose()?;
+    let oracle_state = market.oracle_state(oracle_acc.as_ref(), now_slot)?;
+    if let Some(oracle_state) = oracle_state {
+        market.update_stable_price(ctx.accounts.market.key(), oracle_state.price, now_ts);
+    }
+
+    let mut trigger_orders_account = ctx.accounts.trigger_orders_account.load_mut()?;
+    let trigger_order = *trigger_orders_account.active_trigger_order(slot)?;
+    // Expired trigger orders, and all of them once the market expired, can be removed
+    // without meeting their condition
+    let order = if market.is_expired(now_ts) {
+        None
+    } else {
+        trigger_order.order_args().to_order()?
+    };
+    if order.is_some() {
+        let oracle_price_lots = oracle_state.map(|state| market.native_price_to_lot(state.price));
+        let last_trade_price_lots =
+            (market.last_trade_price_lots > 0).then_some(market.last_trade_price_lots);
+        require!(
+            trigger_order.condition().is_met(
+                trigger_order.trigger_price_lots,
+                oracle_price_lots,
+                last_trade_price_lots
+            ),
+            OpenBookError::TriggerConditionNotMet
+        );
+    }
+    trigger_orders_account.trigger_orders[slot as usize] = TriggerOrder::default();
+    drop(trigger_orders_account);
+
+    // The locked funds pay for the order, whatever remains is free to settle
+    let position = &mut open_orders_account.fixed_mut().position;
+    match trigger_order.side() {
+        Side::Bid => position.quote_free_native += trigger_order.locked_native,
+        Side::Ask => position.base_free_native += trigger_order.locked_native,
+    }
+
+    let order_id = if let Some(order) = order {
+        market.check_instruction_enabled(order.market_instruction())?;
+        // Only an admin who signed the placement counts, the book checks it is still current
+        let open_orders_admin = trigger_order.open_orders_admin();
+        let order_with_amounts = book.new_order(
+            &order,
+            &mut market,
+            &mut event_queue,
+            oracle_state.map(|state| state.price),
+            oracle_state.map_or(0, |state| state.staleness_slots(now_slot)),
+            &mut Some(open_orders_account.borrow_mut()),
+            &open_orders_account_pk,
+            None,
+            now_ts,
+            trigger_order.limit,
+            open_orders_admin,
+            ctx.remaining_accounts,
+        )?;
+        // The bounty is only paid for orders that did something
+        require!(
+            order_with_amounts.order_id.is_some() || order_with_amounts.total_base_taken_native > 0,
+            OpenBookError::TriggerOrderNotExecuted
+        );
+
+        let deposit_amount = lock_order_funds(
+            &order,
+            &order_with_amounts,
+            &mut open_orders_account.fixed_mut().position,
+            &mut market,
+        );
+        require_eq!(deposit_amount, 0);
+
+        order_with_amounts.order_id
+    } else {
+        msg!("Trigger order or market is expired");
+        None
+    };
+
+    // Pay the bounty
+    **ctx
+        .accounts
+        .trigger_orders_account
+        .to_account_info()
+        .try_borrow_mut_lamports()? -= trigger_order.bounty_lamports;
+    **ctx.accounts.keeper.try_borrow_mut_lamports()? += trigger_order.bounty_lamports;
+
+    Ok(order_id)
+}
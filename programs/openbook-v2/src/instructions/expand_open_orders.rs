@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::accounts_ix::*;
+use crate::state::*;
+
+pub fn expand_open_orders(ctx: Context<ExpandOpenOrders>, open_orders_count: u8) -> Result<()> {
+    let new_space = OpenOrdersAccount::space(open_orders_count)?;
+    let new_rent_minimum = Rent::get()?.minimum_balance(new_space);
+
+    let realloc_account = ctx.accounts.open_orders_account.as_ref();
+    let old_space = realloc_account.data_len();
+    let old_lamports = realloc_account.lamports();
+
+    if new_space >= old_space {
+        // pay the extra rent before growing the account
+        if old_lamports < new_rent_minimum {
+            let cpi_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: realloc_account.clone(),
+                },
+            );
+            system_program::transfer(cpi_context, new_rent_minimum - old_lamports)?;
+        }
+
+        realloc_account.realloc(new_space, false)?;
+
+        let mut account = ctx.accounts.open_orders_account.load_full_mut()?;
+        account.expand_dynamic_content(open_orders_count)?;
+    } else {
+        {
+            let mut account = ctx.accounts.open_orders_account.load_full_mut()?;
+            account.shrink_dynamic_content(open_orders_count)?;
+        }
+
+        realloc_account.realloc(new_space, false)?;
+
+        // refund the rent that is no longer needed
+        let excess_lamports = old_lamports.saturating_sub(new_rent_minimum);
+        **realloc_account.try_borrow_mut_lamports()? -= excess_lamports;
+        **ctx.accounts.payer.try_borrow_mut_lamports()? += excess_lamports;
+    }
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    ctx.accounts
+        .open_orders_account
+        .load_mut()?
+        .last_activity_timestamp = now_ts;
+
+    Ok(())
+}
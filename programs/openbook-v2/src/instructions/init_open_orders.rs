@@ -12,7 +12,9 @@ pub fn init_open_orders(
     account.fixed.account_num = account_num;
     account.fixed.bump = *ctx.bumps.get("open_orders_account").unwrap();
     account.fixed.owner = ctx.accounts.owner.key();
+    account.fixed.market = ctx.accounts.market.key();
     account.fixed.delegate = Pubkey::default();
+    account.fixed.last_activity_timestamp = Clock::get()?.unix_timestamp.try_into().unwrap();
 
     account.expand_dynamic_content(open_orders_count)?;
 
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::OpenBookError;
+use crate::state::*;
+
+pub fn migrate_open_orders_account(ctx: Context<MigrateOpenOrdersAccount>) -> Result<()> {
+    let market = ctx.accounts.market.key();
+    let mut account = ctx.accounts.open_orders_account.load_full_mut()?;
+    require_eq!(
+        *account.header_version(),
+        1,
+        OpenBookError::OpenOrdersAccountNotMigratable
+    );
+
+    // Version 1 accounts don't store their market, but it is part of their address seeds
+    let fixed = account.fixed();
+    let address = Pubkey::create_program_address(
+        &[
+            b"OpenOrders".as_ref(),
+            fixed.owner.as_ref(),
+            market.as_ref(),
+            &fixed.account_num.to_le_bytes(),
+            &[fixed.bump],
+        ],
+        &crate::id(),
+    )
+    .map_err(|_| OpenBookError::OpenOrdersAccountNotMigratable)?;
+    require_keys_eq!(
+        address,
+        ctx.accounts.open_orders_account.key(),
+        OpenBookError::OpenOrdersAccountNotMigratable
+    );
+
+    account.migrate_market(market);
+
+    Ok(())
+}
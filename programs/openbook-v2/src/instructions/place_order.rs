@@ -2,13 +2,11 @@ use std::cmp;
 
 use anchor_lang::prelude::*;
 
-use anchor_spl::token::{self, Transfer};
-use fixed::types::I80F48;
-
 use crate::accounts_ix::*;
 use crate::accounts_zerocopy::*;
 use crate::error::*;
 use crate::state::*;
+use crate::token_utils::*;
 
 // TODO
 #[allow(clippy::too_many_arguments)]
@@ -27,6 +25,7 @@ pub fn place_order(ctx: Context<PlaceOrder>, order: Order, limit: u8) -> Result<
     let open_orders_account_pk = ctx.accounts.open_orders_account.key();
 
     let mut market = ctx.accounts.market.load_mut()?;
+    market.check_instruction_enabled(order.market_instruction())?;
     let mut book = Orderbook {
         bids: ctx.accounts.bids.load_mut()?,
         asks: ctx.accounts.asks.load_mut()?,
@@ -34,25 +33,27 @@ pub fn place_order(ctx: Context<PlaceOrder>, order: Order, limit: u8) -> Result<
     let mut event_queue = ctx.accounts.event_queue.load_mut()?;
 
     let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
-    let oracle_price = market.oracle_price(
-        &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?,
-        Clock::get()?.slot,
-    )?;
+    let now_slot = Clock::get()?.slot;
+    let oracle_acc = ctx
+        .accounts
+        .oracle
+        .as_ref()
+        .map(|oracle| AccountInfoRef::borrow(oracle.as_ref()))
+        .transpose()?;
+    let oracle_state = market.oracle_state(oracle_acc.as_ref(), now_slot)?;
+    if let Some(oracle_state) = oracle_state {
+        market.update_stable_price(ctx.accounts.market.key(), oracle_state.price, now_ts);
+    }
 
-    let OrderWithAmounts {
-        order_id,
-        total_base_taken_native,
-        total_quote_taken_native,
-        placed_quantity,
-        maker_fees,
-        ..
-    } = book.new_order(
+    let order_with_amounts = book.new_order(
         &order,
         &mut market,
         &mut event_queue,
-        oracle_price,
+        oracle_state.map(|state| state.price),
+        oracle_state.map_or(0, |state| state.staleness_slots(now_slot)),
         &mut Some(open_orders_account.borrow_mut()),
         &open_orders_account_pk,
+        None,
         now_ts,
         limit,
         ctx.accounts
@@ -62,8 +63,51 @@ pub fn place_order(ctx: Context<PlaceOrder>, order: Order, limit: u8) -> Result<
         ctx.remaining_accounts,
     )?;
 
-    let position = &mut open_orders_account.fixed_mut().position;
-    let (to_vault, deposit_amount) = match order.side {
+    let deposit_amount = lock_order_funds(
+        &order,
+        &order_with_amounts,
+        &mut open_orders_account.fixed_mut().position,
+        &mut market,
+    );
+    open_orders_account.fixed.last_activity_timestamp = now_ts;
+
+    let (to_vault, mint) = match order.side {
+        Side::Bid => (&ctx.accounts.quote_vault, &ctx.accounts.quote_mint),
+        Side::Ask => (&ctx.accounts.base_vault, &ctx.accounts.base_mint),
+    };
+
+    // Transfer funds
+    token_transfer_to_vault(
+        deposit_amount,
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.token_deposit_account.to_account_info(),
+        &to_vault.to_account_info(),
+        mint,
+        &ctx.accounts.owner.to_account_info(),
+    )?;
+    Ok(order_with_amounts.order_id)
+}
+
+/// Lock the funds needed for the matched and posted parts of an order, using free funds
+/// of the position first. Returns the amount that needs to be deposited into the vault
+/// of the order's side.
+pub(crate) fn lock_order_funds(
+    order: &Order,
+    order_with_amounts: &OrderWithAmounts,
+    position: &mut Position,
+    market: &mut Market,
+) -> u64 {
+    let OrderWithAmounts {
+        order_id,
+        total_base_taken_native,
+        total_quote_taken_native,
+        placed_quantity,
+        maker_fees,
+        taker_fee_remainder,
+        ..
+    } = *order_with_amounts;
+
+    let locked = match order.side {
         Side::Bid => {
             let free_quote = position.quote_free_native;
 
@@ -84,15 +128,11 @@ pub fn place_order(ctx: Context<PlaceOrder>, order: Order, limit: u8) -> Result<
             let free_qty_to_lock = cmp::min(max_quote_including_fees, free_quote);
             position.quote_free_native -= free_qty_to_lock;
 
-            // Update market deposit total
-            market.quote_deposit_total += (max_quote_including_fees - free_qty_to_lock)
-                - (I80F48::from(total_quote_taken_native) * (market.taker_fee - market.maker_fee))
-                    .to_num::<u64>();
-
-            (
-                ctx.accounts.quote_vault.to_account_info(),
-                max_quote_including_fees - free_qty_to_lock,
-            )
+            // Update market deposit total. Taker fees stay part of it until the maker
+            // rebates and referrer rebates they pay for are settled
+            market.quote_deposit_total += max_quote_including_fees - free_qty_to_lock;
+
+            max_quote_including_fees - free_qty_to_lock
         }
 
         Side::Ask => {
@@ -106,24 +146,12 @@ pub fn place_order(ctx: Context<PlaceOrder>, order: Order, limit: u8) -> Result<
             // Update market deposit total
             market.base_deposit_total += max_base_native - free_qty_to_lock;
 
-            (
-                ctx.accounts.base_vault.to_account_info(),
-                max_base_native - free_qty_to_lock,
-            )
+            max_base_native - free_qty_to_lock
         }
     };
 
-    // Transfer funds
-    if deposit_amount > 0 {
-        let cpi_context = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.token_deposit_account.to_account_info(),
-                to: to_vault,
-                authority: ctx.accounts.owner.to_account_info(),
-            },
-        );
-        token::transfer(cpi_context, deposit_amount)?;
-    }
-    Ok(order_id)
+    market.quote_deposit_total -= taker_fee_remainder;
+    market.quote_fees_accrued += taker_fee_remainder;
+
+    locked
 }
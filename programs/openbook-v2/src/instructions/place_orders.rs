@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::accounts_zerocopy::*;
+use crate::error::*;
+use crate::state::*;
+use crate::token_utils::*;
+
+use super::lock_order_funds;
+
+pub fn place_orders(
+    ctx: Context<PlaceOrders>,
+    orders: Vec<Option<Order>>,
+    limit: u8,
+) -> Result<Vec<Option<u128>>> {
+    let mut open_orders_account = ctx.accounts.open_orders_account.load_full_mut()?;
+    // account constraint #1
+    require!(
+        open_orders_account
+            .fixed
+            .is_owner_or_delegate(ctx.accounts.owner.key()),
+        OpenBookError::SomeError
+    );
+    let open_orders_account_pk = ctx.accounts.open_orders_account.key();
+
+    let mut market = ctx.accounts.market.load_mut()?;
+    let mut book = Orderbook {
+        bids: ctx.accounts.bids.load_mut()?,
+        asks: ctx.accounts.asks.load_mut()?,
+    };
+    let mut event_queue = ctx.accounts.event_queue.load_mut()?;
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    let now_slot = Clock::get()?.slot;
+    let oracle_acc = ctx
+        .accounts
+        .oracle
+        .as_ref()
+        .map(|oracle| AccountInfoRef::borrow(oracle.as_ref()))
+        .transpose()?;
+    let oracle_state = market.oracle_state(oracle_acc.as_ref(), now_slot)?;
+    if let Some(oracle_state) = oracle_state {
+        market.update_stable_price(ctx.accounts.market.key(), oracle_state.price, now_ts);
+    }
+
+    let mut base_deposit_amount = 0;
+    let mut quote_deposit_amount = 0;
+    let mut order_ids = Vec::with_capacity(orders.len());
+    for order in orders {
+        let order = match order {
+            Some(order) => order,
+            None => {
+                msg!("Order is already expired");
+                order_ids.push(None);
+                continue;
+            }
+        };
+        require_gte!(order.max_base_lots, 0);
+        require_gte!(order.max_quote_lots_including_fees, 0);
+        market.check_instruction_enabled(order.market_instruction())?;
+
+        let order_with_amounts = book.new_order(
+            &order,
+            &mut market,
+            &mut event_queue,
+            oracle_state.map(|state| state.price),
+            oracle_state.map_or(0, |state| state.staleness_slots(now_slot)),
+            &mut Some(open_orders_account.borrow_mut()),
+            &open_orders_account_pk,
+            None,
+            now_ts,
+            limit,
+            ctx.accounts
+                .open_orders_admin
+                .as_ref()
+                .map(|signer| signer.key()),
+            ctx.remaining_accounts,
+        )?;
+
+        let deposit_amount = lock_order_funds(
+            &order,
+            &order_with_amounts,
+            &mut open_orders_account.fixed_mut().position,
+            &mut market,
+        );
+        match order.side {
+            Side::Bid => quote_deposit_amount += deposit_amount,
+            Side::Ask => base_deposit_amount += deposit_amount,
+        }
+        order_ids.push(order_with_amounts.order_id);
+    }
+    open_orders_account.fixed.last_activity_timestamp = now_ts;
+
+    // Transfer funds for all orders at once
+    token_transfer_to_vault(
+        base_deposit_amount,
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.base_deposit_account.to_account_info(),
+        &ctx.accounts.base_vault.to_account_info(),
+        &ctx.accounts.base_mint,
+        &ctx.accounts.owner.to_account_info(),
+    )?;
+    token_transfer_to_vault(
+        quote_deposit_amount,
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.quote_deposit_account.to_account_info(),
+        &ctx.accounts.quote_vault.to_account_info(),
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.owner.to_account_info(),
+    )?;
+
+    Ok(order_ids)
+}
@@ -1,10 +1,9 @@
 use anchor_lang::prelude::*;
 
-use anchor_spl::token::{self, Transfer};
-
 use crate::accounts_ix::*;
 use crate::accounts_zerocopy::*;
 use crate::state::*;
+use crate::token_utils::*;
 
 // TODO
 #[allow(clippy::too_many_arguments)]
@@ -17,6 +16,7 @@ pub fn place_take_order<'info>(
     require_gte!(order.max_quote_lots_including_fees, 0);
 
     let mut market = ctx.accounts.market.load_mut()?;
+    market.check_instruction_enabled(MarketInstruction::PlaceTakeOrder)?;
     let mut book = Orderbook {
         bids: ctx.accounts.bids.load_mut()?,
         asks: ctx.accounts.asks.load_mut()?,
@@ -25,9 +25,22 @@ pub fn place_take_order<'info>(
     let mut event_queue = ctx.accounts.event_queue.load_mut()?;
 
     let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
-    let oracle_price = market.oracle_price(
-        &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?,
-        Clock::get()?.slot,
+    let now_slot = Clock::get()?.slot;
+    let oracle_acc = ctx
+        .accounts
+        .oracle
+        .as_ref()
+        .map(|oracle| AccountInfoRef::borrow(oracle.as_ref()))
+        .transpose()?;
+    let oracle_state = market.oracle_state(oracle_acc.as_ref(), now_slot)?;
+    if let Some(oracle_state) = oracle_state {
+        market.update_stable_price(ctx.accounts.market.key(), oracle_state.price, now_ts);
+    }
+
+    let referrer = referrer_token_account(
+        ctx.accounts.referrer.as_ref(),
+        ctx.accounts.referrer_account.as_deref(),
+        &market.quote_mint,
     )?;
 
     let side = order.side;
@@ -37,14 +50,17 @@ pub fn place_take_order<'info>(
         total_base_taken_native,
         total_quote_taken_native,
         referrer_amount,
+        taker_fee_remainder,
         ..
     } = book.new_order(
         &order,
         &mut market,
         &mut event_queue,
-        oracle_price,
+        oracle_state.map(|state| state.price),
+        oracle_state.map_or(0, |state| state.staleness_slots(now_slot)),
         &mut None,
         &ctx.accounts.owner.key(),
+        referrer.map(|(referrer, _)| referrer),
         now_ts,
         limit,
         ctx.accounts
@@ -54,72 +70,97 @@ pub fn place_take_order<'info>(
         ctx.remaining_accounts,
     )?;
 
-    let (from_vault, to_vault, deposit_amount, withdraw_amount) = match side {
+    let (deposit_amount, withdraw_amount) = match side {
         Side::Bid => {
             // Update market deposit total
             market.quote_deposit_total += total_quote_taken_native;
-            (
-                ctx.accounts.base_vault.to_account_info(),
-                ctx.accounts.quote_vault.to_account_info(),
-                total_quote_taken_native,
-                total_base_taken_native,
-            )
+            market.base_deposit_total -= total_base_taken_native;
+            (total_quote_taken_native, total_base_taken_native)
         }
 
         Side::Ask => {
             // Update market deposit total
             market.base_deposit_total += total_base_taken_native;
-            (
-                ctx.accounts.quote_vault.to_account_info(),
-                ctx.accounts.base_vault.to_account_info(),
-                total_base_taken_native,
-                total_quote_taken_native,
-            )
+            market.quote_deposit_total -= total_quote_taken_native;
+            (total_base_taken_native, total_quote_taken_native)
         }
     };
+    let (from_vault, from_mint, to_vault, to_mint) = match side {
+        Side::Bid => (
+            &ctx.accounts.base_vault,
+            &ctx.accounts.base_mint,
+            &ctx.accounts.quote_vault,
+            &ctx.accounts.quote_mint,
+        ),
+        Side::Ask => (
+            &ctx.accounts.quote_vault,
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.base_vault,
+            &ctx.accounts.base_mint,
+        ),
+    };
 
     // Transfer funds from token_deposit_account to vault
-    if deposit_amount > 0 {
-        let cpi_context = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.token_deposit_account.to_account_info(),
-                to: to_vault,
-                authority: ctx.accounts.owner.to_account_info(),
-            },
-        );
-        token::transfer(cpi_context, deposit_amount)?;
+    token_transfer_to_vault(
+        deposit_amount,
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.token_deposit_account.to_account_info(),
+        &to_vault.to_account_info(),
+        to_mint,
+        &ctx.accounts.owner.to_account_info(),
+    )?;
+
+    // The referrer part of the taker fees leaves the deposits, to the market fees
+    // when there is no referrer
+    market.quote_deposit_total -= referrer_amount;
+    if referrer.is_none() {
+        market.quote_fees_accrued += referrer_amount;
     }
+    market.quote_deposit_total -= taker_fee_remainder;
+    market.quote_fees_accrued += taker_fee_remainder;
 
     let seeds = market_seeds!(market);
     let signer = &[&seeds[..]];
 
     drop(market);
 
-    if withdraw_amount > 0 {
-        let cpi_context = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: from_vault,
-                to: ctx.accounts.token_receiver_account.to_account_info(),
-                authority: ctx.accounts.market.to_account_info(),
-            },
-        );
-        token::transfer(cpi_context.with_signer(signer), withdraw_amount)?;
+    match &ctx.accounts.token_receiver_account {
+        Some(token_receiver_account) => token_transfer_from_vault(
+            withdraw_amount,
+            &ctx.accounts.token_program.to_account_info(),
+            &from_vault.to_account_info(),
+            &token_receiver_account.to_account_info(),
+            from_mint,
+            &ctx.accounts.market.to_account_info(),
+            signer,
+        )?,
+        None => native_transfer_from_vault(
+            withdraw_amount,
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &from_vault.to_account_info(),
+            ctx.accounts
+                .native_unwrap_account
+                .as_ref()
+                .map(|account| account.as_ref()),
+            &ctx.accounts.owner.to_account_info(),
+            from_mint,
+            &ctx.accounts.market.to_account_info(),
+            signer,
+        )?,
     }
 
     // Transfer to referrer
-    if !ctx.remaining_accounts.is_empty() && referrer_amount > 0 {
-        let referrer = ctx.remaining_accounts[0].to_account_info();
-        let cpi_context = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.quote_vault.to_account_info(),
-                to: referrer,
-                authority: ctx.accounts.market.to_account_info(),
-            },
-        );
-        token::transfer(cpi_context.with_signer(signer), referrer_amount)?;
+    if let Some((_, referrer_account)) = referrer {
+        token_transfer_from_vault(
+            referrer_amount,
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.quote_vault.to_account_info(),
+            &referrer_account.to_account_info(),
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.market.to_account_info(),
+            signer,
+        )?;
     }
 
     Ok(order_id)
@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use fixed::types::I80F48;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::state::*;
+use crate::token_utils::*;
+
+pub fn place_trigger_order(
+    ctx: Context<PlaceTriggerOrder>,
+    condition: TriggerCondition,
+    trigger_price_lots: i64,
+    order: PlaceOrderArgs,
+    limit: u8,
+) -> Result<u8> {
+    require_gt!(trigger_price_lots, 0);
+    require_gt!(limit, 0);
+    require_gte!(order.max_base_lots, 0);
+    require_gte!(order.max_quote_lots_including_fees, 0);
+    let market_instruction = order
+        .to_order()?
+        .ok_or_else(|| error_msg!("trigger order is already expired"))?
+        .market_instruction();
+
+    let mut open_orders_account = ctx.accounts.open_orders_account.load_mut()?;
+    // account constraint #1
+    require!(
+        open_orders_account.is_owner_or_delegate(ctx.accounts.owner.key()),
+        OpenBookError::SomeError
+    );
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    open_orders_account.last_activity_timestamp = now_ts;
+
+    let mut market = ctx.accounts.market.load_mut()?;
+    market.check_instruction_enabled(market_instruction)?;
+    // The open orders admin can't sign when the order gets triggered, remember who signed now
+    let mut open_orders_admin_signer = Pubkey::default();
+    if let Some(open_orders_admin) = Option::<Pubkey>::from(market.open_orders_admin) {
+        open_orders_admin_signer = ctx
+            .accounts
+            .open_orders_admin
+            .as_ref()
+            .map(|signer| signer.key())
+            .ok_or(OpenBookError::MissingOpenOrdersAdmin)?;
+        require_eq!(
+            open_orders_admin,
+            open_orders_admin_signer,
+            OpenBookError::InvalidOpenOrdersAdmin
+        );
+    }
+
+    // Lock the maximum the order can use until it is triggered or cancelled
+    let (locked_native, to_vault, mint) = match order.side {
+        Side::Bid => {
+            // Bids don't pay more than their price for the base lots, plus the highest fees
+            let max_quote_lots = match order.order_type {
+                PlaceOrderType::Market => order.max_quote_lots_including_fees,
+                _ => order
+                    .price_lots
+                    .checked_mul(order.max_base_lots)
+                    .and_then(|quote_lots| {
+                        let fees = (I80F48::from(quote_lots) * market.max_fee())
+                            .ceil()
+                            .checked_to_num::<i64>()?;
+                        // One extra lot covers rounding up maker and taker fees separately
+                        quote_lots.checked_add(fees)?.checked_add(1)
+                    })
+                    .map_or(order.max_quote_lots_including_fees, |max_quote_lots| {
+                        max_quote_lots.min(order.max_quote_lots_including_fees)
+                    }),
+            };
+            let locked_native: u64 = max_quote_lots
+                .checked_mul(market.quote_lot_size)
+                .ok_or(OpenBookError::MathError)?
+                .try_into()
+                .unwrap();
+            market.quote_deposit_total += locked_native;
+            (
+                locked_native,
+                &ctx.accounts.quote_vault,
+                &ctx.accounts.quote_mint,
+            )
+        }
+        Side::Ask => {
+            let locked_native: u64 = order
+                .max_base_lots
+                .checked_mul(market.base_lot_size)
+                .ok_or(OpenBookError::MathError)?
+                .try_into()
+                .unwrap();
+            market.base_deposit_total += locked_native;
+            (
+                locked_native,
+                &ctx.accounts.base_vault,
+                &ctx.accounts.base_mint,
+            )
+        }
+    };
+
+    let mut trigger_orders_account = ctx.accounts.trigger_orders_account.load_mut()?;
+    let slot = trigger_orders_account.next_trigger_order_slot()?;
+    trigger_orders_account.trigger_orders[slot] = TriggerOrder {
+        is_active: 1,
+        condition: condition.into(),
+        side: order.side.into(),
+        order_type: order.order_type.into(),
+        self_trade_behavior: order.self_trade_behavior.into(),
+        limit,
+        padding: Default::default(),
+        trigger_price_lots,
+        price_lots: order.price_lots,
+        max_base_lots: order.max_base_lots,
+        max_quote_lots_including_fees: order.max_quote_lots_including_fees,
+        client_order_id: order.client_order_id,
+        expiry_timestamp: order.expiry_timestamp,
+        locked_native,
+        bounty_lamports: TRIGGER_ORDER_BOUNTY_LAMPORTS,
+        open_orders_admin: open_orders_admin_signer,
+        reserved: [0; 24],
+    };
+    drop(trigger_orders_account);
+
+    token_transfer_to_vault(
+        locked_native,
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.token_deposit_account.to_account_info(),
+        &to_vault.to_account_info(),
+        mint,
+        &ctx.accounts.owner.to_account_info(),
+    )?;
+
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.owner.to_account_info(),
+            to: ctx.accounts.trigger_orders_account.to_account_info(),
+        },
+    );
+    system_program::transfer(cpi_context, TRIGGER_ORDER_BOUNTY_LAMPORTS)?;
+
+    Ok(slot as u8)
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::OpenBookError;
+use crate::state::*;
+
+pub fn prune_orders(ctx: Context<PruneOrders>, limit: u8) -> Result<()> {
+    let market = ctx.accounts.market.load()?;
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    require!(
+        market.is_expired(now_ts),
+        OpenBookError::MarketHasNotExpired
+    );
+
+    let mut account = ctx.accounts.open_orders_account.load_full_mut()?;
+    let mut book = Orderbook {
+        bids: ctx.accounts.bids.load_mut()?,
+        asks: ctx.accounts.asks.load_mut()?,
+    };
+
+    // Cancelled orders credit their funds back to the account, settle_funds pays them out
+    book.cancel_all_orders(
+        &mut account.borrow_mut(),
+        ctx.accounts.open_orders_account.key(),
+        *market,
+        limit,
+        None,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::logs::SetDelegateLog;
+
+pub fn set_delegate(ctx: Context<SetDelegate>) -> Result<()> {
+    let mut account = ctx.accounts.open_orders_account.load_mut()?;
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    account.last_activity_timestamp = now_ts;
+
+    let delegate_account = ctx
+        .accounts
+        .delegate_account
+        .as_ref()
+        .map(|account| account.key());
+
+    account.delegate = delegate_account.unwrap_or_default();
+
+    emit!(SetDelegateLog {
+        open_orders_account: ctx.accounts.open_orders_account.key(),
+        delegate: delegate_account,
+    });
+
+    Ok(())
+}
@@ -1,66 +1,125 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Transfer};
 
 use crate::accounts_ix::*;
+use crate::error::*;
 use crate::state::*;
+use crate::token_utils::*;
 
 pub fn settle_funds<'info>(ctx: Context<'_, '_, '_, 'info, SettleFunds<'info>>) -> Result<()> {
     let mut open_orders_account = ctx.accounts.open_orders_account.load_full_mut()?;
+    // account constraint #1
+    require!(
+        open_orders_account
+            .fixed
+            .is_owner_or_delegate(ctx.accounts.owner.key()),
+        OpenBookError::SomeError
+    );
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    open_orders_account.fixed.last_activity_timestamp = now_ts;
+
+    let account_owner = open_orders_account.fixed.owner;
     let position = &mut open_orders_account.fixed_mut().position;
     let mut market = ctx.accounts.market.load_mut()?;
 
-    if ctx.remaining_accounts.is_empty() {
-        market.quote_fees_accrued += position.referrer_rebates_accrued;
-    } else {
+    let referrer = referrer_token_account(
+        ctx.accounts.referrer.as_ref(),
+        ctx.accounts.referrer_account.as_ref(),
+        &market.quote_mint,
+    )?;
+    if referrer.is_some() {
         market.fees_to_referrers += position.referrer_rebates_accrued;
+    } else {
+        market.quote_fees_accrued += position.referrer_rebates_accrued;
     }
     market.referrer_rebates_accrued -= position.referrer_rebates_accrued;
     market.base_deposit_total -= position.base_free_native;
-    market.quote_deposit_total -= position.quote_free_native;
+    // Referrer rebates leave the deposits too, either to the referrer or to the market fees
+    market.quote_deposit_total -= position.quote_free_native + position.referrer_rebates_accrued;
 
     let seeds = market_seeds!(market);
     let signer = &[&seeds[..]];
 
     drop(market);
 
-    if !ctx.remaining_accounts.is_empty() && position.referrer_rebates_accrued > 0 {
-        let referrer = ctx.remaining_accounts[0].to_account_info();
-        let cpi_context = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.quote_vault.to_account_info(),
-                to: referrer,
-                authority: ctx.accounts.market.to_account_info(),
-            },
-        );
-        token::transfer(
-            cpi_context.with_signer(signer),
+    if let Some((_, referrer_account)) = referrer {
+        token_transfer_from_vault(
             position.referrer_rebates_accrued,
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.quote_vault.to_account_info(),
+            &referrer_account.to_account_info(),
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.market.to_account_info(),
+            signer,
         )?;
     }
 
-    if position.base_free_native > 0 {
-        let cpi_context = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.base_vault.to_account_info(),
-                to: ctx.accounts.token_base_account.to_account_info(),
-                authority: ctx.accounts.market.to_account_info(),
-            },
-        );
-        token::transfer(cpi_context.with_signer(signer), position.base_free_native)?;
+    match &ctx.accounts.token_base_account {
+        Some(token_base_account) => token_transfer_from_vault(
+            position.base_free_native,
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.base_vault.to_account_info(),
+            &token_base_account.to_account_info(),
+            &ctx.accounts.base_mint,
+            &ctx.accounts.market.to_account_info(),
+            signer,
+        )?,
+        None => {
+            // Native SOL goes to the signer, a delegate can't settle it
+            require_keys_eq!(
+                ctx.accounts.owner.key(),
+                account_owner,
+                OpenBookError::NativeSolOwnerOnly
+            );
+            native_transfer_from_vault(
+                position.base_free_native,
+                &ctx.accounts.token_program.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                &ctx.accounts.base_vault.to_account_info(),
+                ctx.accounts
+                    .native_unwrap_account
+                    .as_ref()
+                    .map(|account| account.as_ref()),
+                &ctx.accounts.owner.to_account_info(),
+                &ctx.accounts.base_mint,
+                &ctx.accounts.market.to_account_info(),
+                signer,
+            )?
+        }
     }
 
-    if position.quote_free_native > 0 {
-        let cpi_context = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.quote_vault.to_account_info(),
-                to: ctx.accounts.token_quote_account.to_account_info(),
-                authority: ctx.accounts.market.to_account_info(),
-            },
-        );
-        token::transfer(cpi_context.with_signer(signer), position.quote_free_native)?;
+    match &ctx.accounts.token_quote_account {
+        Some(token_quote_account) => token_transfer_from_vault(
+            position.quote_free_native,
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.quote_vault.to_account_info(),
+            &token_quote_account.to_account_info(),
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.market.to_account_info(),
+            signer,
+        )?,
+        None => {
+            // Native SOL goes to the signer, a delegate can't settle it
+            require_keys_eq!(
+                ctx.accounts.owner.key(),
+                account_owner,
+                OpenBookError::NativeSolOwnerOnly
+            );
+            native_transfer_from_vault(
+                position.quote_free_native,
+                &ctx.accounts.token_program.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                &ctx.accounts.quote_vault.to_account_info(),
+                ctx.accounts
+                    .native_unwrap_account
+                    .as_ref()
+                    .map(|account| account.as_ref()),
+                &ctx.accounts.owner.to_account_info(),
+                &ctx.accounts.quote_mint,
+                &ctx.accounts.market.to_account_info(),
+                signer,
+            )?
+        }
     }
 
     // Set to 0 after transfer
@@ -0,0 +1,81 @@
+use std::cmp;
+
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::state::*;
+use crate::token_utils::*;
+
+pub fn settle_funds_permissionless<'info>(
+    ctx: Context<'_, '_, '_, 'info, SettleFundsPermissionless<'info>>,
+) -> Result<()> {
+    let mut open_orders_account = ctx.accounts.open_orders_account.load_full_mut()?;
+    let mut market = ctx.accounts.market.load_mut()?;
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    let is_idle = open_orders_account.fixed.is_idle(now_ts);
+    require!(
+        market.is_expired(now_ts) || is_idle,
+        OpenBookError::OpenOrdersAccountNotIdle
+    );
+
+    let position = &mut open_orders_account.fixed_mut().position;
+
+    // There is no referrer to pay, the rebates go to the market fees
+    market.quote_fees_accrued += position.referrer_rebates_accrued;
+    market.referrer_rebates_accrued -= position.referrer_rebates_accrued;
+    market.base_deposit_total -= position.base_free_native;
+    market.quote_deposit_total -= position.quote_free_native + position.referrer_rebates_accrued;
+
+    // Owners of expired markets didn't neglect their account, only idle ones pay the caller
+    let bounty = if is_idle {
+        cmp::min(
+            SETTLE_FUNDS_BOUNTY_QUOTE_LOTS * market.quote_lot_size as u64,
+            position.quote_free_native,
+        )
+    } else {
+        0
+    };
+
+    let seeds = market_seeds!(market);
+    let signer = &[&seeds[..]];
+
+    drop(market);
+
+    token_transfer_from_vault(
+        position.base_free_native,
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.base_vault.to_account_info(),
+        &ctx.accounts.owner_base_account.to_account_info(),
+        &ctx.accounts.base_mint,
+        &ctx.accounts.market.to_account_info(),
+        signer,
+    )?;
+
+    token_transfer_from_vault(
+        position.quote_free_native - bounty,
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.quote_vault.to_account_info(),
+        &ctx.accounts.owner_quote_account.to_account_info(),
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.market.to_account_info(),
+        signer,
+    )?;
+
+    token_transfer_from_vault(
+        bounty,
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.quote_vault.to_account_info(),
+        &ctx.accounts.caller_quote_account.to_account_info(),
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.market.to_account_info(),
+        signer,
+    )?;
+
+    position.base_free_native = 0;
+    position.quote_free_native = 0;
+    position.referrer_rebates_accrued = 0;
+
+    Ok(())
+}
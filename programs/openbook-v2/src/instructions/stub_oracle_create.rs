@@ -7,7 +7,9 @@ pub fn stub_oracle_create(ctx: Context<StubOracleCreate>, price: I80F48) -> Resu
     let mut oracle = ctx.accounts.oracle.load_init()?;
     oracle.mint = ctx.accounts.mint.key();
     oracle.price = price;
-    oracle.last_updated = Clock::get()?.unix_timestamp;
+    let clock = Clock::get()?;
+    oracle.last_updated = clock.unix_timestamp;
+    oracle.last_update_slot = clock.slot;
 
     Ok(())
 }
@@ -5,7 +5,9 @@ use fixed::types::I80F48;
 pub fn stub_oracle_set(ctx: Context<StubOracleSet>, price: I80F48) -> Result<()> {
     let mut oracle = ctx.accounts.oracle.load_mut()?;
     oracle.price = price;
-    oracle.last_updated = Clock::get()?.unix_timestamp;
+    let clock = Clock::get()?;
+    oracle.last_updated = clock.unix_timestamp;
+    oracle.last_update_slot = clock.slot;
 
     Ok(())
 }
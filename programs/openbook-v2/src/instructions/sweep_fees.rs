@@ -1,30 +1,61 @@
 use crate::state::market_seeds;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Transfer};
 
 use crate::accounts_ix::*;
+use crate::logs::SweepFeesLog;
+use crate::token_utils::*;
 
 pub fn sweep_fees(ctx: Context<SweepFees>) -> Result<()> {
     let mut market = ctx.accounts.market.load_mut()?;
 
     // get/update all values from market and drop reference to it before cpi
-    let amount = market.quote_fees_accrued;
+    let base_amount = market.base_fees_accrued;
+    let quote_amount = market.quote_fees_accrued;
+    market.base_fees_accrued = 0;
     market.quote_fees_accrued = 0;
+    market.base_fees_swept += base_amount;
+    market.quote_fees_swept += quote_amount;
+
+    // Net fees either went to referrers, are still owed to them, or were swept. What is
+    // left over comes from penalty fees, fees bought back and rounding
+    let quote_fees_unreconciled = market.fees_accrued
+        - (market.fees_to_referrers + market.referrer_rebates_accrued + market.quote_fees_swept)
+            as i64;
+
+    emit!(SweepFeesLog {
+        market: ctx.accounts.market.key(),
+        base_amount,
+        quote_amount,
+        base_fees_swept: market.base_fees_swept,
+        quote_fees_swept: market.quote_fees_swept,
+        fees_accrued: market.fees_accrued,
+        fees_to_referrers: market.fees_to_referrers,
+        referrer_rebates_accrued: market.referrer_rebates_accrued,
+        quote_fees_unreconciled,
+    });
 
     let seeds = market_seeds!(market);
     let signer = &[&seeds[..]];
 
     drop(market);
 
-    let cpi_context = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            from: ctx.accounts.quote_vault.to_account_info(),
-            to: ctx.accounts.token_receiver_account.to_account_info(),
-            authority: ctx.accounts.market.to_account_info(),
-        },
-    );
-    token::transfer(cpi_context.with_signer(signer), amount)?;
-
-    Ok(())
+    token_transfer_from_vault(
+        base_amount,
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.base_vault.to_account_info(),
+        &ctx.accounts.token_base_receiver_account.to_account_info(),
+        &ctx.accounts.base_mint,
+        &ctx.accounts.market.to_account_info(),
+        signer,
+    )?;
+
+    token_transfer_from_vault(
+        quote_amount,
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.quote_vault.to_account_info(),
+        &ctx.accounts.token_receiver_account.to_account_info(),
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.market.to_account_info(),
+        signer,
+    )
 }
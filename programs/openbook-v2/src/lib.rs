@@ -16,11 +16,15 @@ pub mod i80f48;
 pub mod logs;
 pub mod pod_option;
 pub mod state;
+pub mod token_utils;
 pub mod types;
 
 use error::*;
 use fixed::types::I80F48;
-use state::{MarketIndex, OracleConfigParams, PlaceOrderType, SelfTradeBehavior, Side};
+use state::{
+    BuybackParams, FeeTierParams, MarketIndex, OracleConfigParams, PlaceOrderArgs, PlaceOrderType,
+    SelfTradeBehavior, Side, TriggerCondition,
+};
 
 #[cfg(feature = "enable-gpl")]
 pub mod instructions;
@@ -33,6 +37,12 @@ pub mod openbook_v2 {
     use super::*;
 
     /// Create a [`Market`](crate::state::Market) for a given token pair.
+    ///
+    /// The oracle account is optional, markets without one don't support
+    /// oracle pegged orders.
+    ///
+    /// After `time_expiry`, a unix timestamp, new orders are rejected and
+    /// anyone can prune resting orders. Pass 0 for a market that never expires.
     #[allow(clippy::too_many_arguments)]
     pub fn create_market(
         ctx: Context<CreateMarket>,
@@ -48,6 +58,8 @@ pub mod openbook_v2 {
         open_orders_admin: Option<Pubkey>,
         consume_events_admin: Option<Pubkey>,
         close_market_admin: Option<Pubkey>,
+        market_admin: Option<Pubkey>,
+        time_expiry: i64,
     ) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::create_market(
@@ -64,6 +76,87 @@ pub mod openbook_v2 {
             open_orders_admin,
             consume_events_admin,
             close_market_admin,
+            market_admin,
+            time_expiry,
+        )?;
+        Ok(())
+    }
+
+    /// Edit the fees, oracle configuration, price bands and admins of a
+    /// [`Market`](crate::state::Market). Only callable by its `market_admin`.
+    ///
+    /// Parameters passed as `None` are left unchanged. Passing the default
+    /// pubkey for an optional admin removes it, `collect_fee_admin` can't
+    /// be removed.
+    ///
+    /// The price bands limit how far, in bps, from both the oracle and the
+    /// stable price takers can be filled and orders can be posted. Taker
+    /// orders stop matching at the band, posting outside of it fails. A
+    /// value of 0 disables the band, values above 10_000 are invalid.
+    ///
+    /// Fees apply to orders resting on the book when they are filled. Be careful
+    /// when changing them while there are open orders: the fees reserved for
+    /// bids at placement time are based on the previous fee rates. A positive
+    /// `maker_fee`, before or after the change, can only change while the book
+    /// and the event queue are empty.
+    ///
+    /// `disabled_instructions` is a bitmask of
+    /// [`MarketInstruction`](crate::state::MarketInstruction)s to freeze during
+    /// incidents. Disabling all of them halts the market. Cancelling orders,
+    /// consuming events and settling funds always stay available.
+    ///
+    /// `time_expiry` can schedule the expiry of the market, see `create_market`.
+    ///
+    /// `fee_tiers` replaces the volume based fee tiers, by increasing volume threshold.
+    /// Accounts pay the fees of the highest tier their maker plus taker volume reached.
+    /// Markets with fee tiers can't charge makers, and no tier may rebate makers more
+    /// than any tier charges takers.
+    ///
+    /// `fee_tier_volume_interval` sets the length in seconds of the volume intervals.
+    /// Only the volume of the current and the previous interval counts towards the
+    /// fee tier, 0 counts the lifetime volume.
+    ///
+    /// `buyback` configures the fees buyback, see `buyback_fees`. The default
+    /// mint disables it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn edit_market(
+        ctx: Context<EditMarket>,
+        maker_fee_opt: Option<f32>,
+        taker_fee_opt: Option<f32>,
+        fee_penalty_opt: Option<u64>,
+        oracle_config_opt: Option<OracleConfigParams>,
+        taker_price_band_bps_opt: Option<u16>,
+        maker_price_band_bps_opt: Option<u16>,
+        collect_fee_admin_opt: Option<Pubkey>,
+        open_orders_admin_opt: Option<Pubkey>,
+        consume_events_admin_opt: Option<Pubkey>,
+        close_market_admin_opt: Option<Pubkey>,
+        market_admin_opt: Option<Pubkey>,
+        disabled_instructions_opt: Option<u8>,
+        time_expiry_opt: Option<i64>,
+        fee_tiers_opt: Option<Vec<FeeTierParams>>,
+        fee_tier_volume_interval_opt: Option<u64>,
+        buyback_opt: Option<BuybackParams>,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::edit_market(
+            ctx,
+            maker_fee_opt,
+            taker_fee_opt,
+            fee_penalty_opt,
+            oracle_config_opt,
+            taker_price_band_bps_opt,
+            maker_price_band_bps_opt,
+            collect_fee_admin_opt,
+            open_orders_admin_opt,
+            consume_events_admin_opt,
+            close_market_admin_opt,
+            market_admin_opt,
+            disabled_instructions_opt,
+            time_expiry_opt,
+            fee_tiers_opt,
+            fee_tier_volume_interval_opt,
+            buyback_opt,
         )?;
         Ok(())
     }
@@ -78,6 +171,48 @@ pub mod openbook_v2 {
         Ok(())
     }
 
+    /// Store the market in a version 1 [`OpenOrdersAccount`](crate::state::OpenOrdersAccount),
+    /// which older versions of the program left empty.
+    ///
+    /// Permissionless, the market is checked against the seeds of the account address.
+    /// Instructions that check the market of the account reject it until it is migrated.
+    pub fn migrate_open_orders_account(ctx: Context<MigrateOpenOrdersAccount>) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::migrate_open_orders_account(ctx)?;
+        Ok(())
+    }
+
+    /// Resize an [`OpenOrdersAccount`](crate::state::OpenOrdersAccount) to hold
+    /// `open_orders_count` open orders, up to 64.
+    ///
+    /// Growing the account charges `payer` the extra rent. Shrinking it requires the
+    /// removed trailing slots to be free and refunds the rent no longer needed to `payer`.
+    pub fn expand_open_orders(ctx: Context<ExpandOpenOrders>, open_orders_count: u8) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::expand_open_orders(ctx, open_orders_count)?;
+        Ok(())
+    }
+
+    /// Close an [`OpenOrdersAccount`](crate::state::OpenOrdersAccount) and send its
+    /// rent to `sol_destination`. The account must not have any open orders,
+    /// active trigger orders or unsettled funds.
+    pub fn close_open_orders_account(ctx: Context<CloseOpenOrdersAccount>) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::close_open_orders_account(ctx)?;
+        Ok(())
+    }
+
+    /// Set the delegate of an [`OpenOrdersAccount`](crate::state::OpenOrdersAccount).
+    ///
+    /// A delegate can place and cancel orders and settle funds on behalf of
+    /// the owner, funds are always settled to the owner's token accounts.
+    /// Not passing a `delegate_account` revokes the current delegate.
+    pub fn set_delegate(ctx: Context<SetDelegate>) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::set_delegate(ctx)?;
+        Ok(())
+    }
+
     /// Place an order.
     ///
     /// Different types of orders have different effects on the order book,
@@ -107,31 +242,66 @@ pub mod openbook_v2 {
         expiry_timestamp: u64,
         limit: u8,
     ) -> Result<Option<u128>> {
-        require_gte!(price_lots, 0);
-
-        use crate::state::{Order, OrderParams};
-        let time_in_force = match Order::tif_from_expiry(expiry_timestamp) {
-            Some(t) => t,
+        let args = PlaceOrderArgs {
+            side,
+            price_lots,
+            max_base_lots,
+            max_quote_lots_including_fees,
+            client_order_id,
+            order_type,
+            self_trade_behavior,
+            expiry_timestamp,
+        };
+        let order = match args.to_order()? {
+            Some(order) => order,
             None => {
                 msg!("Order is already expired");
                 return Ok(None);
             }
         };
-        let order = Order {
+        #[cfg(feature = "enable-gpl")]
+        return instructions::place_order(ctx, order, limit);
+
+        #[cfg(not(feature = "enable-gpl"))]
+        Ok(None)
+    }
+
+    /// Place an iceberg order that shows at most `display_base_lots` on the book.
+    ///
+    /// The rest of the posted quantity is kept in a hidden reserve. Whenever the
+    /// visible part is filled, the order is replenished from the reserve and gets
+    /// a new order id with a new time priority. `order_type` must be `Limit` or
+    /// `PostOnly`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn place_iceberg_order(
+        ctx: Context<PlaceOrder>,
+        side: Side,
+        price_lots: i64,
+        max_base_lots: i64,
+        display_base_lots: i64,
+        max_quote_lots_including_fees: i64,
+        client_order_id: u64,
+        order_type: PlaceOrderType,
+        self_trade_behavior: SelfTradeBehavior,
+        expiry_timestamp: u64,
+        limit: u8,
+    ) -> Result<Option<u128>> {
+        let args = PlaceOrderArgs {
             side,
+            price_lots,
             max_base_lots,
             max_quote_lots_including_fees,
             client_order_id,
-            time_in_force,
+            order_type,
             self_trade_behavior,
-            params: match order_type {
-                PlaceOrderType::Market => OrderParams::Market,
-                PlaceOrderType::ImmediateOrCancel => OrderParams::ImmediateOrCancel { price_lots },
-                _ => OrderParams::Fixed {
-                    price_lots,
-                    order_type: order_type.to_post_order_type()?,
-                },
-            },
+            expiry_timestamp,
+        };
+        let order = match args.to_iceberg_order(display_base_lots)? {
+            Some(order) => order,
+            None => {
+                msg!("Order is already expired");
+                return Ok(None);
+            }
         };
         #[cfg(feature = "enable-gpl")]
         return instructions::place_order(ctx, order, limit);
@@ -140,6 +310,87 @@ pub mod openbook_v2 {
         Ok(None)
     }
 
+    /// Replace an open order, identified by either `cancel_order_id` or
+    /// `cancel_client_order_id`, with a new order.
+    ///
+    /// If the new order is a `Limit` or `PostOnly` order on the same side, at
+    /// the same price and with the same order type, self trade behavior and
+    /// expiry as the existing fixed price order and only shrinks it, the
+    /// existing order is reduced in place and keeps its priority. Otherwise
+    /// the existing order is cancelled and the new one is placed as in
+    /// `place_order`, which returns the new order id.
+    #[allow(clippy::too_many_arguments)]
+    pub fn edit_order(
+        ctx: Context<PlaceOrder>,
+        cancel_order_id: Option<u128>,
+        cancel_client_order_id: Option<u64>,
+        side: Side,
+        price_lots: i64,
+        max_base_lots: i64,
+        max_quote_lots_including_fees: i64,
+        client_order_id: u64,
+        order_type: PlaceOrderType,
+        self_trade_behavior: SelfTradeBehavior,
+        expiry_timestamp: u64,
+        limit: u8,
+    ) -> Result<Option<u128>> {
+        let args = PlaceOrderArgs {
+            side,
+            price_lots,
+            max_base_lots,
+            max_quote_lots_including_fees,
+            client_order_id,
+            order_type,
+            self_trade_behavior,
+            expiry_timestamp,
+        };
+        let order = match args.to_order()? {
+            Some(order) => order,
+            None => {
+                msg!("Order is already expired");
+                return Ok(None);
+            }
+        };
+        #[cfg(feature = "enable-gpl")]
+        return instructions::edit_order(
+            ctx,
+            cancel_order_id,
+            cancel_client_order_id,
+            order,
+            limit,
+        );
+
+        #[cfg(not(feature = "enable-gpl"))]
+        Ok(None)
+    }
+
+    /// Place multiple orders in one instruction.
+    ///
+    /// Each order is matched and posted as in `place_order`, in the given
+    /// sequence. Funds for all orders are deposited with at most one transfer
+    /// per token at the end. Returns the order id for each order, `None` for
+    /// orders that were already expired or did not rest on the book.
+    ///
+    /// `limit` applies to each order separately.
+    pub fn place_orders(
+        ctx: Context<PlaceOrders>,
+        orders: Vec<PlaceOrderArgs>,
+        limit: u8,
+    ) -> Result<Vec<Option<u128>>> {
+        let orders = orders
+            .iter()
+            .map(|args| args.to_order())
+            .collect::<Result<Vec<_>>>()?;
+
+        #[cfg(feature = "enable-gpl")]
+        return instructions::place_orders(ctx, orders, limit);
+
+        #[cfg(not(feature = "enable-gpl"))]
+        Ok(vec![])
+    }
+
+    /// Place an order whose price follows the oracle. Not supported on
+    /// markets without an oracle.
     #[allow(clippy::too_many_arguments)]
     pub fn place_order_pegged(
         ctx: Context<PlaceOrder>,
@@ -175,11 +426,12 @@ pub mod openbook_v2 {
 
         // Oracle staleness limit, in slots. Set to -1 to disable.
         //
-        // WARNING: Not currently implemented.
+        // The order can't be placed if the oracle is staler than this, and it's
+        // skipped during matching as long as that's the case.
         max_oracle_staleness_slots: i32,
     ) -> Result<Option<u128>> {
         require_gt!(peg_limit, 0);
-        require_eq!(max_oracle_staleness_slots, -1); // unimplemented
+        require_gte!(max_oracle_staleness_slots, -1);
 
         use crate::state::{Order, OrderParams};
         let time_in_force = match Order::tif_from_expiry(expiry_timestamp) {
@@ -227,27 +479,24 @@ pub mod openbook_v2 {
         self_trade_behavior: SelfTradeBehavior,
         limit: u8,
     ) -> Result<Option<u128>> {
-        require_gte!(price_lots, 0);
-
-        use crate::state::{Order, OrderParams};
         require!(
-            order_type == PlaceOrderType::Market || order_type == PlaceOrderType::ImmediateOrCancel,
+            order_type == PlaceOrderType::Market
+                || order_type == PlaceOrderType::ImmediateOrCancel
+                || order_type == PlaceOrderType::FillOrKill,
             OpenBookError::InvalidOrderType
         );
-        let order = Order {
+        let args = PlaceOrderArgs {
             side,
+            price_lots,
             max_base_lots,
             max_quote_lots_including_fees,
             client_order_id,
-            time_in_force: 0,
+            order_type,
             self_trade_behavior,
-            params: match order_type {
-                PlaceOrderType::Market => OrderParams::Market,
-                PlaceOrderType::ImmediateOrCancel => OrderParams::ImmediateOrCancel { price_lots },
-                // never goes here, order type already checked
-                _ => panic!(),
-            },
+            expiry_timestamp: 0,
         };
+        // Orders without expiry are never skipped
+        let order = args.to_order()?.unwrap();
         #[cfg(feature = "enable-gpl")]
         return instructions::place_take_order(ctx, order, limit);
 
@@ -307,6 +556,23 @@ pub mod openbook_v2 {
         Ok(())
     }
 
+    /// Cancel several orders by their `order_id` or `client_order_id`, up to
+    /// 24 in total.
+    ///
+    /// Orders that were already filled or expired are skipped. Returns the
+    /// number of orders that were cancelled.
+    pub fn cancel_orders(
+        ctx: Context<CancelOrder>,
+        order_ids: Vec<u128>,
+        client_order_ids: Vec<u64>,
+    ) -> Result<u8> {
+        #[cfg(feature = "enable-gpl")]
+        return instructions::cancel_orders(ctx, order_ids, client_order_ids);
+
+        #[cfg(not(feature = "enable-gpl"))]
+        Ok(0)
+    }
+
     /// Cancel up to `limit` orders.
     pub fn cancel_all_orders(ctx: Context<CancelAllOrders>, limit: u8) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
@@ -325,6 +591,78 @@ pub mod openbook_v2 {
         Ok(())
     }
 
+    /// Cancel up to `limit` orders of any open orders account on an expired
+    /// market, crediting their funds back to the account. Permissionless, so
+    /// the books can be emptied before the market is closed.
+    pub fn prune_orders(ctx: Context<PruneOrders>, limit: u8) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::prune_orders(ctx, limit)?;
+        Ok(())
+    }
+
+    /// Create the account holding the trigger orders of an
+    /// [`OpenOrdersAccount`](crate::state::OpenOrdersAccount).
+    pub fn create_trigger_orders_account(ctx: Context<CreateTriggerOrdersAccount>) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::create_trigger_orders_account(ctx)?;
+        Ok(())
+    }
+
+    /// Place an order that rests off the book until `condition` is met for
+    /// `trigger_price_lots`, e.g. a stop-loss or take-profit order.
+    ///
+    /// The funds the order can use at most are deposited right away, along
+    /// with a bounty in lamports for whoever executes the order. `limit` is
+    /// the maximum number of orders from the book to fill once triggered.
+    /// Returns the slot of the trigger order.
+    pub fn place_trigger_order(
+        ctx: Context<PlaceTriggerOrder>,
+        condition: TriggerCondition,
+        trigger_price_lots: i64,
+        order: PlaceOrderArgs,
+        limit: u8,
+    ) -> Result<u8> {
+        #[cfg(feature = "enable-gpl")]
+        return instructions::place_trigger_order(ctx, condition, trigger_price_lots, order, limit);
+
+        #[cfg(not(feature = "enable-gpl"))]
+        Ok(0)
+    }
+
+    /// Cancel a trigger order. Its deposited funds become free funds of the
+    /// position and the bounty is returned to the owner.
+    pub fn cancel_trigger_order(ctx: Context<CancelTriggerOrder>, slot: u8) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::cancel_trigger_order(ctx, slot)?;
+        Ok(())
+    }
+
+    /// Close a [`TriggerOrdersAccount`](crate::state::TriggerOrdersAccount)
+    /// without active trigger orders and send its lamports to `sol_destination`.
+    pub fn close_trigger_orders_account(ctx: Context<CloseTriggerOrdersAccount>) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::close_trigger_orders_account(ctx)?;
+        Ok(())
+    }
+
+    /// Place a trigger order whose condition is met, paying its bounty to the
+    /// keeper. Can be called by anyone. The order has to match or rest on the
+    /// book for the bounty to be paid.
+    ///
+    /// Expired trigger orders, and all trigger orders once the market expired,
+    /// are removed without placing them. Unused funds become free funds of the
+    /// position.
+    pub fn execute_trigger_order(
+        ctx: Context<ExecuteTriggerOrder>,
+        slot: u8,
+    ) -> Result<Option<u128>> {
+        #[cfg(feature = "enable-gpl")]
+        return instructions::execute_trigger_order(ctx, slot);
+
+        #[cfg(not(feature = "enable-gpl"))]
+        Ok(None)
+    }
+
     /// Desposit a certain amount of `base_amount_lots` and `quote_amount_lots`
     /// into one's [`Position`](crate::state::Position).
     ///
@@ -341,20 +679,74 @@ pub mod openbook_v2 {
     }
 
     /// Withdraw any available tokens.
+    ///
+    /// Can be signed by the owner or the delegate of the open orders account,
+    /// tokens are only sent to token accounts owned by the owner.
     pub fn settle_funds<'info>(ctx: Context<'_, '_, '_, 'info, SettleFunds<'info>>) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::settle_funds(ctx)?;
         Ok(())
     }
 
-    /// Sweep fees, as a [`Market`](crate::state::Market)'s admin.
+    /// Withdraw the free funds of an open orders account to the owner's associated
+    /// token accounts, once the market expired or the account is idle.
+    ///
+    /// Anyone can call this. Settling an idle account pays the caller a fixed bounty
+    /// out of the settled quote funds, settling after the market expired doesn't.
+    pub fn settle_funds_permissionless<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleFundsPermissionless<'info>>,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::settle_funds_permissionless(ctx)?;
+        Ok(())
+    }
+
+    /// Register a referrer under `referrer_id`, paying its rebates to `token_account`.
+    ///
+    /// `settle_funds` and `place_take_order` take the [`Referrer`](crate::state::Referrer)
+    /// together with its token account, rebates can't go to any other account.
+    pub fn create_referrer(ctx: Context<CreateReferrer>, referrer_id: u64) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::create_referrer(ctx, referrer_id)?;
+        Ok(())
+    }
+
+    /// Change the token account a [`Referrer`](crate::state::Referrer) is paid to, as its authority.
+    pub fn edit_referrer(ctx: Context<EditReferrer>) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::edit_referrer(ctx)?;
+        Ok(())
+    }
+
+    /// Redeem quote fees paid by the owner's account against the market's buyback
+    /// token, at the buyback oracle price.
+    ///
+    /// Fees paid accrue per expiry interval and can be bought back until the end of
+    /// the following interval. At most `max_buyback_fees` native quote is redeemed,
+    /// limited by the market's accrued fees.
+    pub fn buyback_fees(ctx: Context<BuybackFees>, max_buyback_fees: u64) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::buyback_fees(ctx, max_buyback_fees)?;
+        Ok(())
+    }
+
+    /// Sweep the base and quote fees, as a [`Market`](crate::state::Market)'s admin.
+    ///
+    /// Both receivers must be owned by the collect fee admin. Emits a `SweepFeesLog`
+    /// reconciling the market's fee counters.
     pub fn sweep_fees(ctx: Context<SweepFees>) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::sweep_fees(ctx)?;
         Ok(())
     }
 
-    /// Close a [`Market`](crate::state::Market).
+    /// Close a [`Market`](crate::state::Market), its books, event queue and
+    /// vaults, sending their rent to `sol_destination`.
+    ///
+    /// The books and the event queue must be empty, all user funds settled
+    /// and all fees swept. Tokens sent straight to the vaults go to the token
+    /// accounts of the `sol_destination` owner, and transfer fees withheld in
+    /// Token-2022 vaults are harvested to the mints before the vaults are closed.
     pub fn close_market(ctx: Context<CloseMarket>) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::close_market(ctx)?;
@@ -27,6 +27,8 @@ pub struct FillLog {
     pub maker: Pubkey,
     pub maker_client_order_id: u64,
     pub maker_fee: f32,
+    /// Fee tier of the maker, 0 for the market fees
+    pub maker_fee_tier: u8,
 
     // Timestamp of when the maker order was placed; copied over from the LeafNode
     pub maker_timestamp: u64,
@@ -34,6 +36,8 @@ pub struct FillLog {
     pub taker: Pubkey,
     pub taker_client_order_id: u64,
     pub taker_fee: f32,
+    /// Fee tier of the taker, 0 for the market fees
+    pub taker_fee_tier: u8,
 
     pub price: i64,
     pub quantity: i64, // number of base lots
@@ -47,7 +51,7 @@ pub struct MarketMetaDataLog {
     pub quote_decimals: u8,
     pub base_lot_size: i64,
     pub quote_lot_size: i64,
-    pub oracle: Pubkey,
+    pub oracle: Option<Pubkey>,
 }
 
 #[event]
@@ -57,4 +61,54 @@ pub struct TotalOrderFillEvent {
     pub total_quantity_paid: u64,
     pub total_quantity_received: u64,
     pub fees: u64,
+    /// Referrer the taker rebates of the order go to, if any
+    pub referrer: Option<Pubkey>,
+}
+
+#[event]
+pub struct SetDelegateLog {
+    pub open_orders_account: Pubkey,
+    pub delegate: Option<Pubkey>,
+}
+
+#[event]
+pub struct StablePriceLog {
+    pub market: Pubkey,
+    /// Native quote per native base
+    pub oracle_price: f64,
+    /// Native quote per native base
+    pub stable_price: f64,
+}
+
+#[event]
+pub struct BuybackFeesLog {
+    pub market: Pubkey,
+    pub open_orders_account: Pubkey,
+    /// Native quote fees paid out
+    pub quote_amount: u64,
+    /// Native buyback tokens paid for them
+    pub buyback_amount: u64,
+    /// Native quote per native buyback token
+    pub price: f64,
+}
+
+#[event]
+pub struct SweepFeesLog {
+    pub market: Pubkey,
+    /// Native base fees swept by this instruction
+    pub base_amount: u64,
+    /// Native quote fees swept by this instruction
+    pub quote_amount: u64,
+    /// Totals swept over the market's lifetime
+    pub base_fees_swept: u64,
+    pub quote_fees_swept: u64,
+    /// Net quote fees of all fills, see `Market::fees_accrued`
+    pub fees_accrued: i64,
+    /// Referrer rebates paid out
+    pub fees_to_referrers: u64,
+    /// Referrer rebates not settled yet
+    pub referrer_rebates_accrued: u64,
+    /// `fees_accrued` not covered by referrer rebates and swept fees. Penalty fees make it
+    /// negative, fees bought back and rounding positive
+    pub quote_fees_unreconciled: i64,
 }
@@ -3,6 +3,8 @@ use fixed::types::I80F48;
 use static_assertions::const_assert_eq;
 use std::mem::size_of;
 
+use crate::error::OpenBookError;
+use crate::logs::StablePriceLog;
 use crate::pod_option::PodOption;
 use crate::state::oracle;
 use crate::{accounts_zerocopy::KeyedAccountReader, state::orderbook::Side};
@@ -11,6 +13,65 @@ use super::{orderbook, OracleConfig, StablePriceModel};
 
 pub type MarketIndex = u32;
 
+pub const MAX_FEE_TIERS: usize = 4;
+
+/// Configuration of the fees buyback, see `Market::buyback_mint`
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug)]
+pub struct BuybackParams {
+    pub mint: Pubkey,
+    pub oracle: Pubkey,
+    pub fees_expiry_interval: u64,
+}
+
+/// Fees for open orders accounts whose maker plus taker volume, in quote native,
+/// reached `volume_threshold`
+#[zero_copy]
+#[derive(AnchorDeserialize, AnchorSerialize, Debug)]
+pub struct FeeTier {
+    pub volume_threshold: u64,
+    pub maker_fee: I80F48,
+    pub taker_fee: I80F48,
+}
+const_assert_eq!(size_of::<FeeTier>(), 8 + 2 * 16);
+const_assert_eq!(size_of::<FeeTier>() % 8, 0);
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug)]
+pub struct FeeTierParams {
+    pub volume_threshold: u64,
+    pub maker_fee: f32,
+    pub taker_fee: f32,
+}
+
+impl FeeTierParams {
+    pub fn to_fee_tier(&self) -> FeeTier {
+        FeeTier {
+            volume_threshold: self.volume_threshold,
+            maker_fee: I80F48::from_num(self.maker_fee),
+            taker_fee: I80F48::from_num(self.taker_fee),
+        }
+    }
+}
+
+/// Instructions that the market admin can disable individually, see
+/// `Market::disabled_instructions`. Cancelling, consuming events and settling
+/// funds are always available.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MarketInstruction {
+    PlaceOrder = 0,
+    PlaceOrderPegged = 1,
+    PlaceTakeOrder = 2,
+    Deposit = 3,
+}
+
+impl MarketInstruction {
+    pub const ALL: u8 = 0b1111;
+
+    pub fn mask(self) -> u8 {
+        1 << self as u8
+    }
+}
+
 #[account(zero_copy)]
 #[derive(Debug)]
 pub struct Market {
@@ -46,7 +107,8 @@ pub struct Market {
     /// Address of the EventQueue account
     pub event_queue: Pubkey,
 
-    /// Oracle account address
+    /// Oracle account address, the default pubkey for markets without one. These
+    /// don't support oracle pegged orders.
     pub oracle: Pubkey,
     /// Oracle configuration
     pub oracle_config: OracleConfig,
@@ -104,7 +166,50 @@ pub struct Market {
     pub quote_fees_accrued: u64,
     pub referrer_rebates_accrued: u64,
 
-    pub reserved: [u8; 1768],
+    /// Admin who can edit the fees, oracle configuration and admins of the market
+    pub market_admin: PodOption<Pubkey>,
+
+    /// Max deviation, in bps, of taker fills from the oracle and stable prices. 0 disables it.
+    pub taker_price_band_bps: u16,
+    /// Max deviation, in bps, of posted orders from the oracle and stable prices. 0 disables it.
+    pub maker_price_band_bps: u16,
+
+    /// Bitmask of the `MarketInstruction`s disabled by the market admin
+    pub disabled_instructions: u8,
+    pub padding2: [u8; 3],
+    /// Price of the last trade on the market, 0 before the first trade
+    pub last_trade_price_lots: i64,
+
+    /// Unix timestamp after which new orders are rejected and resting orders can be
+    /// pruned by anyone, 0 if the market never expires
+    pub time_expiry: i64,
+
+    /// Fees replacing `maker_fee` and `taker_fee` for high volume accounts, by increasing
+    /// volume threshold. Tier 0 stands for the market fees, tier n for `fee_tiers[n - 1]`.
+    pub fee_tiers: [FeeTier; MAX_FEE_TIERS],
+    pub fee_tiers_count: u8,
+    pub padding3: [u8; 7],
+
+    /// Token that users pay, at the oracle price, to get back the fees they paid.
+    /// The default pubkey if the fees buyback is disabled.
+    pub buyback_mint: Pubkey,
+    /// Oracle for the price of the buyback token in quote
+    pub buyback_oracle: Pubkey,
+    /// Length in seconds of the intervals fees accrue in for the buyback. Fees can be
+    /// bought back during their interval and the next one.
+    pub buyback_fees_expiry_interval: u64,
+
+    /// Total base fees swept to the collect fee admin, in native base
+    pub base_fees_swept: u64,
+    /// Total quote fees swept to the collect fee admin, in native quote
+    pub quote_fees_swept: u64,
+
+    /// Length in seconds of the intervals volume is counted in for the fee tiers. The
+    /// volume of the current and the previous interval decides the tier, with 0 the
+    /// lifetime volume does.
+    pub fee_tier_volume_interval: u64,
+
+    pub reserved: [u8; 1440],
 }
 
 const_assert_eq!(
@@ -138,7 +243,21 @@ const_assert_eq!(
     8 + // size of quote_deposit_total
     8 + // size of quote_fees_accrued
     8 + // size of referrer_rebates_accrued
-    1768 // size of reserved
+    40 + // size of market_admin
+    2 * 2 + // size of taker_price_band_bps and maker_price_band_bps
+    1 + // size of disabled_instructions
+    3 + // size of padding2
+    8 + // size of last_trade_price_lots
+    8 + // size of time_expiry
+    MAX_FEE_TIERS * size_of::<FeeTier>() + // size of fee_tiers
+    1 + // size of fee_tiers_count
+    7 + // size of padding3
+    2 * 32 + // size of buyback_mint and buyback_oracle
+    8 + // size of buyback_fees_expiry_interval
+    8 + // size of base_fees_swept
+    8 + // size of quote_fees_swept
+    8 + // size of fee_tier_volume_interval
+    1440 // size of reserved
 );
 const_assert_eq!(size_of::<Market>(), 2720);
 const_assert_eq!(size_of::<Market>() % 8, 0);
@@ -150,6 +269,28 @@ impl Market {
             .trim_matches(char::from(0))
     }
 
+    /// All instructions that can be disabled are, only exiting the market is possible
+    pub fn is_halted(&self) -> bool {
+        self.disabled_instructions & MarketInstruction::ALL == MarketInstruction::ALL
+    }
+
+    pub fn check_instruction_enabled(&self, instruction: MarketInstruction) -> Result<()> {
+        require!(!self.is_halted(), OpenBookError::GroupIsHalted);
+        require!(
+            self.disabled_instructions & instruction.mask() == 0,
+            OpenBookError::IxIsDisabled
+        );
+        Ok(())
+    }
+
+    pub fn is_buyback_enabled(&self) -> bool {
+        self.buyback_mint != Pubkey::default()
+    }
+
+    pub fn is_expired(&self, now_ts: u64) -> bool {
+        self.time_expiry != 0 && self.time_expiry <= now_ts as i64
+    }
+
     pub fn gen_order_id(&mut self, side: Side, price_data: u64) -> u128 {
         self.seq_num += 1;
         orderbook::new_node_key(side, price_data, self.seq_num)
@@ -166,19 +307,185 @@ impl Market {
             .to_num()
     }
 
+    /// Worst price in lots that an order on `side` may execute or be posted at, given a band
+    /// of `band_bps` around both the oracle and the stable price. None if the band is disabled
+    /// or the market has no oracle.
+    pub fn price_band_limit_lots(
+        &self,
+        side: Side,
+        band_bps: u16,
+        oracle_price: Option<I80F48>,
+    ) -> Option<i64> {
+        let oracle_price = oracle_price?;
+        if band_bps == 0 {
+            return None;
+        }
+        let stable_price = I80F48::from_num(self.stable_price_model.stable_price);
+        let band = I80F48::from_num(band_bps) / I80F48::from_num(10_000);
+        Some(match side {
+            Side::Bid => {
+                let reference = if stable_price.is_positive() {
+                    oracle_price.min(stable_price)
+                } else {
+                    oracle_price
+                };
+                self.native_price_to_lot(reference * (I80F48::ONE + band))
+            }
+            Side::Ask => {
+                let reference = oracle_price.max(stable_price);
+                let min_price = (reference * (I80F48::ONE - band)).max(I80F48::ZERO);
+                (min_price * I80F48::from_num(self.base_lot_size)
+                    / I80F48::from_num(self.quote_lot_size))
+                .ceil()
+                .to_num::<i64>()
+                .max(1)
+            }
+        })
+    }
+
+    /// Taker fees should be positive and if maker fees are negative, greater or equal to their abs value
+    pub fn validate_fees(maker_fee: I80F48, taker_fee: I80F48) -> Result<()> {
+        require!(
+            !taker_fee.is_negative() && (!maker_fee.is_negative() || maker_fee.abs() <= taker_fee),
+            OpenBookError::InvalidFeesError
+        );
+        Ok(())
+    }
+
+    /// Fee tiers need increasing volume thresholds and valid fees. Maker rebates are
+    /// reserved out of taker fees before the maker is known, so no maker may get a larger
+    /// rebate than any taker pays. Makers can't pay fees on markets with fee tiers, these
+    /// would be reserved at placement with a tier that may change until the fill.
+    pub fn validate_fee_tiers(&self) -> Result<()> {
+        let fee_tiers = self.active_fee_tiers();
+        if fee_tiers.is_empty() {
+            return Ok(());
+        }
+
+        require!(
+            fee_tiers
+                .windows(2)
+                .all(|tiers| tiers[0].volume_threshold < tiers[1].volume_threshold),
+            OpenBookError::InvalidFeeTiers
+        );
+        require!(
+            fee_tiers.iter().all(|tier| tier.volume_threshold > 0),
+            OpenBookError::InvalidFeeTiers
+        );
+        require!(
+            !self.maker_fee.is_positive()
+                && fee_tiers.iter().all(|tier| !tier.maker_fee.is_positive()),
+            OpenBookError::InvalidFeeTiers
+        );
+        for tier in fee_tiers {
+            Market::validate_fees(tier.maker_fee, tier.taker_fee)?;
+        }
+
+        let min_taker_fee = fee_tiers
+            .iter()
+            .map(|tier| tier.taker_fee)
+            .fold(self.taker_fee, I80F48::min);
+        require!(
+            self.max_maker_rebate() <= min_taker_fee,
+            OpenBookError::InvalidFeeTiers
+        );
+        Ok(())
+    }
+
+    pub fn active_fee_tiers(&self) -> &[FeeTier] {
+        &self.fee_tiers[..self.fee_tiers_count as usize]
+    }
+
+    /// Fee tier of an account with `volume` in quote native, 0 for the market fees
+    pub fn fee_tier(&self, volume: u64) -> u8 {
+        self.active_fee_tiers()
+            .iter()
+            .rposition(|tier| volume >= tier.volume_threshold)
+            .map_or(0, |index| index as u8 + 1)
+    }
+
+    pub fn maker_fee_for_tier(&self, fee_tier: u8) -> I80F48 {
+        match fee_tier {
+            0 => self.maker_fee,
+            tier => self.fee_tiers[tier as usize - 1].maker_fee,
+        }
+    }
+
+    pub fn taker_fee_for_tier(&self, fee_tier: u8) -> I80F48 {
+        match fee_tier {
+            0 => self.taker_fee,
+            tier => self.fee_tiers[tier as usize - 1].taker_fee,
+        }
+    }
+
+    /// Largest maker rebate of the market fees and fee tiers, 0 if makers pay fees
+    pub fn max_maker_rebate(&self) -> I80F48 {
+        let min_maker_fee = self
+            .active_fee_tiers()
+            .iter()
+            .map(|tier| tier.maker_fee)
+            .fold(self.maker_fee, I80F48::min);
+        (-min_maker_fee).max(I80F48::ZERO)
+    }
+
+    /// Largest fee rate of the market fees and fee tiers, for takers or makers
+    pub fn max_fee(&self) -> I80F48 {
+        self.active_fee_tiers()
+            .iter()
+            .map(|tier| tier.taker_fee.max(tier.maker_fee))
+            .fold(self.taker_fee.max(self.maker_fee), I80F48::max)
+    }
+
+    /// The oracle price, None if the market has no oracle
     pub fn oracle_price(
         &self,
-        oracle_acc: &impl KeyedAccountReader,
+        oracle_acc: Option<&impl KeyedAccountReader>,
+        staleness_slot: u64,
+    ) -> Result<Option<I80F48>> {
+        Ok(self
+            .oracle_state(oracle_acc, staleness_slot)?
+            .map(|state| state.price))
+    }
+
+    /// Feed the oracle price into the stable price model, logging the new stable price
+    pub fn update_stable_price(&mut self, market: Pubkey, oracle_price: I80F48, now_ts: u64) {
+        let last_update_timestamp = self.stable_price_model.last_update_timestamp;
+        self.stable_price_model
+            .update(now_ts, oracle_price.to_num::<f64>());
+        if self.stable_price_model.last_update_timestamp != last_update_timestamp {
+            emit!(StablePriceLog {
+                market,
+                oracle_price: oracle_price.to_num::<f64>(),
+                stable_price: self.stable_price_model.stable_price,
+            });
+        }
+    }
+
+    /// The oracle address, None if the market has no oracle
+    pub fn oracle(&self) -> Option<Pubkey> {
+        (self.oracle != Pubkey::default()).then_some(self.oracle)
+    }
+
+    /// The oracle state, None if the market has no oracle
+    pub fn oracle_state(
+        &self,
+        oracle_acc: Option<&impl KeyedAccountReader>,
         staleness_slot: u64,
-    ) -> Result<I80F48> {
-        require_keys_eq!(self.oracle, *oracle_acc.key());
-        oracle::oracle_price(
+    ) -> Result<Option<oracle::OracleState>> {
+        let oracle = match self.oracle() {
+            Some(oracle) => oracle,
+            None => return Ok(None),
+        };
+        let oracle_acc = oracle_acc.ok_or(OpenBookError::MissingOracle)?;
+        require_keys_eq!(oracle, *oracle_acc.key());
+        oracle::oracle_state(
             oracle_acc,
             &self.oracle_config,
             self.base_decimals,
             self.quote_decimals,
             staleness_slot,
         )
+        .map(Some)
     }
 
     // TODO binye
@@ -227,26 +534,63 @@ impl Market {
             quote_deposit_total: 0,
             quote_fees_accrued: 0,
             referrer_rebates_accrued: 0,
-            reserved: [0; 1768],
+            market_admin: Some(Pubkey::new_unique()).into(),
+            taker_price_band_bps: 0,
+            maker_price_band_bps: 0,
+            disabled_instructions: 0,
+            padding2: Default::default(),
+            last_trade_price_lots: 0,
+            time_expiry: 0,
+            fee_tiers: [bytemuck::Zeroable::zeroed(); MAX_FEE_TIERS],
+            fee_tiers_count: 0,
+            padding3: Default::default(),
+            buyback_mint: Pubkey::default(),
+            buyback_oracle: Pubkey::default(),
+            buyback_fees_expiry_interval: 0,
+            base_fees_swept: 0,
+            quote_fees_swept: 0,
+            fee_tier_volume_interval: 0,
+            reserved: [0; 1440],
         }
     }
 
-    pub fn subtract_taker_fees(&self, quote: i64) -> i64 {
-        (I80F48::from(quote) / (I80F48::ONE + self.taker_fee)).to_num()
+    pub fn subtract_taker_fees(&self, quote: i64, taker_fee: I80F48) -> i64 {
+        (I80F48::from(quote) / (I80F48::ONE + taker_fee)).to_num()
+    }
+
+    /// Base lots an order may post at `price_lots` with the quote lots it has left
+    pub fn max_base_lots_to_post(
+        &self,
+        remaining_base_lots: i64,
+        remaining_quote_lots: i64,
+        price_lots: i64,
+    ) -> i64 {
+        let remaining_quote_lots = if self.maker_fee.is_positive() {
+            self.subtract_maker_fees(remaining_quote_lots)
+        } else {
+            remaining_quote_lots
+        };
+        remaining_base_lots.min(remaining_quote_lots / price_lots)
     }
+
     // Only for maker_fee > 0
     pub fn subtract_maker_fees(&self, quote: i64) -> i64 {
         (I80F48::from(quote) / (I80F48::ONE + self.maker_fee)).to_num()
     }
 
-    pub fn referrer_taker_rebate(&self, quote: u64) -> u64 {
+    /// Part of the taker fees of a fill kept back for the maker rebate. Rounded up like the
+    /// rebate paid to the maker, so it covers the maker fees of every fee tier
+    pub fn maker_rebate_reserve(&self, quote: u64) -> u64 {
+        (I80F48::from_num(quote) * self.max_maker_rebate())
+            .ceil()
+            .to_num()
+    }
+
+    /// Part of the taker fees that goes to the referrer. The largest maker rebate is kept
+    /// back for the makers, when they pay fees all goes to the referrer.
+    pub fn referrer_taker_rebate(&self, quote: u64, taker_fee: I80F48) -> u64 {
         let quo = I80F48::from_num(quote);
-        if self.maker_fee < 0 {
-            (quo * (self.taker_fee + self.maker_fee)).to_num()
-        } else {
-            // Nothing goes to maker, all to referrer
-            (quo * self.taker_fee).to_num()
-        }
+        (quo * (taker_fee - self.max_maker_rebate())).to_num()
     }
 }
 
@@ -261,3 +605,73 @@ macro_rules! market_seeds {
     };
 }
 pub(crate) use market_seeds;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market_with_fee_tiers(fee_tiers: &[(u64, f32, f32)]) -> Market {
+        let mut market = Market::default_for_tests();
+        market.maker_fee = I80F48::from_num(-0.0002);
+        market.taker_fee = I80F48::from_num(0.0004);
+        for (tier, &(volume_threshold, maker_fee, taker_fee)) in
+            market.fee_tiers.iter_mut().zip(fee_tiers)
+        {
+            *tier = FeeTierParams {
+                volume_threshold,
+                maker_fee,
+                taker_fee,
+            }
+            .to_fee_tier();
+        }
+        market.fee_tiers_count = fee_tiers.len() as u8;
+        market
+    }
+
+    #[test]
+    fn test_fee_tier() {
+        let market = market_with_fee_tiers(&[(1_000, -0.0002, 0.0003), (10_000, -0.0003, 0.0003)]);
+        assert!(market.validate_fee_tiers().is_ok());
+
+        assert_eq!(market.fee_tier(0), 0);
+        assert_eq!(market.fee_tier(999), 0);
+        assert_eq!(market.fee_tier(1_000), 1);
+        assert_eq!(market.fee_tier(9_999), 1);
+        assert_eq!(market.fee_tier(u64::MAX), 2);
+
+        assert_eq!(market.taker_fee_for_tier(0), market.taker_fee);
+        assert_eq!(market.maker_fee_for_tier(2), I80F48::from_num(-0.0003f32));
+        assert_eq!(market.max_maker_rebate(), I80F48::from_num(0.0003f32));
+        // Only the taker fee above the largest maker rebate goes to the referrer
+        assert_eq!(
+            market.referrer_taker_rebate(1_000_000, market.taker_fee),
+            (I80F48::from_num(1_000_000) * (market.taker_fee - market.max_maker_rebate()))
+                .to_num::<u64>()
+        );
+    }
+
+    #[test]
+    fn test_validate_fee_tiers() {
+        assert!(market_with_fee_tiers(&[]).validate_fee_tiers().is_ok());
+        // thresholds must increase
+        assert!(
+            market_with_fee_tiers(&[(1_000, -0.0001, 0.0003), (1_000, -0.0001, 0.0002)])
+                .validate_fee_tiers()
+                .is_err()
+        );
+        assert!(market_with_fee_tiers(&[(0, -0.0001, 0.0003)])
+            .validate_fee_tiers()
+            .is_err());
+        // makers can't pay fees
+        assert!(market_with_fee_tiers(&[(1_000, 0.0001, 0.0003)])
+            .validate_fee_tiers()
+            .is_err());
+        // a maker rebate larger than the lowest taker fee can't be paid for
+        assert!(market_with_fee_tiers(&[(1_000, -0.0002, 0.0001)])
+            .validate_fee_tiers()
+            .is_err());
+        assert!(market_with_fee_tiers(&[(1_000, -0.0005, 0.0004)])
+            .validate_fee_tiers()
+            .is_err());
+    }
+}
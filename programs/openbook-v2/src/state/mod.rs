@@ -5,7 +5,9 @@ pub use open_orders_components::*;
 pub use oracle::*;
 pub use oracle_config::*;
 pub use orderbook::*;
+pub use referrer::*;
 pub use stable_price::*;
+pub use trigger_orders::*;
 
 mod dynamic_account;
 mod market;
@@ -14,4 +16,6 @@ mod open_orders_components;
 mod oracle;
 mod oracle_config;
 mod orderbook;
+mod referrer;
 mod stable_price;
+mod trigger_orders;
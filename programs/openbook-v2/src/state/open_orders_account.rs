@@ -24,7 +24,16 @@ use super::{BookSideOrderTree, Position};
 type BorshVecLength = u32;
 const BORSH_VEC_PADDING_BYTES: usize = 4;
 const BORSH_VEC_SIZE_BYTES: usize = 4;
-const DEFAULT_OPEN_ORDERS_ACCOUNT_VERSION: u8 = 1;
+/// Version 2 accounts store the market they belong to. Version 1 accounts have the same
+/// layout, with a default market that no market constraint accepts until they are
+/// migrated with `migrate_open_orders_account`.
+const DEFAULT_OPEN_ORDERS_ACCOUNT_VERSION: u8 = 2;
+
+/// Seconds without owner activity after which anyone can settle the free funds of an account
+pub const OPEN_ORDERS_ACCOUNT_IDLE_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+/// Bounty paid to whoever settles an idle account, out of its free quote funds
+pub const SETTLE_FUNDS_BOUNTY_QUOTE_LOTS: u64 = 100;
 
 // OpenOrdersAccount
 // This struct definition is only for clients e.g. typescript, so that they can easily use out of the box
@@ -55,7 +64,21 @@ pub struct OpenOrdersAccount {
     pub buyback_fees_expiry_timestamp: u64,
 
     pub position: Position,
-    pub reserved: [u8; 208],
+    /// Last time the owner or delegate used the account, or one of its orders got filled.
+    /// Accounts idle for longer than OPEN_ORDERS_ACCOUNT_IDLE_SECONDS can be settled
+    /// permissionlessly.
+    pub last_activity_timestamp: u64,
+    /// Market the account trades on, also part of its address seeds
+    pub market: Pubkey,
+
+    /// Quote volume traded in the current fee tier volume interval.
+    pub fee_tier_volume_current: u64,
+    /// Quote volume traded in the previous fee tier volume interval.
+    pub fee_tier_volume_previous: u64,
+    /// End timestamp of the current fee tier volume interval.
+    pub fee_tier_volume_expiry_timestamp: u64,
+
+    pub reserved: [u8; 144],
 
     // dynamic
     pub header_version: u8,
@@ -77,7 +100,12 @@ impl OpenOrdersAccount {
             buyback_fees_accrued_current: 0,
             buyback_fees_accrued_previous: 0,
             buyback_fees_expiry_timestamp: 0,
-            reserved: [0; 208],
+            last_activity_timestamp: 0,
+            market: Pubkey::default(),
+            fee_tier_volume_current: 0,
+            fee_tier_volume_previous: 0,
+            fee_tier_volume_expiry_timestamp: 0,
+            reserved: [0; 144],
             header_version: DEFAULT_OPEN_ORDERS_ACCOUNT_VERSION,
             padding3: Default::default(),
             padding4: Default::default(),
@@ -119,18 +147,23 @@ pub struct OpenOrdersAccountFixed {
     pub buyback_fees_accrued_previous: u64,
     pub buyback_fees_expiry_timestamp: u64,
     pub position: Position,
-    pub reserved: [u8; 208],
+    pub last_activity_timestamp: u64,
+    pub market: Pubkey,
+    pub fee_tier_volume_current: u64,
+    pub fee_tier_volume_previous: u64,
+    pub fee_tier_volume_expiry_timestamp: u64,
+    pub reserved: [u8; 144],
 }
 
 const_assert_eq!(
     size_of::<Position>(),
     size_of::<OpenOrdersAccountFixed>()
-        - size_of::<Pubkey>() * 3
+        - size_of::<Pubkey>() * 4
         - size_of::<u32>()
         - size_of::<u8>()
         - size_of::<[u8; 3]>()
-        - size_of::<u64>() * 3
-        - size_of::<[u8; 208]>()
+        - size_of::<u64>() * 7
+        - size_of::<[u8; 144]>()
 );
 const_assert_eq!(size_of::<OpenOrdersAccountFixed>(), 488);
 const_assert_eq!(size_of::<OpenOrdersAccountFixed>() % 8, 0);
@@ -149,6 +182,99 @@ impl OpenOrdersAccountFixed {
     pub fn is_delegate(&self, ix_signer: Pubkey) -> bool {
         self.delegate == ix_signer
     }
+
+    pub fn is_idle(&self, now_ts: u64) -> bool {
+        now_ts >= self.last_activity_timestamp + OPEN_ORDERS_ACCOUNT_IDLE_SECONDS
+    }
+
+    /// Roll the buyback fees over to the interval containing `now_ts`.
+    ///
+    /// Fees of the previous interval stay available, older ones expire.
+    pub fn expire_buyback_fees(&mut self, now_ts: u64, interval: u64) {
+        expire_interval_amounts(
+            &mut self.buyback_fees_accrued_current,
+            &mut self.buyback_fees_accrued_previous,
+            &mut self.buyback_fees_expiry_timestamp,
+            now_ts,
+            interval,
+        );
+    }
+
+    /// Record `amount` of quote fees paid, to be bought back later
+    pub fn accrue_buyback_fees(&mut self, amount: u64, now_ts: u64, interval: u64) {
+        if interval == 0 {
+            return;
+        }
+        self.expire_buyback_fees(now_ts, interval);
+        self.buyback_fees_accrued_current += amount;
+    }
+
+    /// Fees that can currently be bought back; call expire_buyback_fees() first
+    pub fn buyback_fees_accrued(&self) -> u64 {
+        self.buyback_fees_accrued_current + self.buyback_fees_accrued_previous
+    }
+
+    /// Use up `amount` of the buyback fees, the oldest ones first
+    pub fn reduce_buyback_fees_accrued(&mut self, amount: u64) {
+        let from_previous = amount.min(self.buyback_fees_accrued_previous);
+        self.buyback_fees_accrued_previous -= from_previous;
+        self.buyback_fees_accrued_current -= amount - from_previous;
+    }
+
+    /// Roll the fee tier volume over to the interval containing `now_ts`.
+    ///
+    /// Volume of the previous interval still counts, older volume is dropped.
+    pub fn expire_fee_tier_volume(&mut self, now_ts: u64, interval: u64) {
+        expire_interval_amounts(
+            &mut self.fee_tier_volume_current,
+            &mut self.fee_tier_volume_previous,
+            &mut self.fee_tier_volume_expiry_timestamp,
+            now_ts,
+            interval,
+        );
+    }
+
+    /// Record `amount` of quote volume traded, for the fee tier
+    pub fn accrue_fee_tier_volume(&mut self, amount: u64, now_ts: u64, interval: u64) {
+        if interval == 0 {
+            return;
+        }
+        self.expire_fee_tier_volume(now_ts, interval);
+        self.fee_tier_volume_current += amount;
+    }
+
+    /// Quote volume that decides the fee tier; call expire_fee_tier_volume() first.
+    /// Markets without a volume interval use the lifetime volume.
+    pub fn fee_tier_volume(&self, interval: u64) -> u64 {
+        if interval == 0 {
+            self.position.volume()
+        } else {
+            self.fee_tier_volume_current + self.fee_tier_volume_previous
+        }
+    }
+}
+
+/// Roll an amount tracked per interval over to the interval containing `now_ts`,
+/// keeping the amount of the previous interval and dropping older ones.
+fn expire_interval_amounts(
+    current: &mut u64,
+    previous: &mut u64,
+    expiry_timestamp: &mut u64,
+    now_ts: u64,
+    interval: u64,
+) {
+    if interval == 0 || now_ts < *expiry_timestamp {
+        return;
+    } else if now_ts < *expiry_timestamp + interval {
+        *previous = *current;
+    } else {
+        *previous = 0;
+    }
+    *current = 0;
+
+    let prev_start = expiry_timestamp.saturating_sub(interval);
+    let new_start = now_ts - (now_ts - prev_start) % interval;
+    *expiry_timestamp = new_start + interval;
 }
 
 impl Owner for OpenOrdersAccountFixed {
@@ -173,7 +299,7 @@ impl DynamicHeader for OpenOrdersAccountDynamicHeader {
         let header_version = u8::from_le_bytes(*array_ref![dynamic_data, 0, size_of::<u8>()]);
 
         match header_version {
-            1 => {
+            1 | 2 => {
                 let oo_count = u8::try_from(BorshVecLength::from_le_bytes(*array_ref![
                     dynamic_data,
                     OpenOrdersAccount::dynamic_oo_vec_offset(),
@@ -340,6 +466,12 @@ impl<
         self.dynamic.deref_or_borrow_mut()
     }
 
+    /// Store the market of a version 1 account and bump it to the current version
+    pub fn migrate_market(&mut self, market: Pubkey) {
+        self.fixed_mut().market = market;
+        *get_helper_mut::<u8>(self.dynamic_mut(), 0) = DEFAULT_OPEN_ORDERS_ACCOUNT_VERSION;
+    }
+
     pub fn borrow_mut(&mut self) -> OpenOrdersAccountRefMut {
         OpenOrdersAccountRefMut {
             header: self.header.deref_or_borrow_mut(),
@@ -357,16 +489,31 @@ impl<
         let side = fill.taker_side().invert_side();
         let (base_change, quote_change) = fill.base_quote_change(side);
         let quote_native_abs = (market.quote_lot_size * quote_change).unsigned_abs();
-        let fees = if market.maker_fee.is_positive() {
+        self.fixed_mut()
+            .expire_fee_tier_volume(fill.timestamp, market.fee_tier_volume_interval);
+        let maker_fee_tier = market.fee_tier(
+            self.fixed()
+                .fee_tier_volume(market.fee_tier_volume_interval),
+        );
+        let maker_fee = market.maker_fee_for_tier(maker_fee_tier);
+        let fees = if maker_fee.is_positive() {
             // Maker pays fee. Fees already subtracted before sending to the book
             0
         } else {
-            (I80F48::from(quote_native_abs) * market.maker_fee)
+            (I80F48::from(quote_native_abs) * maker_fee)
                 .abs()
                 .ceil()
                 .to_num::<u64>()
         };
 
+        // The taker fees kept back the largest maker rebate of all tiers, what this
+        // maker doesn't get leaves the deposits to the market fees
+        let unused_rebate = market
+            .maker_rebate_reserve(quote_native_abs)
+            .saturating_sub(fees);
+        market.quote_fees_accrued += unused_rebate;
+        market.quote_deposit_total -= unused_rebate;
+
         let locked_price = {
             let oo = self.order_by_raw_index(fill.maker_slot as usize);
             match oo.side_and_tree().order_tree() {
@@ -375,6 +522,14 @@ impl<
             }
         };
 
+        // A fill of a resting order counts as activity of the account
+        self.fixed_mut().last_activity_timestamp = fill.timestamp;
+        self.fixed_mut().accrue_fee_tier_volume(
+            quote_native_abs,
+            fill.timestamp,
+            market.fee_tier_volume_interval,
+        );
+
         let pa = &mut self.fixed_mut().position;
         pa.maker_volume += quote_native_abs;
 
@@ -423,6 +578,14 @@ impl<
         if fill.maker_out() {
             self.remove_order(fill.maker_slot as usize, base_change.abs())?;
         } else {
+            // Iceberg orders get a new id when replenished from the hidden reserve
+            if fill.maker_replenished_order_id != 0 {
+                let oo = self.open_order_mut_by_raw_index(fill.maker_slot as usize);
+                if oo.id == fill.maker_order_id {
+                    oo.id = fill.maker_replenished_order_id;
+                }
+            }
+            let pa = &mut self.fixed_mut().position;
             match side {
                 Side::Bid => {
                     pa.bids_base_lots -= base_change.abs();
@@ -434,7 +597,18 @@ impl<
         }
 
         // Update market fees
-        market.fees_accrued += (market.maker_fee * I80F48::from(quote_native_abs)).to_num::<i64>();
+        market.fees_accrued += (maker_fee * I80F48::from(quote_native_abs)).to_num::<i64>();
+
+        if maker_fee.is_positive() {
+            let maker_fees_paid = (I80F48::from(quote_native_abs) * maker_fee)
+                .ceil()
+                .to_num::<u64>();
+            self.fixed_mut().accrue_buyback_fees(
+                maker_fees_paid,
+                fill.timestamp,
+                market.buyback_fees_expiry_interval,
+            );
+        }
 
         //Emit event
         emit!(FillLog {
@@ -445,43 +619,19 @@ impl<
             seq_num: fill.seq_num,
             maker: fill.maker,
             maker_client_order_id: fill.maker_client_order_id,
-            maker_fee: market.maker_fee.to_num(),
+            maker_fee: maker_fee.to_num(),
+            maker_fee_tier,
             maker_timestamp: fill.maker_timestamp,
             taker: fill.taker,
             taker_client_order_id: fill.taker_client_order_id,
-            taker_fee: market.taker_fee.to_num(),
+            taker_fee: market.taker_fee_for_tier(fill.taker_fee_tier).to_num(),
+            taker_fee_tier: fill.taker_fee_tier,
             price: fill.price,
             quantity: fill.quantity,
         });
         Ok(())
     }
 
-    pub fn execute_taker(&mut self, market: &mut Market, fill: &FillEvent) -> Result<()> {
-        let pa = &mut self.fixed_mut().position;
-
-        // Replicate the base_quote_change function but subtracting the fees for an Ask
-        // let (base_change, quote_change) = fill.base_quote_change(fill.taker_side());
-        let _base_change: i64;
-        let quote_change: i64;
-        match fill.taker_side() {
-            Side::Bid => {
-                _base_change = fill.quantity;
-                quote_change = -fill.price * fill.quantity;
-            }
-            Side::Ask => {
-                // remove fee from quote_change
-                _base_change = -fill.quantity;
-                quote_change = fill.price * fill.quantity * (1 - market.taker_fee.to_num::<i64>());
-            }
-        };
-
-        // fees are assessed at time of trade; no need to assess fees here
-        let quote_change_native = I80F48::from(market.quote_lot_size) * I80F48::from(quote_change);
-        pa.taker_volume += quote_change_native.abs().to_num::<u64>();
-
-        Ok(())
-    }
-
     fn write_oo_length(&mut self) {
         let oo_offset = self.header().oo_offset(0);
 
@@ -525,6 +675,26 @@ impl<
         Ok(())
     }
 
+    /// Drop the trailing open order slots, which must all be free. The account data can
+    /// be reallocated to the smaller size afterwards.
+    pub fn shrink_dynamic_content(&mut self, new_oo_count: u8) -> Result<()> {
+        require_gte!(self.header().oo_count, new_oo_count);
+        for i in usize::from(new_oo_count)..self.header().oo_count() {
+            require!(
+                self.order_by_raw_index(i).is_free(),
+                OpenBookError::OpenOrdersSlotInUse
+            );
+        }
+
+        // open orders start at the same offset whatever their count
+        *self.header_mut() = OpenOrdersAccountDynamicHeader {
+            oo_count: new_oo_count,
+        };
+        self.write_oo_length();
+
+        Ok(())
+    }
+
     pub fn add_order(
         &mut self,
         side: Side,
@@ -536,10 +706,10 @@ impl<
         let position = &mut self.fixed_mut().position;
         match side {
             Side::Bid => {
-                position.bids_base_lots += order.quantity;
+                position.bids_base_lots += order.total_quantity();
             }
             Side::Ask => {
-                position.asks_base_lots += order.quantity;
+                position.asks_base_lots += order.total_quantity();
             }
         };
         let slot = order.owner_slot as usize;
@@ -580,6 +750,39 @@ impl<
     }
 
     pub fn cancel_order(&mut self, slot: usize, base_quantity: i64, market: Market) -> Result<()> {
+        self.release_order_funds(slot, base_quantity, &market);
+        self.remove_order(slot, base_quantity)
+    }
+
+    /// Reduce the order in `slot` by `base_quantity` lots while keeping it open
+    pub fn reduce_order(
+        &mut self,
+        slot: usize,
+        base_quantity: i64,
+        client_order_id: u64,
+        market: Market,
+    ) -> Result<()> {
+        self.release_order_funds(slot, base_quantity, &market);
+
+        let oo = self.open_order_mut_by_raw_index(slot);
+        require_neq!(oo.id, 0);
+        oo.client_id = client_order_id;
+
+        let order_side = oo.side_and_tree().side();
+        let position = &mut self.fixed_mut().position;
+        match order_side {
+            Side::Bid => {
+                position.bids_base_lots -= base_quantity;
+            }
+            Side::Ask => {
+                position.asks_base_lots -= base_quantity;
+            }
+        }
+        Ok(())
+    }
+
+    /// Credit the funds reserved for `base_quantity` lots of the order in `slot` back to the position
+    fn release_order_funds(&mut self, slot: usize, base_quantity: i64, market: &Market) {
         {
             let oo = self.open_order_mut_by_raw_index(slot);
 
@@ -614,8 +817,6 @@ impl<
                 }
             }
         }
-
-        self.remove_order(slot, base_quantity)
     }
 }
 
@@ -682,3 +883,69 @@ impl<'a, 'info: 'a> OpenOrdersLoader<'a> for &'a AccountLoader<'info, OpenOrders
         self.load_full_mut()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buyback_fees_expiry() {
+        let mut fixed: OpenOrdersAccountFixed = bytemuck::Zeroable::zeroed();
+        let interval = 100;
+
+        // disabled
+        fixed.accrue_buyback_fees(10, 1000, 0);
+        assert_eq!(fixed.buyback_fees_accrued(), 0);
+
+        fixed.accrue_buyback_fees(10, 1010, interval);
+        assert_eq!(fixed.buyback_fees_accrued_current, 10);
+        assert_eq!(fixed.buyback_fees_expiry_timestamp, 1100);
+
+        fixed.accrue_buyback_fees(5, 1090, interval);
+        assert_eq!(fixed.buyback_fees_accrued_current, 15);
+
+        // rolls over into the previous interval
+        fixed.accrue_buyback_fees(7, 1150, interval);
+        assert_eq!(fixed.buyback_fees_accrued_previous, 15);
+        assert_eq!(fixed.buyback_fees_accrued_current, 7);
+        assert_eq!(fixed.buyback_fees_expiry_timestamp, 1200);
+        assert_eq!(fixed.buyback_fees_accrued(), 22);
+
+        // oldest fees are used first
+        fixed.reduce_buyback_fees_accrued(17);
+        assert_eq!(fixed.buyback_fees_accrued_previous, 0);
+        assert_eq!(fixed.buyback_fees_accrued_current, 5);
+
+        // more than one interval later everything expired
+        fixed.expire_buyback_fees(1420, interval);
+        assert_eq!(fixed.buyback_fees_accrued(), 0);
+        assert_eq!(fixed.buyback_fees_expiry_timestamp, 1500);
+    }
+
+    #[test]
+    fn test_fee_tier_volume_expiry() {
+        let mut fixed: OpenOrdersAccountFixed = bytemuck::Zeroable::zeroed();
+        fixed.position.maker_volume = 500;
+        fixed.position.taker_volume = 300;
+        let interval = 100;
+
+        // without an interval the lifetime volume counts
+        fixed.accrue_fee_tier_volume(10, 1000, 0);
+        assert_eq!(fixed.fee_tier_volume(0), 800);
+        assert_eq!(fixed.fee_tier_volume(interval), 0);
+
+        fixed.accrue_fee_tier_volume(10, 1010, interval);
+        fixed.accrue_fee_tier_volume(20, 1150, interval);
+        assert_eq!(fixed.fee_tier_volume_previous, 10);
+        assert_eq!(fixed.fee_tier_volume_current, 20);
+        assert_eq!(fixed.fee_tier_volume(interval), 30);
+
+        // the previous interval drops out
+        fixed.expire_fee_tier_volume(1250, interval);
+        assert_eq!(fixed.fee_tier_volume(interval), 20);
+
+        // more than one interval later all volume expired
+        fixed.expire_fee_tier_volume(1420, interval);
+        assert_eq!(fixed.fee_tier_volume(interval), 0);
+    }
+}
@@ -60,6 +60,11 @@ impl Default for Position {
 }
 
 impl Position {
+    /// Maker plus taker volume in quote native, which determines the fee tier
+    pub fn volume(&self) -> u64 {
+        self.maker_volume + self.taker_volume
+    }
+
     /// Does the user have any orders on the book?
     ///
     /// Note that it's possible they were matched already: This only becomes
@@ -67,6 +72,14 @@ impl Position {
     pub fn has_open_orders(&self) -> bool {
         self.asks_base_lots != 0 || self.bids_base_lots != 0
     }
+
+    /// Is the position free of orders and funds, so that its account can be closed?
+    pub fn is_empty(&self) -> bool {
+        !self.has_open_orders()
+            && self.base_free_native == 0
+            && self.quote_free_native == 0
+            && self.referrer_rebates_accrued == 0
+    }
 }
 
 #[zero_copy]
@@ -100,4 +113,9 @@ impl OpenOrder {
     pub fn side_and_tree(&self) -> SideAndOrderTree {
         SideAndOrderTree::try_from(self.side_and_tree).unwrap()
     }
+
+    /// Is this order slot available for a new order?
+    pub fn is_free(&self) -> bool {
+        self.id == 0
+    }
 }
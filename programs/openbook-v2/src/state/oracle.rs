@@ -96,9 +96,10 @@ pub struct StubOracle {
     pub mint: Pubkey,
     pub price: I80F48,
     pub last_updated: i64,
-    pub reserved: [u8; 128],
+    pub last_update_slot: u64,
+    pub reserved: [u8; 120],
 }
-const_assert_eq!(size_of::<StubOracle>(), 32 + 32 + 16 + 8 + 128);
+const_assert_eq!(size_of::<StubOracle>(), 32 + 32 + 16 + 8 + 8 + 120);
 const_assert_eq!(size_of::<StubOracle>(), 216);
 const_assert_eq!(size_of::<StubOracle>() % 8, 0);
 
@@ -125,6 +126,21 @@ pub fn determine_oracle_type(acc_info: &impl KeyedAccountReader) -> Result<Oracl
     Err(OpenBookError::UnknownOracleType.into())
 }
 
+#[derive(Clone, Copy)]
+pub struct OracleState {
+    /// Price of one native base token, in native quote tokens
+    pub price: I80F48,
+    /// Slot at which the oracle price was last updated
+    pub last_update_slot: u64,
+}
+
+impl OracleState {
+    /// Number of slots elapsed since the last oracle update
+    pub fn staleness_slots(&self, now_slot: u64) -> u64 {
+        now_slot.saturating_sub(self.last_update_slot)
+    }
+}
+
 /// Returns the price of one native base token, in native quote tokens
 ///
 /// Example: The for SOL at 40 USDC/SOL it would return 0.04 (the unit is USDC-native/SOL-native)
@@ -136,11 +152,35 @@ pub fn oracle_price(
     quote_decimals: u8,
     staleness_slot: u64,
 ) -> Result<I80F48> {
+    oracle_state(
+        acc_info,
+        config,
+        base_decimals,
+        quote_decimals,
+        staleness_slot,
+    )
+    .map(|state| state.price)
+}
+
+/// Returns the oracle price together with the slot of its last update
+pub fn oracle_state(
+    acc_info: &impl KeyedAccountReader,
+    config: &OracleConfig,
+    base_decimals: u8,
+    quote_decimals: u8,
+    staleness_slot: u64,
+) -> Result<OracleState> {
     let data = &acc_info.data();
     let oracle_type = determine_oracle_type(acc_info)?;
 
     Ok(match oracle_type {
-        OracleType::Stub => acc_info.load::<StubOracle>()?.price,
+        OracleType::Stub => {
+            let stub = acc_info.load::<StubOracle>()?;
+            OracleState {
+                price: stub.price,
+                last_update_slot: stub.last_update_slot,
+            }
+        }
         OracleType::Pyth => {
             let price_account = pyth_sdk_solana::state::load_price_account(data).unwrap();
             let price_data = price_account.to_price();
@@ -182,7 +222,10 @@ pub fn oracle_price(
             let decimals =
                 (price_account.expo as i8) + (quote_decimals as i8) - (base_decimals as i8);
             let decimal_adj = power_of_ten(decimals);
-            price * decimal_adj
+            OracleState {
+                price: price * decimal_adj,
+                last_update_slot: last_slot,
+            }
         }
         OracleType::SwitchboardV2 => {
             fn from_foreign_error(e: impl std::fmt::Display) -> Error {
@@ -228,7 +271,10 @@ pub fn oracle_price(
 
             let decimals = (quote_decimals as i8) - (base_decimals as i8);
             let decimal_adj = power_of_ten(decimals);
-            price * decimal_adj
+            OracleState {
+                price: price * decimal_adj,
+                last_update_slot: round_open_slot,
+            }
         }
         OracleType::SwitchboardV1 => {
             let result = FastRoundResultAccountData::deserialize(data).unwrap();
@@ -264,7 +310,10 @@ pub fn oracle_price(
 
             let decimals = (quote_decimals as i8) - (base_decimals as i8);
             let decimal_adj = power_of_ten(decimals);
-            price * decimal_adj
+            OracleState {
+                price: price * decimal_adj,
+                last_update_slot: round_open_slot,
+            }
         }
     })
 }
@@ -21,6 +21,7 @@ pub struct Orderbook<'a> {
     pub asks: RefMut<'a, BookSide>,
 }
 
+#[derive(Clone, Copy)]
 pub struct OrderWithAmounts {
     pub order_id: Option<u128>,
     pub placed_quantity: i64,
@@ -28,6 +29,9 @@ pub struct OrderWithAmounts {
     pub total_quote_taken_native: u64,
     pub maker_fees: u64,
     pub referrer_amount: u64,
+    /// Rounding remainder of the taker fees after the referrer and maker rebates, to be
+    /// moved from the quote deposits to the market fees
+    pub taker_fee_remainder: u64,
 }
 
 impl<'a> Orderbook<'a> {
@@ -56,15 +60,18 @@ impl<'a> Orderbook<'a> {
         order: &Order,
         open_book_market: &mut Market,
         event_queue: &mut EventQueue,
-        oracle_price: I80F48,
+        oracle_price: Option<I80F48>,
+        oracle_staleness_slots: u64,
         mut open_orders_acc: &mut Option<OpenOrdersAccountRefMut>,
         owner: &Pubkey,
+        referrer: Option<Pubkey>,
         now_ts: u64,
         mut limit: u8,
         open_orders_admin_signer: Option<Pubkey>,
         remaining_accs: &[AccountInfo],
     ) -> std::result::Result<OrderWithAmounts, Error> {
         let market = open_book_market;
+        require!(!market.is_expired(now_ts), OpenBookError::MarketHasExpired);
         if let Some(open_orders_admin) = Option::<Pubkey>::from(market.open_orders_admin) {
             let open_orders_admin_signer =
                 open_orders_admin_signer.ok_or(OpenBookError::MissingOpenOrdersAdmin)?;
@@ -75,17 +82,46 @@ impl<'a> Orderbook<'a> {
             );
         }
 
+        // Oracle pegged orders can't be placed while the oracle is staler than they accept
+        let max_oracle_staleness_slots = order.max_oracle_staleness_slots();
+        require!(
+            max_oracle_staleness_slots < 0
+                || oracle_staleness_slots <= max_oracle_staleness_slots as u64,
+            OpenBookError::OracleStale
+        );
+
         let side = order.side;
 
         let other_side = side.invert_side();
-        let oracle_price_lots = market.native_price_to_lot(oracle_price);
+        let oracle_price_lots = oracle_price.map(|price| market.native_price_to_lot(price));
         let post_only = order.is_post_only();
         let mut post_target = order.post_target();
-        let (price_lots, price_data) = order.price(now_ts, oracle_price_lots, self)?;
+        let (price_lots, price_data) =
+            order.price(now_ts, oracle_price_lots, oracle_staleness_slots, self)?;
+
+        // Takers don't match beyond the taker price band
+        let match_limit_lots =
+            match market.price_band_limit_lots(side, market.taker_price_band_bps, oracle_price) {
+                Some(band_limit_lots) => match side {
+                    Side::Bid => price_lots.min(band_limit_lots),
+                    Side::Ask => price_lots.max(band_limit_lots),
+                },
+                None => price_lots,
+            };
 
         // generate new order id
         let order_id = market.gen_order_id(side, price_data);
 
+        // Takers without an open orders account pay the market fees
+        let taker_fee_tier = open_orders_acc.as_mut().map_or(0, |open_orders_acc| {
+            let interval = market.fee_tier_volume_interval;
+            open_orders_acc
+                .fixed_mut()
+                .expire_fee_tier_volume(now_ts, interval);
+            market.fee_tier(open_orders_acc.fixed.fee_tier_volume(interval))
+        });
+        let taker_fee = market.taker_fee_for_tier(taker_fee_tier);
+
         // Iterate through book and match against this new order.
         //
         // Any changes to matching orders on the other side of the book are collected in
@@ -95,139 +131,205 @@ impl<'a> Orderbook<'a> {
         let mut decremented_quote_lots = 0_i64;
 
         let mut max_quote_lots = remaining_quote_lots;
-        let mut matched_order_changes: Vec<(BookSideOrderHandle, i64)> = vec![];
-        let mut matched_order_deletes: Vec<(BookSideOrderTree, u128)> = vec![];
         let mut number_of_dropped_expired_orders = 0;
         // In case of take order, need this
         let mut referrer_amount: u64 = 0;
-        let opposing_bookside = self.bookside_mut(other_side);
+        let mut taker_fee_remainder: u64 = 0;
+        // Taker fees kept back for the rebates of the matched makers
+        let mut maker_rebate_reserve: u64 = 0;
 
         // Subtract fees in case of bid
         if side == Side::Bid {
-            max_quote_lots = market.subtract_taker_fees(remaining_quote_lots);
+            max_quote_lots = market.subtract_taker_fees(remaining_quote_lots, taker_fee);
             remaining_quote_lots = max_quote_lots;
         }
 
-        for best_opposing in opposing_bookside.iter_all_including_invalid(now_ts, oracle_price_lots)
-        {
-            if remaining_base_lots == 0 || remaining_quote_lots == 0 {
-                break;
-            }
+        // Matching starts over on the updated book after an iceberg order was replenished,
+        // as it can still be the best order to match against
+        loop {
+            let mut matched_order_changes: Vec<(BookSideOrderHandle, i64)> = vec![];
+            let mut matched_order_deletes: Vec<(BookSideOrderTree, u128)> = vec![];
+            // Iceberg order whose visible quantity was consumed: tree, old key, new key
+            let mut matched_order_replenish: Option<(BookSideOrderTree, u128, u128)> = None;
+            let opposing_bookside = self.bookside_mut(other_side);
 
-            if !best_opposing.is_valid() {
-                // Remove the order from the book unless we've done that enough
-                if number_of_dropped_expired_orders < DROP_EXPIRED_ORDER_LIMIT {
-                    number_of_dropped_expired_orders += 1;
-                    let event = OutEvent::new(
-                        other_side,
-                        best_opposing.node.owner_slot,
-                        now_ts,
-                        event_queue.header.seq_num,
-                        best_opposing.node.owner,
-                        best_opposing.node.quantity,
-                    );
-
-                    process_out_event(
-                        event,
-                        market,
-                        event_queue,
-                        open_orders_acc,
-                        owner,
-                        remaining_accs,
-                    )?;
-                    matched_order_deletes
-                        .push((best_opposing.handle.order_tree, best_opposing.node.key));
+            for best_opposing in opposing_bookside.iter_all_including_invalid(
+                now_ts,
+                oracle_price_lots,
+                oracle_staleness_slots,
+            ) {
+                if remaining_base_lots == 0 || remaining_quote_lots == 0 {
+                    break;
                 }
-                continue;
-            }
-
-            let best_opposing_price = best_opposing.price_lots;
 
-            if !side.is_price_within_limit(best_opposing_price, price_lots) {
-                break;
-            } else if post_only {
-                msg!("Order could not be placed due to PostOnly");
-                post_target = None;
-                break; // return silently to not fail other instructions in tx
-            } else if limit == 0 {
-                msg!("Order matching limit reached");
-                post_target = None;
-                break;
-            }
-
-            let max_match_by_quote = remaining_quote_lots / best_opposing_price;
-            if max_match_by_quote == 0 {
-                break;
-            }
-
-            let match_base_lots = remaining_base_lots
-                .min(best_opposing.node.quantity)
-                .min(max_match_by_quote);
-            let match_quote_lots = match_base_lots * best_opposing_price;
-
-            // Self-trade behaviour
-            if owner == &best_opposing.node.owner {
-                match order.self_trade_behavior {
-                    SelfTradeBehavior::DecrementTake => {
-                        // remember all decremented quote lots to only charge fees on not-self-trades
-                        decremented_quote_lots += match_quote_lots;
-                    }
-                    SelfTradeBehavior::CancelProvide => {
-                        // The open orders acc is always present in this case, no need event_queue
-                        open_orders_acc.as_mut().unwrap().cancel_order(
-                            best_opposing.node.owner_slot as usize,
-                            best_opposing.node.quantity,
-                            *market,
+                if !best_opposing.is_valid() {
+                    // Remove the order from the book unless we've done that enough
+                    if number_of_dropped_expired_orders < DROP_EXPIRED_ORDER_LIMIT {
+                        number_of_dropped_expired_orders += 1;
+                        let event = OutEvent::new(
+                            other_side,
+                            best_opposing.node.owner_slot,
+                            now_ts,
+                            event_queue.header.seq_num,
+                            best_opposing.node.owner,
+                            best_opposing.node.total_quantity(),
+                        );
+
+                        process_out_event(
+                            event,
+                            market,
+                            event_queue,
+                            open_orders_acc,
+                            owner,
+                            remaining_accs,
                         )?;
                         matched_order_deletes
                             .push((best_opposing.handle.order_tree, best_opposing.node.key));
-
-                        // skip actual matching
-                        continue;
                     }
-                    SelfTradeBehavior::AbortTransaction => {
-                        return err!(OpenBookError::WouldSelfTrade)
+                    continue;
+                }
+
+                let best_opposing_price = best_opposing.price_lots;
+
+                if !side.is_price_within_limit(best_opposing_price, price_lots) {
+                    break;
+                } else if post_only {
+                    msg!("Order could not be placed due to PostOnly");
+                    post_target = None;
+                    break; // return silently to not fail other instructions in tx
+                } else if !side.is_price_within_limit(best_opposing_price, match_limit_lots) {
+                    // The unmatched remainder would cross the book, don't post it
+                    msg!("Order matching stopped at the taker price band");
+                    post_target = None;
+                    break;
+                } else if limit == 0 {
+                    msg!("Order matching limit reached");
+                    post_target = None;
+                    break;
+                }
+
+                let max_match_by_quote = remaining_quote_lots / best_opposing_price;
+                if max_match_by_quote == 0 {
+                    break;
+                }
+
+                let match_base_lots = remaining_base_lots
+                    .min(best_opposing.node.quantity)
+                    .min(max_match_by_quote);
+                let match_quote_lots = match_base_lots * best_opposing_price;
+
+                // Self-trade behaviour
+                if owner == &best_opposing.node.owner {
+                    match order.self_trade_behavior {
+                        SelfTradeBehavior::DecrementTake => {
+                            // remember all decremented quote lots to only charge fees on not-self-trades
+                            decremented_quote_lots += match_quote_lots;
+                        }
+                        SelfTradeBehavior::CancelProvide => {
+                            // The open orders acc is always present in this case, no need event_queue
+                            open_orders_acc.as_mut().unwrap().cancel_order(
+                                best_opposing.node.owner_slot as usize,
+                                best_opposing.node.total_quantity(),
+                                *market,
+                            )?;
+                            matched_order_deletes
+                                .push((best_opposing.handle.order_tree, best_opposing.node.key));
+
+                            // skip actual matching
+                            continue;
+                        }
+                        SelfTradeBehavior::AbortTransaction => {
+                            return err!(OpenBookError::WouldSelfTrade)
+                        }
                     }
+                    assert!(order.self_trade_behavior == SelfTradeBehavior::DecrementTake);
+                } else {
+                    maker_rebate_reserve += market
+                        .maker_rebate_reserve((match_quote_lots * market.quote_lot_size) as u64);
                 }
-                assert!(order.self_trade_behavior == SelfTradeBehavior::DecrementTake);
-            }
 
-            remaining_base_lots -= match_base_lots;
-            remaining_quote_lots -= match_quote_lots;
-            assert!(remaining_quote_lots >= 0);
+                remaining_base_lots -= match_base_lots;
+                remaining_quote_lots -= match_quote_lots;
+                assert!(remaining_quote_lots >= 0);
+
+                let new_best_opposing_quantity = best_opposing.node.quantity - match_base_lots;
+                let mut maker_replenished_order_id = 0;
+                if new_best_opposing_quantity == 0 && best_opposing.node.hidden_quantity > 0 {
+                    // Replenish from the hidden reserve, the order loses its time priority
+                    maker_replenished_order_id =
+                        market.gen_order_id(other_side, best_opposing.node.price_data());
+                    matched_order_replenish = Some((
+                        best_opposing.handle.order_tree,
+                        best_opposing.node.key,
+                        maker_replenished_order_id,
+                    ));
+                }
+                let maker_out = new_best_opposing_quantity == 0 && maker_replenished_order_id == 0;
+                if maker_out {
+                    matched_order_deletes
+                        .push((best_opposing.handle.order_tree, best_opposing.node.key));
+                } else if maker_replenished_order_id == 0 {
+                    matched_order_changes.push((best_opposing.handle, new_best_opposing_quantity));
+                }
 
-            let new_best_opposing_quantity = best_opposing.node.quantity - match_base_lots;
-            let maker_out = new_best_opposing_quantity == 0;
-            if maker_out {
-                matched_order_deletes
-                    .push((best_opposing.handle.order_tree, best_opposing.node.key));
-            } else {
-                matched_order_changes.push((best_opposing.handle, new_best_opposing_quantity));
-            }
+                let fill = FillEvent::new(
+                    side,
+                    maker_out,
+                    best_opposing.node.owner_slot,
+                    now_ts,
+                    event_queue.header.seq_num,
+                    best_opposing.node.owner,
+                    best_opposing.node.key,
+                    maker_replenished_order_id,
+                    best_opposing.node.client_order_id,
+                    best_opposing.node.timestamp,
+                    *owner,
+                    order.client_order_id,
+                    best_opposing_price,
+                    match_base_lots,
+                    taker_fee_tier,
+                );
 
-            let fill = FillEvent::new(
-                side,
-                maker_out,
-                best_opposing.node.owner_slot,
-                now_ts,
-                event_queue.header.seq_num,
-                best_opposing.node.owner,
-                best_opposing.node.client_order_id,
-                best_opposing.node.timestamp,
-                *owner,
-                order.client_order_id,
-                best_opposing_price,
-                match_base_lots,
-            );
+                process_fill_event(fill, market, event_queue, remaining_accs)?;
+                market.last_trade_price_lots = best_opposing_price;
 
-            process_fill_event(fill, market, event_queue, remaining_accs)?;
+                limit -= 1;
 
-            limit -= 1;
+                if matched_order_replenish.is_some() {
+                    break;
+                }
+            }
+
+            // Apply changes to matched asks (handles invalidate on delete!)
+            for (handle, new_quantity) in matched_order_changes {
+                opposing_bookside
+                    .node_mut(handle.node)
+                    .unwrap()
+                    .as_leaf_mut()
+                    .unwrap()
+                    .quantity = new_quantity;
+            }
+            for (component, key) in matched_order_deletes {
+                let _removed_leaf = opposing_bookside.remove_by_key(component, key).unwrap();
+            }
+            if let Some((component, key, new_key)) = matched_order_replenish {
+                let mut leaf = opposing_bookside.remove_by_key(component, key).unwrap();
+                leaf.quantity = leaf.display_quantity.min(leaf.hidden_quantity);
+                leaf.hidden_quantity -= leaf.quantity;
+                leaf.key = new_key;
+                let _result = opposing_bookside.insert_leaf(component, &leaf)?;
+            }
 
-            if let Some(open_orders_acc) = open_orders_acc.as_mut() {
-                open_orders_acc.execute_taker(market, &fill)?
+            if matched_order_replenish.is_none() {
+                break;
             }
         }
+
+        // FillOrKill orders fail, reverting the matches
+        if order.is_fill_or_kill() && remaining_base_lots > 0 {
+            return err!(OpenBookError::WouldNotFill);
+        }
+
         let total_quote_lots_taken = max_quote_lots - remaining_quote_lots;
         let total_base_lots_taken: i64 = order.max_base_lots - remaining_base_lots;
         assert!(total_quote_lots_taken >= 0);
@@ -246,8 +348,7 @@ impl<'a> Orderbook<'a> {
             // Calculations
             let total_quantity_paid: u64;
             let total_quantity_received: u64;
-            let taker_fees = (I80F48::from_num(total_quote_taken_native_wo_self)
-                * market.taker_fee)
+            let taker_fees = (I80F48::from_num(total_quote_taken_native_wo_self) * taker_fee)
                 .ceil()
                 .to_num::<u64>();
 
@@ -266,6 +367,11 @@ impl<'a> Orderbook<'a> {
                 }
             };
 
+            let referrer_rebate =
+                market.referrer_taker_rebate(total_quote_taken_native_wo_self, taker_fee);
+            // Taker fees are rounded up and the rebates down, the difference is market fees
+            taker_fee_remainder = taker_fees.saturating_sub(referrer_rebate + maker_rebate_reserve);
+
             if let Some(open_orders_acc) = &mut open_orders_acc {
                 release_funds_fees(
                     side,
@@ -274,15 +380,16 @@ impl<'a> Orderbook<'a> {
                     total_base_taken_native,
                     total_quote_taken_native_wo_self,
                     taker_fees,
+                    referrer_rebate,
+                    now_ts,
                 )?;
             } else {
                 // It's a taker order, transfer to referrer
-                referrer_amount += market.referrer_taker_rebate(total_quote_taken_native_wo_self);
+                referrer_amount += referrer_rebate;
             }
             // Only account taker fees now. Maker fees accounted once processing the event
-            market.fees_accrued += (I80F48::from_num(total_quote_taken_native_wo_self)
-                * market.taker_fee)
-                .to_num::<i64>();
+            market.fees_accrued +=
+                (I80F48::from_num(total_quote_taken_native_wo_self) * taker_fee).to_num::<i64>();
 
             emit!(TotalOrderFillEvent {
                 side: side.into(),
@@ -290,6 +397,7 @@ impl<'a> Orderbook<'a> {
                 total_quantity_paid,
                 total_quantity_received,
                 fees: taker_fees,
+                referrer,
             });
         } else if order.needs_penalty_fee() {
             // IOC orders have a fee penalty applied if not match to avoid spam
@@ -299,20 +407,7 @@ impl<'a> Orderbook<'a> {
         // Update remaining based on quote_lots taken. If nothing taken, same as the beginning
         remaining_quote_lots = order.max_quote_lots_including_fees
             - total_quote_lots_taken
-            - (market.taker_fee * I80F48::from_num(total_quote_taken_lots_wo_self)).to_num::<i64>();
-
-        // Apply changes to matched asks (handles invalidate on delete!)
-        for (handle, new_quantity) in matched_order_changes {
-            opposing_bookside
-                .node_mut(handle.node)
-                .unwrap()
-                .as_leaf_mut()
-                .unwrap()
-                .quantity = new_quantity;
-        }
-        for (component, key) in matched_order_deletes {
-            let _removed_leaf = opposing_bookside.remove_by_key(component, key).unwrap();
-        }
+            - (taker_fee * I80F48::from_num(total_quote_taken_lots_wo_self)).to_num::<i64>();
 
         //
         // Place remainder on the book if requested
@@ -325,13 +420,8 @@ impl<'a> Orderbook<'a> {
             price_lots
         };
         // If there are still quantity unmatched, place on the book
-        let book_base_quantity_lots = if market.maker_fee.is_positive() {
-            // Subtract fees
-            remaining_quote_lots -= remaining_quote_lots * market.maker_fee.to_num::<i64>();
-            remaining_base_lots.min(remaining_quote_lots / price)
-        } else {
-            remaining_base_lots.min(remaining_quote_lots / price)
-        };
+        let book_base_quantity_lots =
+            market.max_base_lots_to_post(remaining_base_lots, remaining_quote_lots, price);
 
         if book_base_quantity_lots <= 0 {
             post_target = None;
@@ -340,6 +430,16 @@ impl<'a> Orderbook<'a> {
         let mut maker_fees = 0;
 
         if let Some(order_tree_target) = post_target {
+            // Orders can't be posted too far from the oracle and stable prices
+            if let Some(band_limit_lots) =
+                market.price_band_limit_lots(side, market.maker_price_band_bps, oracle_price)
+            {
+                require!(
+                    side.is_price_within_limit(price_lots, band_limit_lots),
+                    OpenBookError::PriceOutsideBand
+                );
+            }
+
             // Subtract maker fees in bid.
             if market.maker_fee.is_positive() && side == Side::Bid {
                 let book_price = match order_tree_target {
@@ -364,7 +464,7 @@ impl<'a> Orderbook<'a> {
                     now_ts,
                     event_queue.header.seq_num,
                     expired_order.owner,
-                    expired_order.quantity,
+                    expired_order.total_quantity(),
                 );
                 process_out_event(
                     event,
@@ -391,7 +491,7 @@ impl<'a> Orderbook<'a> {
                     now_ts,
                     event_queue.header.seq_num,
                     worst_order.owner,
-                    worst_order.quantity,
+                    worst_order.total_quantity(),
                 );
                 process_out_event(
                     event,
@@ -406,17 +506,29 @@ impl<'a> Orderbook<'a> {
             // Open orders always exists in this case, unwrap
             let open_orders = open_orders_acc.as_mut().unwrap();
             let owner_slot = open_orders.next_order_slot()?;
-            let new_order = LeafNode::new(
+            let display_base_lots = order.display_base_lots();
+            let visible_base_lots = if display_base_lots > 0 {
+                display_base_lots.min(book_base_quantity_lots)
+            } else {
+                book_base_quantity_lots
+            };
+            let mut new_order = LeafNode::new(
                 owner_slot as u8,
                 order_id,
                 *owner,
-                book_base_quantity_lots,
+                visible_base_lots,
                 now_ts,
-                PostOrderType::Limit, // TODO: Support order types? needed?
+                order.post_order_type().unwrap(),
                 order.time_in_force,
                 order.peg_limit(),
                 order.client_order_id,
+                order.max_oracle_staleness_slots(),
             );
+            new_order.self_trade_behavior = order.self_trade_behavior.into();
+            if display_base_lots > 0 {
+                new_order.display_quantity = display_base_lots;
+                new_order.hidden_quantity = book_base_quantity_lots - visible_base_lots;
+            }
             let _result = bookside.insert_leaf(order_tree_target, &new_order)?;
 
             // TODO OPT remove if PlaceOrder needs more compute
@@ -453,6 +565,7 @@ impl<'a> Orderbook<'a> {
             total_quote_taken_native,
             referrer_amount,
             maker_fees,
+            taker_fee_remainder,
         })
     }
 
@@ -462,6 +575,7 @@ impl<'a> Orderbook<'a> {
     pub fn cancel_all_orders(
         &mut self,
         open_orders_acc: &mut OpenOrdersAccountRefMut,
+        open_orders_account_pk: Pubkey,
         market: Market,
         mut limit: u8,
         side_to_cancel_option: Option<Side>,
@@ -478,8 +592,13 @@ impl<'a> Orderbook<'a> {
 
             let order_id = oo.id;
 
-            let cancel_result =
-                self.cancel_order(open_orders_acc, order_id, order_side_and_tree, market, None);
+            let cancel_result = self.cancel_order(
+                open_orders_acc,
+                order_id,
+                order_side_and_tree,
+                market,
+                Some(open_orders_account_pk),
+            );
             if cancel_result.is_anchor_error_with_code(OpenBookError::OrderIdNotFound.into()) {
                 // It's possible for the order to be filled or expired already.
                 // There will be an event on the queue, the perp order slot is freed once
@@ -511,18 +630,122 @@ impl<'a> Orderbook<'a> {
     ) -> Result<LeafNode> {
         let side = side_and_tree.side();
         let book_component = side_and_tree.order_tree();
-        let leaf_node = self.bookside_mut(side).
-        remove_by_key(book_component, order_id).ok_or_else(|| {
-            // possibly already filled or expired?
-            error_msg_typed!(OpenBookError::OrderIdNotFound, "no order with id {order_id}, side {side:?}, component {book_component:?} found on the orderbook")
-        })?;
+        let key = self
+            .find_order_key(open_orders_acc, order_id, side_and_tree, expected_owner)
+            .ok_or_else(|| {
+                // possibly already filled or expired?
+                error_msg_typed!(OpenBookError::OrderIdNotFound, "no order with id {order_id}, side {side:?}, component {book_component:?} found on the orderbook")
+            })?;
+        let leaf_node = self
+            .bookside_mut(side)
+            .remove_by_key(book_component, key)
+            .unwrap();
         if let Some(owner) = expected_owner {
             require_keys_eq!(leaf_node.owner, owner);
         }
-        open_orders_acc.cancel_order(leaf_node.owner_slot as usize, leaf_node.quantity, market)?;
+        open_orders_acc.cancel_order(
+            leaf_node.owner_slot as usize,
+            leaf_node.total_quantity(),
+            market,
+        )?;
 
         Ok(leaf_node)
     }
+
+    /// Key of an order of the open orders account on the book.
+    ///
+    /// Replenished iceberg orders get a new key that the account only learns about
+    /// once the fill event is processed. Until then, orders of a known `owner` that
+    /// aren't found by `order_id` are looked up by their owner slot and price.
+    fn find_order_key(
+        &self,
+        open_orders_acc: &OpenOrdersAccountRefMut,
+        order_id: u128,
+        side_and_tree: SideAndOrderTree,
+        owner: Option<Pubkey>,
+    ) -> Option<u128> {
+        let bookside = self.bookside(side_and_tree.side());
+        let root = bookside.root(side_and_tree.order_tree());
+        if bookside.nodes.find_by_key(root, order_id).is_some() {
+            return Some(order_id);
+        }
+
+        let owner = owner?;
+        let owner_slot = open_orders_acc
+            .all_orders()
+            .position(|oo| oo.id == order_id)?;
+        let price_data = (order_id >> 64) as u64;
+        bookside
+            .nodes
+            .iter(root)
+            .find(|(_, leaf)| {
+                leaf.owner == owner
+                    && leaf.owner_slot as usize == owner_slot
+                    && leaf.price_data() == price_data
+            })
+            .map(|(_, leaf)| leaf.key)
+    }
+
+    /// Reduce an order on the book to `quantity` lots, keeping its priority.
+    ///
+    /// Returns false without changing anything if the order isn't larger than `quantity`,
+    /// or if it was placed with another order type, self trade behavior or expiry than `order`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn reduce_order(
+        &mut self,
+        open_orders_acc: &mut OpenOrdersAccountRefMut,
+        order_id: u128,
+        side_and_tree: SideAndOrderTree,
+        order: &Order,
+        price_lots: i64,
+        quantity: i64,
+        now_ts: u64,
+        market: Market,
+        expected_owner: Pubkey,
+    ) -> Result<bool> {
+        require_gt!(quantity, 0);
+        let side = side_and_tree.side();
+        let book_component = side_and_tree.order_tree();
+        let key = self
+            .find_order_key(open_orders_acc, order_id, side_and_tree, Some(expected_owner))
+            .ok_or_else(|| {
+                error_msg_typed!(OpenBookError::OrderIdNotFound, "no order with id {order_id}, side {side:?}, component {book_component:?} found on the orderbook")
+            })?;
+        let bookside = self.bookside_mut(side);
+        let handle = bookside
+            .nodes
+            .find_by_key(bookside.root(book_component), key)
+            .unwrap();
+        let leaf_node = bookside.node_mut(handle).unwrap().as_leaf_mut().unwrap();
+        require_keys_eq!(leaf_node.owner, expected_owner);
+        // Only fixed orders keep their price, iceberg orders can't be reduced in place
+        if book_component != BookSideOrderTree::Fixed
+            || fixed_price_lots(leaf_node.price_data()) != price_lots
+            || leaf_node.display_quantity > 0
+            || leaf_node.quantity <= quantity
+        {
+            return Ok(false);
+        }
+        // The reduced order keeps everything but its size and client order id
+        if order.post_order_type().map(u8::from) != Some(leaf_node.order_type)
+            || u8::from(order.self_trade_behavior) != leaf_node.self_trade_behavior
+            || order.expiry(now_ts) != leaf_node.expiry()
+        {
+            return Ok(false);
+        }
+
+        let reduced_quantity = leaf_node.quantity - quantity;
+        leaf_node.quantity = quantity;
+        leaf_node.client_order_id = order.client_order_id;
+        open_orders_acc.reduce_order(
+            leaf_node.owner_slot as usize,
+            reduced_quantity,
+            order.client_order_id,
+            market,
+        )?;
+
+        Ok(true)
+    }
 }
 
 pub fn process_out_event(
@@ -570,6 +793,7 @@ pub fn process_fill_event(
 }
 
 /// Release funds and apply taker fees to the taker account. Account fees for referrer
+#[allow(clippy::too_many_arguments)]
 fn release_funds_fees(
     taker_side: Side,
     market: &mut Market,
@@ -577,7 +801,15 @@ fn release_funds_fees(
     base_native: u64,
     quote_native: u64,
     taker_fees: u64,
+    referrer_rebate: u64,
+    now_ts: u64,
 ) -> Result<()> {
+    open_orders_acc.fixed_mut().accrue_buyback_fees(
+        taker_fees,
+        now_ts,
+        market.buyback_fees_expiry_interval,
+    );
+
     let pa = &mut open_orders_acc.fixed_mut().position;
     // Update free_lots
     match taker_side {
@@ -590,10 +822,15 @@ fn release_funds_fees(
     };
 
     // Referrer rebates
-    pa.referrer_rebates_accrued += market.referrer_taker_rebate(quote_native);
-    market.referrer_rebates_accrued += market.referrer_taker_rebate(quote_native);
-
-    open_orders_acc.fixed.position.taker_volume += taker_fees;
+    pa.referrer_rebates_accrued += referrer_rebate;
+    market.referrer_rebates_accrued += referrer_rebate;
+
+    open_orders_acc.fixed.position.taker_volume += quote_native;
+    open_orders_acc.fixed_mut().accrue_fee_tier_volume(
+        quote_native,
+        now_ts,
+        market.fee_tier_volume_interval,
+    );
 
     Ok(())
 }
@@ -49,14 +49,21 @@ impl BookSide {
     pub fn iter_valid(
         &self,
         now_ts: u64,
-        oracle_price_lots: i64,
+        oracle_price_lots: Option<i64>,
+        oracle_staleness_slots: u64,
     ) -> impl Iterator<Item = BookSideIterItem> {
-        BookSideIter::new(self, now_ts, oracle_price_lots).filter(|it| it.is_valid())
+        BookSideIter::new(self, now_ts, oracle_price_lots, oracle_staleness_slots)
+            .filter(|it| it.is_valid())
     }
 
     /// Iterate over all entries, including invalid orders
-    pub fn iter_all_including_invalid(&self, now_ts: u64, oracle_price_lots: i64) -> BookSideIter {
-        BookSideIter::new(self, now_ts, oracle_price_lots)
+    pub fn iter_all_including_invalid(
+        &self,
+        now_ts: u64,
+        oracle_price_lots: Option<i64>,
+        oracle_staleness_slots: u64,
+    ) -> BookSideIter {
+        BookSideIter::new(self, now_ts, oracle_price_lots, oracle_staleness_slots)
     }
 
     pub fn node(&self, handle: NodeHandle) -> Option<&AnyNode> {
@@ -79,6 +86,10 @@ impl BookSide {
         self.nodes.is_full()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.roots.iter().all(|root| root.leaf_count == 0)
+    }
+
     pub fn insert_leaf(
         &mut self,
         component: BookSideOrderTree,
@@ -89,7 +100,11 @@ impl BookSide {
     }
 
     /// Remove the overall worst-price order.
-    pub fn remove_worst(&mut self, now_ts: u64, oracle_price_lots: i64) -> Option<(LeafNode, i64)> {
+    pub fn remove_worst(
+        &mut self,
+        now_ts: u64,
+        oracle_price_lots: Option<i64>,
+    ) -> Option<(LeafNode, i64)> {
         let worst_fixed = self.nodes.find_worst(&self.roots[0]);
         let worst_pegged = self.nodes.find_worst(&self.roots[1]);
         let side = self.nodes.order_tree_type().side();
@@ -146,11 +161,12 @@ impl BookSide {
         &self,
         limit_price_lots: i64,
         now_ts: u64,
-        oracle_price_lots: i64,
+        oracle_price_lots: Option<i64>,
+        oracle_staleness_slots: u64,
     ) -> i64 {
         let side = self.side();
         let mut sum = 0;
-        for item in self.iter_valid(now_ts, oracle_price_lots) {
+        for item in self.iter_valid(now_ts, oracle_price_lots, oracle_staleness_slots) {
             if side.is_price_better(limit_price_lots, item.price_lots) {
                 break;
             }
@@ -160,9 +176,14 @@ impl BookSide {
     }
 
     /// Return the price of the order closest to the spread
-    pub fn best_price(&self, now_ts: u64, oracle_price_lots: i64) -> Option<i64> {
+    pub fn best_price(
+        &self,
+        now_ts: u64,
+        oracle_price_lots: Option<i64>,
+        oracle_staleness_slots: u64,
+    ) -> Option<i64> {
         Some(
-            self.iter_valid(now_ts, oracle_price_lots)
+            self.iter_valid(now_ts, oracle_price_lots, oracle_staleness_slots)
                 .next()?
                 .price_lots,
         )
@@ -170,9 +191,15 @@ impl BookSide {
 
     /// Walk up the book `quantity` units and return the price at that level. If `quantity` units
     /// not on book, return None
-    pub fn impact_price(&self, quantity: i64, now_ts: u64, oracle_price_lots: i64) -> Option<i64> {
+    pub fn impact_price(
+        &self,
+        quantity: i64,
+        now_ts: u64,
+        oracle_price_lots: Option<i64>,
+        oracle_staleness_slots: u64,
+    ) -> Option<i64> {
         let mut sum: i64 = 0;
-        for order in self.iter_valid(now_ts, oracle_price_lots) {
+        for order in self.iter_valid(now_ts, oracle_price_lots, oracle_staleness_slots) {
             sum += order.node.quantity;
             if sum >= quantity {
                 return Some(order.price_lots);
@@ -216,6 +243,7 @@ mod tests {
                 0,
                 -1,
                 0,
+                -1,
             )
         };
 
@@ -267,7 +295,7 @@ mod tests {
             let mut total = 0;
             let ascending = order_tree_type == OrderTreeType::Asks;
             let mut last_price = if ascending { 0 } else { i64::MAX };
-            for order in bookside.iter_all_including_invalid(0, oracle_price_lots) {
+            for order in bookside.iter_all_including_invalid(0, Some(oracle_price_lots), 0) {
                 let price = order.price_lots;
                 println!("{} {:?} {price}", order.node.key, order.handle.order_tree);
                 if ascending {
@@ -311,6 +339,7 @@ mod tests {
                 tif,
                 peg_limit,
                 0,
+                -1,
             )
         };
         let mut add_fixed = |price: i64, tif: u16| {
@@ -348,7 +377,7 @@ mod tests {
 
         let order_prices = |now_ts: u64, oracle: i64| -> Vec<i64> {
             bookside
-                .iter_valid(now_ts, oracle)
+                .iter_valid(now_ts, Some(oracle), 0)
                 .map(|it| it.price_lots)
                 .collect()
         };
@@ -375,34 +404,97 @@ mod tests {
         let order_prices = |now_ts: u64, oracle: i64| -> Vec<i64> {
             bookside
                 .borrow()
-                .iter_valid(now_ts, oracle)
+                .iter_valid(now_ts, Some(oracle), 0)
                 .map(|it| it.price_lots)
                 .collect()
         };
 
         // remove pegged order
         assert_eq!(order_prices(0, 100), vec![120, 100, 90, 85, 80]);
-        let (_, p) = bookside.borrow_mut().remove_worst(0, 100).unwrap();
+        let (_, p) = bookside.borrow_mut().remove_worst(0, Some(100)).unwrap();
         assert_eq!(p, 80);
         assert_eq!(order_prices(0, 100), vec![120, 100, 90, 85]);
 
         // remove fixed order (order at 190=200-10 hits the peg limit)
         assert_eq!(order_prices(0, 200), vec![185, 120, 100]);
-        let (_, p) = bookside.borrow_mut().remove_worst(0, 200).unwrap();
+        let (_, p) = bookside.borrow_mut().remove_worst(0, Some(200)).unwrap();
         assert_eq!(p, 100);
         assert_eq!(order_prices(0, 200), vec![185, 120]);
 
         // remove until end
 
         assert_eq!(order_prices(0, 100), vec![120, 90, 85]);
-        let (_, p) = bookside.borrow_mut().remove_worst(0, 100).unwrap();
+        let (_, p) = bookside.borrow_mut().remove_worst(0, Some(100)).unwrap();
         assert_eq!(p, 85);
         assert_eq!(order_prices(0, 100), vec![120, 90]);
-        let (_, p) = bookside.borrow_mut().remove_worst(0, 100).unwrap();
+        let (_, p) = bookside.borrow_mut().remove_worst(0, Some(100)).unwrap();
         assert_eq!(p, 90);
         assert_eq!(order_prices(0, 100), vec![120]);
-        let (_, p) = bookside.borrow_mut().remove_worst(0, 100).unwrap();
+        let (_, p) = bookside.borrow_mut().remove_worst(0, Some(100)).unwrap();
         assert_eq!(p, 120);
         assert_eq!(order_prices(0, 100), Vec::<i64>::new());
     }
+
+    #[test]
+    fn bookside_oracle_staleness() {
+        let side = Side::Bid;
+        let mut order_tree = new_order_tree(OrderTreeType::Bids);
+        let mut root_fixed = OrderTreeRoot::zeroed();
+        let mut root_pegged = OrderTreeRoot::zeroed();
+        let new_node = |key: u128, max_oracle_staleness_slots: i32| {
+            LeafNode::new(
+                0,
+                key,
+                Pubkey::default(),
+                0,
+                1000,
+                PostOrderType::Limit,
+                0,
+                -1,
+                0,
+                max_oracle_staleness_slots,
+            )
+        };
+
+        let key = new_node_key(side, fixed_price_data(100).unwrap(), 0);
+        order_tree
+            .insert_leaf(&mut root_fixed, &new_node(key, -1))
+            .unwrap();
+        let key = new_node_key(side, oracle_pegged_price_data(-10), 0);
+        order_tree
+            .insert_leaf(&mut root_pegged, &new_node(key, 10))
+            .unwrap();
+        let key = new_node_key(side, oracle_pegged_price_data(-15), 0);
+        order_tree
+            .insert_leaf(&mut root_pegged, &new_node(key, -1))
+            .unwrap();
+        let key = new_node_key(side, oracle_pegged_price_data(-20), 0);
+        order_tree
+            .insert_leaf(&mut root_pegged, &new_node(key, -1))
+            .unwrap();
+        let key = new_node_key(side, oracle_pegged_price_data(-25), 0);
+        order_tree
+            .insert_leaf(&mut root_pegged, &new_node(key, 0))
+            .unwrap();
+
+        let bookside = BookSide {
+            roots: [root_fixed, root_pegged],
+            reserved_roots: [OrderTreeRoot::zeroed(); 4],
+            reserved: [0; 256],
+            nodes: order_tree,
+        };
+
+        let order_prices = |oracle_staleness_slots: u64| -> Vec<i64> {
+            bookside
+                .iter_all_including_invalid(0, Some(120), oracle_staleness_slots)
+                .map(|it| it.price_lots)
+                .collect()
+        };
+
+        assert_eq!(order_prices(0), vec![110, 105, 100, 100, 95]);
+        assert_eq!(order_prices(1), vec![110, 105, 100, 100]);
+        assert_eq!(order_prices(10), vec![110, 105, 100, 100]);
+        assert_eq!(order_prices(11), vec![105, 100, 100]);
+        assert_eq!(bookside.best_price(0, Some(120), 11), Some(105));
+    }
 }
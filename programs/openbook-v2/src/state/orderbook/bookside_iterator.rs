@@ -17,7 +17,7 @@ impl<'a> BookSideIterItem<'a> {
 /// walk the orderbook without caring about where an order came from.
 ///
 /// This will skip over orders that are not currently matchable, but might be valid
-/// in the future.
+/// in the future, like oracle pegged orders that don't accept the current oracle staleness.
 ///
 /// This may return invalid orders (tif expired, peg_limit exceeded; see is_valid) which
 /// users are supposed to remove from the orderbook if they can.
@@ -25,11 +25,17 @@ pub struct BookSideIter<'a> {
     fixed_iter: OrderTreeIter<'a>,
     oracle_pegged_iter: OrderTreeIter<'a>,
     now_ts: u64,
-    oracle_price_lots: i64,
+    oracle_price_lots: Option<i64>,
+    oracle_staleness_slots: u64,
 }
 
 impl<'a> BookSideIter<'a> {
-    pub fn new(book_side: &'a BookSide, now_ts: u64, oracle_price_lots: i64) -> Self {
+    pub fn new(
+        book_side: &'a BookSide,
+        now_ts: u64,
+        oracle_price_lots: Option<i64>,
+        oracle_staleness_slots: u64,
+    ) -> Self {
         Self {
             fixed_iter: book_side
                 .nodes
@@ -39,6 +45,7 @@ impl<'a> BookSideIter<'a> {
                 .iter(book_side.root(BookSideOrderTree::OraclePegged)),
             now_ts,
             oracle_price_lots,
+            oracle_staleness_slots,
         }
     }
 }
@@ -128,13 +135,16 @@ pub fn rank_orders<'a>(
     oracle_pegged: Option<(NodeHandle, &'a LeafNode)>,
     return_worse: bool,
     now_ts: u64,
-    oracle_price_lots: i64,
+    oracle_price_lots: Option<i64>,
 ) -> Option<BookSideIterItem<'a>> {
-    // Enrich with data that'll always be needed
-    let oracle_pegged = oracle_pegged.map(|(handle, node)| {
-        let (state, price_lots) = oracle_pegged_price(oracle_price_lots, node, side);
-        (handle, node, price_lots, state)
-    });
+    // Enrich with data that'll always be needed, pegged orders have no price without an oracle
+    let oracle_pegged =
+        oracle_pegged
+            .zip(oracle_price_lots)
+            .map(|((handle, node), oracle_price_lots)| {
+                let (state, price_lots) = oracle_pegged_price(oracle_price_lots, node, side);
+                (handle, node, price_lots, state)
+            });
 
     match (fixed, oracle_pegged) {
         (Some(f), Some(o)) => {
@@ -165,13 +175,23 @@ impl<'a> Iterator for BookSideIter<'a> {
         // Skip all the oracle pegged orders that aren't representable with the current oracle
         // price. Example: iterating asks, but the best ask is at offset -100 with the oracle at 50.
         // We need to skip asks until we find the first that has a price >= 1.
-        let mut o_peek = self.oracle_pegged_iter.peek();
-        while let Some((_, o_node)) = o_peek {
-            if oracle_pegged_price(self.oracle_price_lots, o_node, side).0 != OrderState::Skipped {
-                break;
+        // Orders whose max_oracle_staleness_slots is exceeded by the current oracle are skipped too.
+        // Without an oracle price no oracle pegged order can be matched.
+        let o_peek = if let Some(oracle_price_lots) = self.oracle_price_lots {
+            let mut o_peek = self.oracle_pegged_iter.peek();
+            while let Some((_, o_node)) = o_peek {
+                if oracle_pegged_price(oracle_price_lots, o_node, side).0 != OrderState::Skipped
+                    && !o_node.is_oracle_stale(self.oracle_staleness_slots)
+                {
+                    break;
+                }
+                self.oracle_pegged_iter.next();
+                o_peek = self.oracle_pegged_iter.peek();
             }
-            o_peek = self.oracle_pegged_iter.next()
-        }
+            o_peek
+        } else {
+            None
+        };
 
         let f_peek = self.fixed_iter.peek();
 
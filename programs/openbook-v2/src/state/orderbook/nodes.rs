@@ -5,7 +5,7 @@ use bytemuck::{cast_mut, cast_ref};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use static_assertions::const_assert_eq;
 
-use super::order_type::{PostOrderType, Side};
+use super::order_type::{PostOrderType, SelfTradeBehavior, Side};
 
 pub type NodeHandle = u32;
 const NODE_SIZE: usize = 120;
@@ -153,7 +153,8 @@ pub struct LeafNode {
     /// PostOrderType, this was added for TradingView move order
     pub order_type: u8,
 
-    pub padding: [u8; 1],
+    /// SelfTradeBehavior the order was placed with
+    pub self_trade_behavior: u8,
 
     /// Time in seconds after `timestamp` at which the order expires.
     /// A value of 0 means no expiry.
@@ -182,11 +183,25 @@ pub struct LeafNode {
     /// User defined id for this order, used in FillEvents
     pub client_order_id: u64,
 
-    pub reserved: [u8; 32],
+    /// Oracle pegged orders are skipped while the oracle hasn't been updated for more
+    /// than this many slots. A value of -1 means no limit.
+    ///
+    /// Only applicable in the oracle_pegged OrderTree
+    pub max_oracle_staleness_slots: i32,
+
+    pub padding3: [u8; 4],
+
+    /// Number of base lots shown on the book by an iceberg order, 0 for regular orders
+    pub display_quantity: i64,
+
+    /// Base lots of an iceberg order that aren't shown on the book yet
+    pub hidden_quantity: i64,
+
+    pub reserved: [u8; 8],
 }
 const_assert_eq!(
     size_of::<LeafNode>(),
-    4 + 1 + 1 + 1 + 1 + 16 + 32 + 8 + 8 + 8 + 8 + 32
+    4 + 1 + 1 + 1 + 1 + 16 + 32 + 8 + 8 + 8 + 8 + 4 + 4 + 8 + 8 + 8
 );
 const_assert_eq!(size_of::<LeafNode>(), NODE_SIZE);
 const_assert_eq!(size_of::<LeafNode>() % 8, 0);
@@ -203,12 +218,13 @@ impl LeafNode {
         time_in_force: u16,
         peg_limit: i64,
         client_order_id: u64,
+        max_oracle_staleness_slots: i32,
     ) -> Self {
         Self {
             tag: NodeTag::LeafNode.into(),
             owner_slot,
             order_type: order_type.into(),
-            padding: Default::default(),
+            self_trade_behavior: SelfTradeBehavior::default().into(),
             time_in_force,
             padding2: Default::default(),
             key,
@@ -217,10 +233,20 @@ impl LeafNode {
             timestamp,
             peg_limit,
             client_order_id,
-            reserved: [0; 32],
+            max_oracle_staleness_slots,
+            padding3: Default::default(),
+            display_quantity: 0,
+            hidden_quantity: 0,
+            reserved: [0; 8],
         }
     }
 
+    /// Base lots of the order, including the hidden part of iceberg orders
+    #[inline(always)]
+    pub fn total_quantity(&self) -> i64 {
+        self.quantity + self.hidden_quantity
+    }
+
     /// The order's price_data as stored in the key
     ///
     /// Needs to be unpacked differently for fixed and oracle pegged orders.
@@ -229,6 +255,13 @@ impl LeafNode {
         (self.key >> 64) as u64
     }
 
+    /// Is the oracle too stale for this order to be matched?
+    #[inline(always)]
+    pub fn is_oracle_stale(&self, oracle_staleness_slots: u64) -> bool {
+        self.max_oracle_staleness_slots >= 0
+            && oracle_staleness_slots > self.max_oracle_staleness_slots as u64
+    }
+
     /// Time at which this order will expire, u64::MAX if never
     #[inline(always)]
     pub fn expiry(&self) -> u64 {
@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
 
 use super::*;
+use crate::error::OpenBookError;
+use crate::state::MarketInstruction;
 
 ///  order parameters
 pub struct Order {
@@ -26,14 +28,78 @@ pub struct Order {
     pub params: OrderParams,
 }
 
+/// Parameters of a single order placed with `place_orders`
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy)]
+pub struct PlaceOrderArgs {
+    pub side: Side,
+    pub price_lots: i64,
+    pub max_base_lots: i64,
+    pub max_quote_lots_including_fees: i64,
+    pub client_order_id: u64,
+    pub order_type: PlaceOrderType,
+    pub self_trade_behavior: SelfTradeBehavior,
+    /// Timestamp of when the order expires, 0 for never
+    pub expiry_timestamp: u64,
+}
+
+impl PlaceOrderArgs {
+    /// The order to place, None if it is already expired
+    pub fn to_order(&self) -> Result<Option<Order>> {
+        require_gte!(self.price_lots, 0);
+        let time_in_force = match Order::tif_from_expiry(self.expiry_timestamp) {
+            Some(t) => t,
+            None => return Ok(None),
+        };
+        let price_lots = self.price_lots;
+        Ok(Some(Order {
+            side: self.side,
+            max_base_lots: self.max_base_lots,
+            max_quote_lots_including_fees: self.max_quote_lots_including_fees,
+            client_order_id: self.client_order_id,
+            time_in_force,
+            self_trade_behavior: self.self_trade_behavior,
+            params: match self.order_type {
+                PlaceOrderType::Market => OrderParams::Market,
+                PlaceOrderType::ImmediateOrCancel => OrderParams::ImmediateOrCancel { price_lots },
+                PlaceOrderType::FillOrKill => OrderParams::FillOrKill { price_lots },
+                _ => OrderParams::Fixed {
+                    price_lots,
+                    order_type: self.order_type.to_post_order_type()?,
+                    display_base_lots: 0,
+                },
+            },
+        }))
+    }
+
+    /// The order to place showing at most `display_base_lots` on the book, None if it is
+    /// already expired
+    pub fn to_iceberg_order(&self, display_base_lots: i64) -> Result<Option<Order>> {
+        require_gt!(display_base_lots, 0);
+        let order_type = self.order_type.to_post_order_type()?;
+        Ok(self.to_order()?.map(|order| Order {
+            params: OrderParams::Fixed {
+                price_lots: self.price_lots,
+                order_type,
+                display_base_lots,
+            },
+            ..order
+        }))
+    }
+}
+
 pub enum OrderParams {
     Market,
     ImmediateOrCancel {
         price_lots: i64,
     },
+    FillOrKill {
+        price_lots: i64,
+    },
     Fixed {
         price_lots: i64,
         order_type: PostOrderType,
+        /// Base lots shown on the book at a time, 0 to show all
+        display_base_lots: i64,
     },
     OraclePegged {
         price_offset_lots: i64,
@@ -69,14 +135,35 @@ impl Order {
         matches!(self.params, OrderParams::ImmediateOrCancel { .. })
     }
 
+    /// Must this order be filled completely? It will fail otherwise.
+    pub fn is_fill_or_kill(&self) -> bool {
+        matches!(self.params, OrderParams::FillOrKill { .. })
+    }
+
     /// Is this order required to be posted to the orderbook? It will fail if it would take.
     pub fn is_post_only(&self) -> bool {
-        let order_type = match self.params {
-            OrderParams::Fixed { order_type, .. } => order_type,
-            OrderParams::OraclePegged { order_type, .. } => order_type,
-            _ => return false,
-        };
-        order_type == PostOrderType::PostOnly || order_type == PostOrderType::PostOnlySlide
+        matches!(
+            self.post_order_type(),
+            Some(PostOrderType::PostOnly | PostOrderType::PostOnlySlide)
+        )
+    }
+
+    /// Order type of orders that can rest on the book
+    pub fn post_order_type(&self) -> Option<PostOrderType> {
+        match self.params {
+            OrderParams::Fixed { order_type, .. } => Some(order_type),
+            OrderParams::OraclePegged { order_type, .. } => Some(order_type),
+            _ => None,
+        }
+    }
+
+    /// Time at which the order would expire if it was posted at `now_ts`, u64::MAX if never
+    pub fn expiry(&self, now_ts: u64) -> u64 {
+        if self.time_in_force == 0 {
+            u64::MAX
+        } else {
+            now_ts + self.time_in_force as u64
+        }
     }
 
     /// Order tree that this order should be added to
@@ -93,16 +180,18 @@ impl Order {
     fn price_for_order_type(
         &self,
         now_ts: u64,
-        oracle_price_lots: i64,
+        oracle_price_lots: Option<i64>,
+        oracle_staleness_slots: u64,
         price_lots: i64,
         order_type: PostOrderType,
         order_book: &Orderbook,
     ) -> i64 {
         if order_type == PostOrderType::PostOnlySlide {
-            if let Some(best_other_price) = order_book
-                .bookside(self.side.invert_side())
-                .best_price(now_ts, oracle_price_lots)
-            {
+            if let Some(best_other_price) = order_book.bookside(self.side.invert_side()).best_price(
+                now_ts,
+                oracle_price_lots,
+                oracle_staleness_slots,
+            ) {
                 post_only_slide_limit(self.side, best_other_price, price_lots)
             } else {
                 price_lots
@@ -117,18 +206,22 @@ impl Order {
     pub fn price(
         &self,
         now_ts: u64,
-        oracle_price_lots: i64,
+        oracle_price_lots: Option<i64>,
+        oracle_staleness_slots: u64,
         order_book: &Orderbook,
     ) -> Result<(i64, u64)> {
         let price_lots = match self.params {
             OrderParams::Market => market_order_limit_for_side(self.side),
             OrderParams::ImmediateOrCancel { price_lots } => price_lots,
+            OrderParams::FillOrKill { price_lots } => price_lots,
             OrderParams::Fixed {
                 price_lots,
                 order_type,
+                ..
             } => self.price_for_order_type(
                 now_ts,
                 oracle_price_lots,
+                oracle_staleness_slots,
                 price_lots,
                 order_type,
                 order_book,
@@ -138,10 +231,13 @@ impl Order {
                 order_type,
                 ..
             } => {
+                let oracle_price_lots =
+                    oracle_price_lots.ok_or(OpenBookError::DisabledOraclePeg)?;
                 let price_lots = oracle_price_lots + price_offset_lots;
                 self.price_for_order_type(
                     now_ts,
-                    oracle_price_lots,
+                    Some(oracle_price_lots),
+                    oracle_staleness_slots,
                     price_lots,
                     order_type,
                     order_book,
@@ -150,7 +246,8 @@ impl Order {
         };
         let price_data = match self.params {
             OrderParams::OraclePegged { .. } => {
-                oracle_pegged_price_data(price_lots - oracle_price_lots)
+                // oracle_price_lots was checked to be set above
+                oracle_pegged_price_data(price_lots - oracle_price_lots.unwrap())
             }
             _ => fixed_price_data(price_lots)?,
         };
@@ -158,6 +255,24 @@ impl Order {
         Ok((price_lots, price_data))
     }
 
+    /// Instruction that needs to be enabled on the market to place this order
+    pub fn market_instruction(&self) -> MarketInstruction {
+        match self.params {
+            OrderParams::OraclePegged { .. } => MarketInstruction::PlaceOrderPegged,
+            _ => MarketInstruction::PlaceOrder,
+        }
+    }
+
+    /// Base lots an iceberg order shows on the book, 0 to show all
+    pub fn display_base_lots(&self) -> i64 {
+        match self.params {
+            OrderParams::Fixed {
+                display_base_lots, ..
+            } => display_base_lots,
+            _ => 0,
+        }
+    }
+
     /// pegging limit for oracle peg orders, otherwise -1
     pub fn peg_limit(&self) -> i64 {
         match self.params {
@@ -165,6 +280,17 @@ impl Order {
             _ => -1,
         }
     }
+
+    /// oracle staleness limit for oracle peg orders, otherwise -1
+    pub fn max_oracle_staleness_slots(&self) -> i32 {
+        match self.params {
+            OrderParams::OraclePegged {
+                max_oracle_staleness_slots,
+                ..
+            } => max_oracle_staleness_slots,
+            _ => -1,
+        }
+    }
 }
 
 /// The implicit limit price to use for market orders
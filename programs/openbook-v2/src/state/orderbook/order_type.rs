@@ -39,6 +39,10 @@ pub enum PlaceOrderType {
     /// If existing orders match with this order, adjust the price to just barely
     /// not match. Always places an order on the book.
     PostOnlySlide = 4,
+
+    /// Take existing orders up to price, max_base_quantity and max_quote_quantity.
+    /// Fails unless max_base_quantity is filled completely. Never place an order on the book.
+    FillOrKill = 5,
 }
 
 impl PlaceOrderType {
@@ -46,6 +50,7 @@ impl PlaceOrderType {
         match *self {
             Self::Market => Err(error_msg!("Market is not a PostOrderType")),
             Self::ImmediateOrCancel => Err(error_msg!("ImmediateOrCancel is not a PostOrderType")),
+            Self::FillOrKill => Err(error_msg!("FillOrKill is not a PostOrderType")),
             Self::Limit => Ok(PostOrderType::Limit),
             Self::PostOnly => Ok(PostOrderType::PostOnly),
             Self::PostOnlySlide => Ok(PostOrderType::PostOnlySlide),
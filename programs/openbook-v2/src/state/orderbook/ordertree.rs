@@ -193,6 +193,17 @@ impl OrderTreeNodes {
         }
     }
 
+    /// Returns the handle of the leaf with `search_key`, if there is one
+    pub fn find_by_key(&self, root: &OrderTreeRoot, search_key: u128) -> Option<NodeHandle> {
+        let mut node_h = root.node()?;
+        loop {
+            match self.node(node_h).unwrap().case().unwrap() {
+                NodeRef::Inner(inner) => node_h = inner.walk_down(search_key).0,
+                NodeRef::Leaf(leaf) => return (leaf.key == search_key).then_some(node_h),
+            }
+        }
+    }
+
     pub fn remove_by_key(
         &mut self,
         root: &mut OrderTreeRoot,
@@ -552,6 +563,7 @@ mod tests {
                 1,
                 -1,
                 0,
+                -1,
             )
         };
 
@@ -645,6 +657,7 @@ mod tests {
                 1,
                 -1,
                 0,
+                -1,
             )
         };
 
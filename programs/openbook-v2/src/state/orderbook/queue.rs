@@ -179,12 +179,17 @@ pub struct FillEvent {
     pub taker_side: u8, // Side, from the taker's POV
     pub maker_out: u8,  // 1 if maker order quantity == 0
     pub maker_slot: u8,
-    pub padding: [u8; 4],
+    /// Fee tier the taker paid, see `Market::fee_tiers`
+    pub taker_fee_tier: u8,
+    pub padding: [u8; 3],
     pub timestamp: u64,
     pub seq_num: u64,
 
     pub maker: Pubkey,
-    pub padding2: [u8; 32],
+    /// Id of the maker order that was matched
+    pub maker_order_id: u128,
+    /// New id of an iceberg maker order that replenished its visible quantity, 0 otherwise
+    pub maker_replenished_order_id: u128,
 
     // Timestamp of when the maker order was placed; copied over from the LeafNode
     pub maker_timestamp: u64,
@@ -211,21 +216,27 @@ impl FillEvent {
         timestamp: u64,
         seq_num: u64,
         maker: Pubkey,
+        maker_order_id: u128,
+        maker_replenished_order_id: u128,
         maker_client_order_id: u64,
         maker_timestamp: u64,
         taker: Pubkey,
         taker_client_order_id: u64,
         price: i64,
         quantity: i64,
+        taker_fee_tier: u8,
     ) -> FillEvent {
         Self {
             event_type: EventType::Fill as u8,
             taker_side: taker_side.into(),
             maker_out: maker_out.into(),
             maker_slot,
+            taker_fee_tier,
             timestamp,
             seq_num,
             maker,
+            maker_order_id,
+            maker_replenished_order_id,
             maker_client_order_id,
             maker_timestamp,
             taker,
@@ -233,7 +244,6 @@ impl FillEvent {
             price,
             quantity,
             padding: Default::default(),
-            padding2: Default::default(),
             padding3: Default::default(),
             padding4: Default::default(),
             reserved: [0; 8],
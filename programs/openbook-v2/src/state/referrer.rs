@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use static_assertions::const_assert_eq;
+use std::mem::size_of;
+
+use crate::error::*;
+
+/// Registry entry of a referrer, at the PDA of its referrer id. Referrer rebates are
+/// only paid to the quote token account registered here.
+#[account(zero_copy)]
+#[derive(Debug)]
+pub struct Referrer {
+    pub referrer_id: u64,
+    /// Can change the registered token account
+    pub authority: Pubkey,
+    pub token_account: Pubkey,
+    /// Mint of `token_account`, rebates can only be paid on markets with this quote mint
+    pub mint: Pubkey,
+    pub bump: u8,
+    pub padding: [u8; 7],
+    pub reserved: [u8; 64],
+}
+const_assert_eq!(size_of::<Referrer>(), 8 + 3 * 32 + 1 + 7 + 64);
+const_assert_eq!(size_of::<Referrer>(), 176);
+const_assert_eq!(size_of::<Referrer>() % 8, 0);
+
+impl Referrer {
+    pub fn address(referrer_id: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"Referrer".as_ref(), &referrer_id.to_le_bytes()],
+            &crate::id(),
+        )
+    }
+}
+
+/// Validates the optional referrer accounts of an instruction, which have to be passed
+/// together. Returns the referrer and the token account its rebates go to.
+pub fn referrer_token_account<'a, 'info>(
+    referrer: Option<&AccountLoader<'info, Referrer>>,
+    token_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    quote_mint: &Pubkey,
+) -> Result<Option<(Pubkey, &'a InterfaceAccount<'info, TokenAccount>)>> {
+    match (referrer, token_account) {
+        (Some(referrer), Some(token_account)) => {
+            let referrer_data = referrer.load()?;
+            require_keys_eq!(
+                referrer_data.token_account,
+                token_account.key(),
+                OpenBookError::InvalidReferrer
+            );
+            require_keys_eq!(
+                referrer_data.mint,
+                *quote_mint,
+                OpenBookError::InvalidReferrer
+            );
+            Ok(Some((referrer.key(), token_account)))
+        }
+        (None, None) => Ok(None),
+        _ => err!(OpenBookError::InvalidReferrer),
+    }
+}
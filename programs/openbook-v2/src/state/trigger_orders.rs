@@ -0,0 +1,201 @@
+use anchor_lang::prelude::*;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use static_assertions::const_assert_eq;
+use std::mem::size_of;
+
+use crate::error::*;
+use crate::state::*;
+
+pub const MAX_TRIGGER_ORDERS: usize = 8;
+
+/// Lamports paid by the owner when placing a trigger order, and paid out to
+/// whoever executes it
+pub const TRIGGER_ORDER_BOUNTY_LAMPORTS: u64 = 10_000;
+
+#[derive(
+    Eq,
+    PartialEq,
+    Copy,
+    Clone,
+    TryFromPrimitive,
+    IntoPrimitive,
+    Debug,
+    AnchorSerialize,
+    AnchorDeserialize,
+)]
+#[repr(u8)]
+pub enum TriggerCondition {
+    /// The oracle price is at or above the trigger price
+    OraclePriceAbove = 0,
+    /// The oracle price is at or below the trigger price
+    OraclePriceBelow = 1,
+    /// The price of the last trade on the market is at or above the trigger price
+    LastTradePriceAbove = 2,
+    /// The price of the last trade on the market is at or below the trigger price
+    LastTradePriceBelow = 3,
+}
+
+impl TriggerCondition {
+    /// Is the condition met for prices in lots? Missing prices never meet it.
+    pub fn is_met(
+        &self,
+        trigger_price_lots: i64,
+        oracle_price_lots: Option<i64>,
+        last_trade_price_lots: Option<i64>,
+    ) -> bool {
+        let price_lots = match self {
+            Self::OraclePriceAbove | Self::OraclePriceBelow => oracle_price_lots,
+            Self::LastTradePriceAbove | Self::LastTradePriceBelow => last_trade_price_lots,
+        };
+        match (self, price_lots) {
+            (_, None) => false,
+            (Self::OraclePriceAbove | Self::LastTradePriceAbove, Some(price_lots)) => {
+                price_lots >= trigger_price_lots
+            }
+            (Self::OraclePriceBelow | Self::LastTradePriceBelow, Some(price_lots)) => {
+                price_lots <= trigger_price_lots
+            }
+        }
+    }
+}
+
+/// An order that rests off the book until its trigger condition is met
+#[zero_copy]
+#[derive(Debug)]
+pub struct TriggerOrder {
+    pub is_active: u8,
+    pub condition: u8, // TriggerCondition -- enums aren't POD
+    pub side: u8,
+    pub order_type: u8,
+    pub self_trade_behavior: u8,
+    /// Maximum number of orders from the book to fill once triggered
+    pub limit: u8,
+    pub padding: [u8; 2],
+
+    pub trigger_price_lots: i64,
+
+    pub price_lots: i64,
+    pub max_base_lots: i64,
+    pub max_quote_lots_including_fees: i64,
+    pub client_order_id: u64,
+    pub expiry_timestamp: u64,
+
+    /// Funds deposited for the order, base for asks and quote for bids
+    pub locked_native: u64,
+    pub bounty_lamports: u64,
+    /// The open orders admin who signed the placement, default if the market had none
+    pub open_orders_admin: Pubkey,
+
+    pub reserved: [u8; 24],
+}
+const_assert_eq!(size_of::<TriggerOrder>(), 8 + 8 * 8 + 32 + 24);
+const_assert_eq!(size_of::<TriggerOrder>(), 128);
+const_assert_eq!(size_of::<TriggerOrder>() % 8, 0);
+
+impl Default for TriggerOrder {
+    fn default() -> Self {
+        Self {
+            is_active: 0,
+            condition: TriggerCondition::OraclePriceAbove.into(),
+            side: Side::Bid.into(),
+            order_type: PlaceOrderType::Limit.into(),
+            self_trade_behavior: SelfTradeBehavior::DecrementTake.into(),
+            limit: 0,
+            padding: Default::default(),
+            trigger_price_lots: 0,
+            price_lots: 0,
+            max_base_lots: 0,
+            max_quote_lots_including_fees: 0,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            locked_native: 0,
+            bounty_lamports: 0,
+            open_orders_admin: Pubkey::default(),
+            reserved: [0; 24],
+        }
+    }
+}
+
+impl TriggerOrder {
+    pub fn open_orders_admin(&self) -> Option<Pubkey> {
+        (self.open_orders_admin != Pubkey::default()).then_some(self.open_orders_admin)
+    }
+
+    pub fn condition(&self) -> TriggerCondition {
+        TriggerCondition::try_from(self.condition).unwrap()
+    }
+
+    pub fn side(&self) -> Side {
+        Side::try_from(self.side).unwrap()
+    }
+
+    /// The order to place once triggered
+    pub fn order_args(&self) -> PlaceOrderArgs {
+        PlaceOrderArgs {
+            side: self.side(),
+            price_lots: self.price_lots,
+            max_base_lots: self.max_base_lots,
+            max_quote_lots_including_fees: self.max_quote_lots_including_fees,
+            client_order_id: self.client_order_id,
+            order_type: PlaceOrderType::try_from(self.order_type).unwrap(),
+            self_trade_behavior: SelfTradeBehavior::try_from(self.self_trade_behavior).unwrap(),
+            expiry_timestamp: self.expiry_timestamp,
+        }
+    }
+}
+
+#[account(zero_copy)]
+pub struct TriggerOrdersAccount {
+    pub open_orders_account: Pubkey,
+    pub market: Pubkey,
+    pub bump: u8,
+    pub padding: [u8; 7],
+    pub trigger_orders: [TriggerOrder; MAX_TRIGGER_ORDERS],
+    pub reserved: [u8; 128],
+}
+const_assert_eq!(
+    size_of::<TriggerOrdersAccount>(),
+    32 + 32 + 1 + 7 + size_of::<TriggerOrder>() * MAX_TRIGGER_ORDERS + 128
+);
+const_assert_eq!(size_of::<TriggerOrdersAccount>(), 1224);
+const_assert_eq!(size_of::<TriggerOrdersAccount>() % 8, 0);
+
+impl TriggerOrdersAccount {
+    pub fn next_trigger_order_slot(&self) -> Result<usize> {
+        self.trigger_orders
+            .iter()
+            .position(|order| order.is_active == 0)
+            .ok_or_else(|| OpenBookError::TooManyTriggerOrders.into())
+    }
+
+    pub fn has_active_trigger_orders(&self) -> bool {
+        self.trigger_orders.iter().any(|order| order.is_active != 0)
+    }
+
+    pub fn active_trigger_order(&self, slot: u8) -> Result<&TriggerOrder> {
+        self.trigger_orders
+            .get(slot as usize)
+            .filter(|order| order.is_active != 0)
+            .ok_or_else(|| OpenBookError::TriggerOrderNotFound.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigger_condition() {
+        let oracle_above = TriggerCondition::OraclePriceAbove;
+        assert!(oracle_above.is_met(100, Some(100), None));
+        assert!(oracle_above.is_met(100, Some(101), Some(50)));
+        assert!(!oracle_above.is_met(100, Some(99), Some(150)));
+        assert!(!oracle_above.is_met(100, None, Some(150)));
+
+        let last_trade_below = TriggerCondition::LastTradePriceBelow;
+        assert!(last_trade_below.is_met(100, None, Some(100)));
+        assert!(last_trade_below.is_met(100, Some(150), Some(99)));
+        assert!(!last_trade_below.is_met(100, Some(50), Some(101)));
+        assert!(!last_trade_below.is_met(100, Some(50), None));
+    }
+}
@@ -0,0 +1,336 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::system_program;
+use anchor_spl::token::{accessor, spl_token};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::{
+            instruction::harvest_withheld_tokens_to_mint, TransferFeeAmount, TransferFeeConfig,
+        },
+        *,
+    },
+};
+use anchor_spl::token_interface::{
+    self, CloseAccount, InitializeAccount3, Mint, SyncNative, TransferChecked,
+};
+
+use crate::error::*;
+
+const ONE_IN_BASIS_POINTS: u128 = 10_000;
+
+/// Amount that has to be sent so that exactly `amount` arrives at the destination,
+/// taking the transfer fee of Token-2022 mints for the current epoch into account.
+pub fn amount_with_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != spl_token_2022::ID || amount == 0 {
+        return Ok(amount);
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let transfer_fee = match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => *transfer_fee_config.get_epoch_fee(Clock::get()?.epoch),
+        Err(_) => return Ok(amount),
+    };
+
+    pre_fee_amount(
+        u16::from(transfer_fee.transfer_fee_basis_points),
+        u64::from(transfer_fee.maximum_fee),
+        amount,
+    )
+    .ok_or_else(|| error!(OpenBookError::TransferFeeNotCovered))
+}
+
+/// Smallest amount that still leaves `amount` after the fee, which the token program
+/// rounds up and caps at `maximum_fee`
+fn pre_fee_amount(fee_basis_points: u16, maximum_fee: u64, amount: u64) -> Option<u64> {
+    let fee_basis_points = fee_basis_points as u128;
+    if fee_basis_points == 0 {
+        return Some(amount);
+    }
+    if fee_basis_points >= ONE_IN_BASIS_POINTS {
+        return None;
+    }
+
+    let denominator = ONE_IN_BASIS_POINTS - fee_basis_points;
+    let raw_pre_fee_amount = (amount as u128 * ONE_IN_BASIS_POINTS + denominator - 1) / denominator;
+    if raw_pre_fee_amount - amount as u128 >= maximum_fee as u128 {
+        amount.checked_add(maximum_fee)
+    } else {
+        u64::try_from(raw_pre_fee_amount).ok()
+    }
+}
+
+/// Transfers `amount` from a user's token account into a market vault. The transfer fee
+/// of the mint, if any, is paid on top so that the vault receives exactly `amount`.
+pub fn token_transfer_to_vault<'info>(
+    amount: u64,
+    token_program: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: &AccountInfo<'info>,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let mint_info = mint.to_account_info();
+    let transfer_amount = amount_with_transfer_fee(&mint_info, amount)?;
+    let vault_amount_before = accessor::amount(vault)?;
+
+    let cpi_context = CpiContext::new(
+        token_program.clone(),
+        TransferChecked {
+            from: from.clone(),
+            mint: mint_info,
+            to: vault.clone(),
+            authority: authority.clone(),
+        },
+    );
+    token_interface::transfer_checked(cpi_context, transfer_amount, mint.decimals)?;
+
+    // Deposit totals are tracked with what the vault actually received
+    let received = accessor::amount(vault)? - vault_amount_before;
+    require_eq!(received, amount, OpenBookError::TransferFeeNotCovered);
+
+    Ok(())
+}
+
+/// Transfers `amount` out of a market vault, signed by the market. Transfer fees of the
+/// mint are withheld from what the receiver gets.
+pub fn token_transfer_from_vault<'info>(
+    amount: u64,
+    token_program: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    market: &AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let cpi_context = CpiContext::new(
+        token_program.clone(),
+        TransferChecked {
+            from: vault.clone(),
+            mint: mint.to_account_info(),
+            to: to.clone(),
+            authority: market.clone(),
+        },
+    );
+    token_interface::transfer_checked(cpi_context.with_signer(signer), amount, mint.decimals)
+}
+
+/// Moves the transfer fees withheld in a Token-2022 vault to its mint, where the
+/// withdraw authority of the mint can collect them. Accounts holding withheld fees
+/// can't be closed.
+pub fn harvest_withheld_fees<'info>(
+    token_program: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+) -> Result<()> {
+    if *vault.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let withheld_amount = {
+        let vault_data = vault.try_borrow_data()?;
+        let vault_state =
+            StateWithExtensions::<spl_token_2022::state::Account>::unpack(&vault_data)?;
+        match vault_state.get_extension::<TransferFeeAmount>() {
+            Ok(transfer_fee_amount) => u64::from(transfer_fee_amount.withheld_amount),
+            Err(_) => 0,
+        }
+    };
+    if withheld_amount == 0 {
+        return Ok(());
+    }
+
+    let instruction =
+        harvest_withheld_tokens_to_mint(token_program.key, &mint.key(), &[vault.key])?;
+    invoke(
+        &instruction,
+        &[mint.to_account_info(), vault.clone(), token_program.clone()],
+    )?;
+    Ok(())
+}
+
+/// Wrapped SOL mint of either token program
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    *mint == spl_token::native_mint::ID || *mint == spl_token_2022::native_mint::ID
+}
+
+/// Wraps `amount` lamports of `owner` directly in a wrapped SOL vault
+pub fn native_transfer_to_vault<'info>(
+    amount: u64,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    require!(
+        is_native_mint(&mint.key()),
+        OpenBookError::NativeSolNotSupported
+    );
+
+    let cpi_context = CpiContext::new(
+        system_program.clone(),
+        system_program::Transfer {
+            from: owner.clone(),
+            to: vault.clone(),
+        },
+    );
+    system_program::transfer(cpi_context, amount)?;
+
+    let cpi_context = CpiContext::new(
+        token_program.clone(),
+        SyncNative {
+            account: vault.clone(),
+        },
+    );
+    token_interface::sync_native(cpi_context)
+}
+
+pub fn native_unwrap_address(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"NativeUnwrap".as_ref(), owner.as_ref()], &crate::id())
+}
+
+/// Pays out `amount` of a wrapped SOL vault as native SOL. The wrapped SOL goes through
+/// a temporary token account of the market, which `owner` funds and gets closed into.
+#[allow(clippy::too_many_arguments)]
+pub fn native_transfer_from_vault<'info>(
+    amount: u64,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    unwrap_account: Option<&AccountInfo<'info>>,
+    owner: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    market: &AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    require!(
+        is_native_mint(&mint.key()),
+        OpenBookError::NativeSolNotSupported
+    );
+    let unwrap_account =
+        unwrap_account.ok_or_else(|| error_msg!("the native unwrap account is missing"))?;
+    let (unwrap_address, bump) = native_unwrap_address(owner.key);
+    require_keys_eq!(unwrap_account.key(), unwrap_address);
+
+    let unwrap_seeds: &[&[u8]] = &[b"NativeUnwrap".as_ref(), owner.key.as_ref(), &[bump]];
+    let space = spl_token::state::Account::LEN;
+    let rent = Rent::get()?.minimum_balance(space);
+    let lamports = unwrap_account.lamports();
+    if lamports == 0 {
+        let cpi_context = CpiContext::new(
+            system_program.clone(),
+            system_program::CreateAccount {
+                from: owner.clone(),
+                to: unwrap_account.clone(),
+            },
+        );
+        system_program::create_account(
+            cpi_context.with_signer(&[unwrap_seeds]),
+            rent,
+            space as u64,
+            token_program.key,
+        )?;
+    } else {
+        // Lamports were sent to the address beforehand, create the account in place
+        if lamports < rent {
+            let cpi_context = CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: owner.clone(),
+                    to: unwrap_account.clone(),
+                },
+            );
+            system_program::transfer(cpi_context, rent - lamports)?;
+        }
+        let cpi_context = CpiContext::new(
+            system_program.clone(),
+            system_program::Allocate {
+                account_to_allocate: unwrap_account.clone(),
+            },
+        );
+        system_program::allocate(cpi_context.with_signer(&[unwrap_seeds]), space as u64)?;
+        let cpi_context = CpiContext::new(
+            system_program.clone(),
+            system_program::Assign {
+                account_to_assign: unwrap_account.clone(),
+            },
+        );
+        system_program::assign(cpi_context.with_signer(&[unwrap_seeds]), token_program.key)?;
+    }
+
+    let cpi_context = CpiContext::new(
+        token_program.clone(),
+        InitializeAccount3 {
+            account: unwrap_account.clone(),
+            mint: mint.to_account_info(),
+            authority: market.clone(),
+        },
+    );
+    token_interface::initialize_account3(cpi_context)?;
+
+    token_transfer_from_vault(
+        amount,
+        token_program,
+        vault,
+        unwrap_account,
+        mint,
+        market,
+        signer,
+    )?;
+
+    let cpi_context = CpiContext::new(
+        token_program.clone(),
+        CloseAccount {
+            account: unwrap_account.clone(),
+            destination: owner.clone(),
+            authority: market.clone(),
+        },
+    );
+    token_interface::close_account(cpi_context.with_signer(signer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fee(fee_basis_points: u16, maximum_fee: u64, pre_fee_amount: u64) -> u64 {
+        let raw_fee = (pre_fee_amount as u128 * fee_basis_points as u128 + 9_999) / 10_000;
+        (raw_fee as u64).min(maximum_fee)
+    }
+
+    #[test]
+    fn test_pre_fee_amount() {
+        assert_eq!(pre_fee_amount(0, 0, 1_000), Some(1_000));
+        assert_eq!(pre_fee_amount(1_000, u64::MAX, 9), Some(10));
+        assert_eq!(pre_fee_amount(1_000, u64::MAX, 10), Some(12));
+        // the maximum fee caps the amount on top
+        assert_eq!(pre_fee_amount(1_000, 5, 1_000), Some(1_005));
+        assert_eq!(pre_fee_amount(10_000, 5, 1_000), None);
+        assert_eq!(pre_fee_amount(1, u64::MAX, u64::MAX), None);
+
+        for (fee_basis_points, maximum_fee) in [(1, u64::MAX), (50, 1_000), (2_500, u64::MAX)] {
+            for amount in [1, 7, 99, 10_000, 123_456_789] {
+                let pre = pre_fee_amount(fee_basis_points, maximum_fee, amount).unwrap();
+                assert_eq!(pre - fee(fee_basis_points, maximum_fee, pre), amount);
+                assert!(pre - 1 - fee(fee_basis_points, maximum_fee, pre - 1) < amount);
+            }
+        }
+    }
+}
@@ -4,7 +4,7 @@ pub use fixed::types::I80F48;
 pub use solana_program_test::*;
 pub use solana_sdk::transport::TransportError;
 
-pub use openbook_v2::{error::OpenBookError, state::*};
+pub use openbook_v2::{error::OpenBookError, state::*, token_utils::native_unwrap_address};
 pub use program_test::*;
 pub use setup::*;
 
@@ -13,10 +13,28 @@ pub use super::program_test;
 pub use utils::assert_equal_fixed_f64 as assert_equal;
 
 mod test;
+mod test_buyback_fees;
+mod test_cancel_orders;
+mod test_disabled_instructions;
+mod test_edit_market;
+mod test_edit_order;
+mod test_fee_tiers;
 mod test_fees;
+mod test_fill_or_kill;
+mod test_iceberg_order;
+mod test_native_sol;
+mod test_open_orders_account;
 mod test_oracle_peg;
 mod test_order_types;
 mod test_permissioned;
 mod test_place_order_remaining;
+mod test_place_orders;
+mod test_price_band;
+mod test_prune_orders;
+mod test_referrer;
 mod test_self_trade;
+mod test_settle_funds_permissionless;
+mod test_stable_price;
 mod test_take_order;
+mod test_token_2022;
+mod test_trigger_order;
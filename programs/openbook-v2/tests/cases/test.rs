@@ -160,8 +160,8 @@ async fn test_simple_settle() -> Result<(), TransportError> {
             open_orders_account: account_0,
             base_vault,
             quote_vault,
-            token_base_account: owner_token_0,
-            token_quote_account: owner_token_1,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
             referrer: None,
         },
     )
@@ -186,8 +186,8 @@ async fn test_simple_settle() -> Result<(), TransportError> {
             open_orders_account: account_1,
             base_vault,
             quote_vault,
-            token_base_account: owner_token_0,
-            token_quote_account: owner_token_1,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
             referrer: None,
         },
     )
@@ -204,17 +204,81 @@ async fn test_simple_settle() -> Result<(), TransportError> {
         assert_eq!(open_orders_account_1.position.quote_free_native, 0);
     }
 
+    // The market can only be closed once the fees are swept too
+    assert_openbook_error(
+        &send_tx(
+            solana,
+            CloseMarketInstruction {
+                close_market_admin,
+                market,
+                sol_destination: owner.pubkey(),
+                token_base_receiver_account: owner_token_0,
+                token_quote_receiver_account: owner_token_1,
+            },
+        )
+        .await,
+        OpenBookError::MarketHasOutstandingBalances.into(),
+        "fees are not swept".to_string(),
+    );
+
+    let admin_token_0 = solana
+        .create_associated_token_account(&collect_fee_admin.pubkey(), mints[0].pubkey)
+        .await;
+    let admin_token_1 = solana
+        .create_associated_token_account(&collect_fee_admin.pubkey(), mints[1].pubkey)
+        .await;
+    send_tx(
+        solana,
+        SweepFeesInstruction {
+            collect_fee_admin,
+            market,
+            quote_vault,
+            token_base_receiver_account: admin_token_0,
+            token_receiver_account: admin_token_1,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(solana.token_account_balance(admin_token_1).await, 20);
+
+    // TEST: Tokens sent straight to a vault go to the owner of sol_destination
+    solana
+        .process_transaction(
+            &[spl_token::instruction::transfer(
+                &spl_token::id(),
+                &owner_token_1,
+                &quote_vault,
+                &owner.pubkey(),
+                &[],
+                5,
+            )
+            .unwrap()],
+            Some(&[owner]),
+        )
+        .await
+        .unwrap();
+    let owner_quote_before = solana.token_account_balance(owner_token_1).await;
+
     send_tx(
         solana,
         CloseMarketInstruction {
             close_market_admin,
             market,
             sol_destination: owner.pubkey(),
+            token_base_receiver_account: owner_token_0,
+            token_quote_receiver_account: owner_token_1,
         },
     )
     .await
     .unwrap();
 
+    assert!(solana.get_account_data(base_vault).await.is_none());
+    assert!(solana.get_account_data(quote_vault).await.is_none());
+    assert_eq!(
+        solana.token_account_balance(owner_token_1).await,
+        owner_quote_before + 5
+    );
+
     Ok(())
 }
 
@@ -336,8 +400,8 @@ async fn test_cancel_orders() -> Result<(), TransportError> {
             open_orders_account: account_0,
             base_vault,
             quote_vault,
-            token_base_account: owner_token_0,
-            token_quote_account: owner_token_1,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
             base_amount_lots: 100,
             quote_amount_lots: 0,
         },
@@ -402,8 +466,8 @@ async fn test_cancel_orders() -> Result<(), TransportError> {
             open_orders_account: account_0,
             base_vault,
             quote_vault,
-            token_base_account: owner_token_0,
-            token_quote_account: owner_token_1,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
             referrer: None,
         },
     )
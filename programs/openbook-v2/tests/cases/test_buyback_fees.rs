@@ -0,0 +1,204 @@
+use super::*;
+
+#[tokio::test]
+async fn test_buyback_fees() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        collect_fee_admin,
+        market_admin,
+        owner,
+        mints,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        tokens,
+        account_0,
+        account_1,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize {
+        market_admin_bool: true,
+        ..TestNewMarketInitialize::default()
+    })
+    .await?;
+    let solana = &context.solana.clone();
+
+    let owner_token_2 = context.users[0].token_accounts[2];
+    let admin_token_2 = solana
+        .create_associated_token_account(&collect_fee_admin.pubkey(), mints[2].pubkey)
+        .await;
+
+    // Buy back fees with the third token, worth 2 native quote per native token
+    set_stub_oracle_price(solana, &tokens[2], collect_fee_admin, 2.0).await;
+    send_tx(
+        solana,
+        EditMarketInstruction {
+            buyback_opt: Some(BuybackParams {
+                mint: mints[2].pubkey,
+                oracle: tokens[2].oracle,
+                fees_expiry_interval: 24 * 60 * 60,
+            }),
+            ..EditMarketInstruction::new(market_admin, market)
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_0,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 10000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_1,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_0,
+            base_vault,
+            quote_vault,
+            side: Side::Ask,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 10000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    // TEST: The taker fees paid accrued into the current interval, the maker got a rebate
+    {
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        let open_orders_account_1 = solana.get_account::<OpenOrdersAccount>(account_1).await;
+        assert_eq!(open_orders_account_0.buyback_fees_accrued_current, 0);
+        assert_eq!(open_orders_account_1.buyback_fees_accrued_current, 40);
+        assert_eq!(open_orders_account_1.buyback_fees_accrued_previous, 0);
+        let now = solana.get_clock().await.unix_timestamp as u64;
+        assert!(open_orders_account_1.buyback_fees_expiry_timestamp > now);
+    }
+
+    send_tx(
+        solana,
+        ConsumeEventsInstruction {
+            consume_events_admin: None,
+            market,
+            open_orders_accounts: vec![account_0, account_1],
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(
+        solana,
+        SettleFundsInstruction {
+            owner,
+            market,
+            open_orders_account: account_1,
+            base_vault,
+            quote_vault,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
+            referrer: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    let quote_fees_accrued = solana
+        .get_account::<Market>(market)
+        .await
+        .quote_fees_accrued;
+    assert!(quote_fees_accrued > 0);
+    let expected_quote = quote_fees_accrued.min(40);
+    let expected_buyback = (expected_quote + 1) / 2;
+
+    let quote_balance_before = solana.token_account_balance(owner_token_1).await;
+    let buyback_balance_before = solana.token_account_balance(owner_token_2).await;
+
+    // TEST: Fees are paid out against the buyback token at the oracle price
+    send_tx(
+        solana,
+        BuybackFeesInstruction {
+            owner,
+            open_orders_account: account_1,
+            market,
+            buyback_token_account: owner_token_2,
+            buyback_receiver_account: admin_token_2,
+            token_quote_account: owner_token_1,
+            max_buyback_fees: u64::MAX,
+        },
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        solana.token_account_balance(owner_token_1).await,
+        quote_balance_before + expected_quote
+    );
+    assert_eq!(
+        solana.token_account_balance(owner_token_2).await,
+        buyback_balance_before - expected_buyback
+    );
+    assert_eq!(
+        solana.token_account_balance(admin_token_2).await,
+        expected_buyback
+    );
+    {
+        let market = solana.get_account::<Market>(market).await;
+        assert_eq!(
+            market.quote_fees_accrued,
+            quote_fees_accrued - expected_quote
+        );
+        let open_orders_account_1 = solana.get_account::<OpenOrdersAccount>(account_1).await;
+        assert_eq!(
+            open_orders_account_1.buyback_fees_accrued_current,
+            40 - expected_quote
+        );
+    }
+
+    // TEST: Only the owner can buy back the fees of an account
+    assert!(send_tx(
+        solana,
+        BuybackFeesInstruction {
+            owner: context.users[2].key,
+            open_orders_account: account_1,
+            market,
+            buyback_token_account: context.users[2].token_accounts[2],
+            buyback_receiver_account: admin_token_2,
+            token_quote_account: context.users[2].token_accounts[1],
+            max_buyback_fees: u64::MAX,
+        },
+    )
+    .await
+    .is_err());
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use super::*;
+
+#[tokio::test]
+async fn test_cancel_orders() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        account_0,
+        bids,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    let order = |client_order_id| PlaceOrderArgs {
+        side: Side::Bid,
+        price_lots,
+        max_base_lots: 1,
+        max_quote_lots_including_fees: 100_000,
+        client_order_id,
+        order_type: PlaceOrderType::Limit,
+        self_trade_behavior: SelfTradeBehavior::default(),
+        expiry_timestamp: 0,
+    };
+
+    send_tx(
+        solana,
+        PlaceOrdersInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            base_vault,
+            quote_vault,
+            base_deposit_account: owner_token_0,
+            quote_deposit_account: owner_token_1,
+            orders: vec![order(1), order(2), order(3)],
+        },
+    )
+    .await
+    .unwrap();
+
+    let order_id = solana
+        .get_account::<OpenOrdersAccount>(account_0)
+        .await
+        .open_orders
+        .iter()
+        .find(|oo| oo.client_id == 1)
+        .unwrap()
+        .id;
+
+    // Unknown and repeated ids are skipped
+    send_tx(
+        solana,
+        CancelOrdersInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            order_ids: vec![order_id],
+            client_order_ids: vec![1, 2, 99],
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let open_orders_account = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        let client_ids = open_orders_account
+            .open_orders
+            .iter()
+            .filter(|oo| oo.id != 0)
+            .map(|oo| oo.client_id)
+            .collect::<Vec<_>>();
+        assert_eq!(client_ids, vec![3]);
+        assert_eq!(open_orders_account.position.bids_base_lots, 1);
+        assert_eq!(open_orders_account.position.quote_free_native, 200_000);
+
+        let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+        assert_eq!(bids_data.roots[0].leaf_count, 1);
+    }
+
+    // Too many ids
+    assert!(send_tx(
+        solana,
+        CancelOrdersInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            order_ids: vec![],
+            client_order_ids: (0..25).collect(),
+        },
+    )
+    .await
+    .is_err());
+
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use super::*;
+
+#[tokio::test]
+async fn test_disabled_instructions() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        market,
+        market_admin,
+        base_vault,
+        quote_vault,
+        price_lots,
+        account_0,
+        account_1,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize {
+        market_admin_bool: true,
+        ..TestNewMarketInitialize::default()
+    })
+    .await?;
+    let solana = &context.solana.clone();
+
+    let place_order = |client_order_id| PlaceOrderInstruction {
+        open_orders_account: account_1,
+        open_orders_admin: None,
+        market,
+        owner,
+        token_deposit_account: owner_token_0,
+        base_vault,
+        quote_vault,
+        side: Side::Ask,
+        price_lots,
+        max_base_lots: 2,
+        max_quote_lots_including_fees: 100_000,
+        client_order_id,
+        expiry_timestamp: 0,
+        order_type: PlaceOrderType::Limit,
+        self_trade_behavior: SelfTradeBehavior::default(),
+        remainings: vec![],
+    };
+    let take_order = PlaceTakeOrderInstruction {
+        open_orders_admin: None,
+        market,
+        owner,
+        token_deposit_account: owner_token_1,
+        token_receiver_account: Some(owner_token_0),
+        base_vault,
+        quote_vault,
+        side: Side::Bid,
+        price_lots,
+        max_base_lots: 1,
+        max_quote_lots_including_fees: 100_000,
+        client_order_id: 0,
+        expiry_timestamp: 0,
+        order_type: PlaceOrderType::ImmediateOrCancel,
+        referrer: None,
+    };
+    let deposit = DepositInstruction {
+        open_orders_account: account_0,
+        market,
+        base_vault,
+        quote_vault,
+        token_base_account: Some(owner_token_0),
+        token_quote_account: Some(owner_token_1),
+        owner,
+        base_amount_lots: 0,
+        quote_amount_lots: 100,
+    };
+    let set_disabled_instructions = |disabled_instructions| EditMarketInstruction {
+        disabled_instructions_opt: Some(disabled_instructions),
+        ..EditMarketInstruction::new(market_admin, market)
+    };
+
+    send_tx(solana, place_order(1)).await.unwrap();
+
+    // Unknown instruction bits are rejected
+    assert!(send_tx(solana, set_disabled_instructions(0b10000))
+        .await
+        .is_err());
+
+    // TEST: Disabled instructions fail, the others keep working
+    send_tx(
+        solana,
+        set_disabled_instructions(
+            MarketInstruction::PlaceOrder.mask() | MarketInstruction::Deposit.mask(),
+        ),
+    )
+    .await
+    .unwrap();
+
+    assert_openbook_error(
+        &send_tx(solana, place_order(2)).await,
+        OpenBookError::IxIsDisabled.into(),
+        "place order is disabled".to_string(),
+    );
+    assert_openbook_error(
+        &send_tx(solana, deposit.clone()).await,
+        OpenBookError::IxIsDisabled.into(),
+        "deposit is disabled".to_string(),
+    );
+    send_tx(solana, take_order.clone()).await.unwrap();
+
+    // TEST: A halted market only allows exiting it
+    send_tx(solana, set_disabled_instructions(MarketInstruction::ALL))
+        .await
+        .unwrap();
+
+    assert_openbook_error(
+        &send_tx(solana, take_order.clone()).await,
+        OpenBookError::GroupIsHalted.into(),
+        "market is halted".to_string(),
+    );
+
+    send_tx(
+        solana,
+        ConsumeEventsInstruction {
+            consume_events_admin: None,
+            market,
+            open_orders_accounts: vec![account_1],
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(
+        solana,
+        CancelAllOrdersInstruction {
+            open_orders_account: account_1,
+            market,
+            owner,
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(
+        solana,
+        SettleFundsInstruction {
+            owner,
+            market,
+            open_orders_account: account_1,
+            base_vault,
+            quote_vault,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
+            referrer: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let open_orders_account_1 = solana.get_account::<OpenOrdersAccount>(account_1).await;
+        assert_eq!(open_orders_account_1.position.asks_base_lots, 0);
+        assert_eq!(open_orders_account_1.position.base_free_native, 0);
+        assert_eq!(open_orders_account_1.position.quote_free_native, 0);
+    }
+
+    // TEST: Re-enabling the instructions resumes trading
+    send_tx(solana, set_disabled_instructions(0)).await.unwrap();
+    send_tx(solana, place_order(3)).await.unwrap();
+    send_tx(solana, deposit).await.unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use super::*;
+
+#[tokio::test]
+async fn test_edit_market() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        market,
+        market_admin,
+        close_market_admin,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize {
+        market_admin_bool: true,
+        ..TestNewMarketInitialize::default()
+    })
+    .await?;
+    let solana = &context.solana.clone();
+
+    // Only the market admin can edit the market
+    assert!(send_tx(
+        solana,
+        EditMarketInstruction {
+            taker_fee_opt: Some(0.0005),
+            ..EditMarketInstruction::new(close_market_admin, market)
+        },
+    )
+    .await
+    .is_err());
+
+    // Maker rebates can't be larger than taker fees
+    assert!(send_tx(
+        solana,
+        EditMarketInstruction {
+            maker_fee_opt: Some(-0.0005),
+            ..EditMarketInstruction::new(market_admin, market)
+        },
+    )
+    .await
+    .is_err());
+
+    send_tx(
+        solana,
+        EditMarketInstruction {
+            maker_fee_opt: Some(-0.0001),
+            taker_fee_opt: Some(0.0005),
+            fee_penalty_opt: Some(1000),
+            close_market_admin_opt: Some(close_market_admin.pubkey()),
+            ..EditMarketInstruction::new(market_admin, market)
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let market = solana.get_account::<Market>(market).await;
+        assert_eq!(market.maker_fee, I80F48::from_num(-0.0001f32));
+        assert_eq!(market.taker_fee, I80F48::from_num(0.0005f32));
+        assert_eq!(market.fee_penalty, 1000);
+        assert_eq!(
+            Option::<Pubkey>::from(market.close_market_admin),
+            Some(close_market_admin.pubkey())
+        );
+    }
+
+    // The default pubkey removes the market admin, after which the market can't be edited anymore
+    send_tx(
+        solana,
+        EditMarketInstruction {
+            market_admin_opt: Some(Pubkey::default()),
+            ..EditMarketInstruction::new(market_admin, market)
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let market = solana.get_account::<Market>(market).await;
+        assert_eq!(Option::<Pubkey>::from(market.market_admin), None);
+    }
+
+    assert!(send_tx(
+        solana,
+        EditMarketInstruction {
+            fee_penalty_opt: Some(0),
+            ..EditMarketInstruction::new(market_admin, market)
+        },
+    )
+    .await
+    .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_edit_maker_fee_with_resting_orders() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        market,
+        market_admin,
+        owner,
+        owner_token_1,
+        base_vault,
+        quote_vault,
+        account_0,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize {
+        market_admin_bool: true,
+        ..TestNewMarketInitialize::default()
+    })
+    .await?;
+    let solana = &context.solana.clone();
+
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_0,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots: 8,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 100,
+            client_order_id: 1,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    // TEST: A positive maker fee can't be set while the bid rests with its reserved fees
+    assert_openbook_error(
+        &send_tx(
+            solana,
+            EditMarketInstruction {
+                maker_fee_opt: Some(0.0002),
+                ..EditMarketInstruction::new(market_admin, market)
+            },
+        )
+        .await,
+        OpenBookError::OrderbookNotEmpty.into(),
+        "positive maker fee with resting orders".to_string(),
+    );
+
+    // TEST: Maker rebates reserve nothing and can still change
+    send_tx(
+        solana,
+        EditMarketInstruction {
+            maker_fee_opt: Some(-0.0001),
+            ..EditMarketInstruction::new(market_admin, market)
+        },
+    )
+    .await
+    .unwrap();
+
+    // TEST: Once the book is empty the maker fee can become positive
+    send_tx(
+        solana,
+        CancelAllOrdersInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+        },
+    )
+    .await
+    .unwrap();
+    send_tx(
+        solana,
+        EditMarketInstruction {
+            maker_fee_opt: Some(0.0002),
+            ..EditMarketInstruction::new(market_admin, market)
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let market = solana.get_account::<Market>(market).await;
+        assert_eq!(market.maker_fee, I80F48::from_num(0.0002f32));
+    }
+
+    Ok(())
+}
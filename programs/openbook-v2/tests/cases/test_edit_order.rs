@@ -0,0 +1,297 @@
+use super::*;
+
+#[tokio::test]
+async fn test_edit_order() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        account_0,
+        bids,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_0,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 3,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 1,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    let order_id = solana
+        .get_account::<OpenOrdersAccount>(account_0)
+        .await
+        .open_orders[0]
+        .id;
+
+    // TEST: Reducing the size at the same price keeps the order in place
+    send_tx(
+        solana,
+        EditOrderInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            base_vault,
+            quote_vault,
+            token_deposit_account: owner_token_1,
+            cancel_order_id: None,
+            cancel_client_order_id: Some(1),
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 2,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 2,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            expiry_timestamp: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let open_orders_account = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account.open_orders[0].id, order_id);
+        assert_eq!(open_orders_account.open_orders[0].client_id, 2);
+        assert_eq!(open_orders_account.position.bids_base_lots, 2);
+        assert_eq!(open_orders_account.position.quote_free_native, 100_000);
+
+        let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+        assert_eq!(bids_data.roots[0].leaf_count, 1);
+        let (_, leaf) = bids_data.nodes.iter(&bids_data.roots[0]).next().unwrap();
+        assert_eq!(leaf.key, order_id);
+        assert_eq!(leaf.quantity, 2);
+        assert_eq!(leaf.client_order_id, 2);
+    }
+
+    // TEST: Changing the price replaces the order, reusing the freed funds
+    send_tx(
+        solana,
+        EditOrderInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            base_vault,
+            quote_vault,
+            token_deposit_account: owner_token_1,
+            cancel_order_id: Some(order_id),
+            cancel_client_order_id: None,
+            side: Side::Bid,
+            price_lots: price_lots - 1000,
+            max_base_lots: 2,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 3,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            expiry_timestamp: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let open_orders_account = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        let order = open_orders_account
+            .open_orders
+            .iter()
+            .find(|oo| oo.id != 0)
+            .unwrap();
+        assert_ne!(order.id, order_id);
+        assert_eq!(order.client_id, 3);
+        assert_eq!(open_orders_account.position.bids_base_lots, 2);
+        assert_eq!(open_orders_account.position.quote_free_native, 120_000);
+
+        let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+        assert_eq!(bids_data.roots[0].leaf_count, 1);
+    }
+
+    // TEST: The order to replace must exist
+    assert!(send_tx(
+        solana,
+        EditOrderInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            base_vault,
+            quote_vault,
+            token_deposit_account: owner_token_1,
+            cancel_order_id: None,
+            cancel_client_order_id: Some(1),
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 4,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            expiry_timestamp: 0,
+        },
+    )
+    .await
+    .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_edit_order_mismatched_settings() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        account_0,
+        bids,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_0,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 5,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 1,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    let now_ts = solana.get_clock().await.unix_timestamp as u64;
+    let edit = EditOrderInstruction {
+        open_orders_account: account_0,
+        market,
+        owner,
+        base_vault,
+        quote_vault,
+        token_deposit_account: owner_token_1,
+        cancel_order_id: None,
+        cancel_client_order_id: Some(1),
+        side: Side::Bid,
+        price_lots,
+        max_base_lots: 4,
+        max_quote_lots_including_fees: 100_000,
+        client_order_id: 1,
+        order_type: PlaceOrderType::Limit,
+        self_trade_behavior: SelfTradeBehavior::default(),
+        expiry_timestamp: 0,
+    };
+
+    // TEST: Shrinking the order with another order type, self trade behavior or expiry
+    // replaces it instead of reducing it in place
+    for (max_base_lots, edit) in [
+        (
+            4,
+            EditOrderInstruction {
+                order_type: PlaceOrderType::PostOnly,
+                ..edit
+            },
+        ),
+        (
+            3,
+            EditOrderInstruction {
+                order_type: PlaceOrderType::PostOnly,
+                self_trade_behavior: SelfTradeBehavior::CancelProvide,
+                ..edit
+            },
+        ),
+        (
+            2,
+            EditOrderInstruction {
+                order_type: PlaceOrderType::PostOnly,
+                self_trade_behavior: SelfTradeBehavior::CancelProvide,
+                expiry_timestamp: now_ts + 1000,
+                ..edit
+            },
+        ),
+    ] {
+        let order_id = solana
+            .get_account::<OpenOrdersAccount>(account_0)
+            .await
+            .open_orders
+            .iter()
+            .find(|oo| oo.id != 0)
+            .unwrap()
+            .id;
+
+        send_tx(
+            solana,
+            EditOrderInstruction {
+                max_base_lots,
+                ..edit
+            },
+        )
+        .await
+        .unwrap();
+
+        let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+        assert_eq!(bids_data.roots[0].leaf_count, 1);
+        let (_, leaf) = bids_data.nodes.iter(&bids_data.roots[0]).next().unwrap();
+        assert_ne!(leaf.key, order_id);
+        assert_eq!(leaf.quantity, max_base_lots);
+    }
+
+    // TEST: Shrinking with the same settings as the resting order keeps it in place
+    let (order_id, expiry) = {
+        let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+        let (_, leaf) = bids_data.nodes.iter(&bids_data.roots[0]).next().unwrap();
+        (leaf.key, leaf.expiry())
+    };
+    send_tx(
+        solana,
+        EditOrderInstruction {
+            max_base_lots: 1,
+            order_type: PlaceOrderType::PostOnly,
+            self_trade_behavior: SelfTradeBehavior::CancelProvide,
+            expiry_timestamp: expiry,
+            ..edit
+        },
+    )
+    .await
+    .unwrap();
+
+    let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+    let (_, leaf) = bids_data.nodes.iter(&bids_data.roots[0]).next().unwrap();
+    assert_eq!(leaf.key, order_id);
+    assert_eq!(leaf.quantity, 1);
+
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use super::*;
+
+#[tokio::test]
+async fn test_fee_tiers() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        collect_fee_admin,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        market,
+        market_admin,
+        base_vault,
+        quote_vault,
+        price_lots,
+        tokens,
+        account_0,
+        account_1,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize {
+        market_admin_bool: true,
+        ..TestNewMarketInitialize::default()
+    })
+    .await?;
+    let solana = &context.solana.clone();
+
+    set_stub_oracle_price(solana, &tokens[1], collect_fee_admin, 1000.0).await;
+
+    // TEST: Maker rebates have to be covered by the lowest taker fee
+    assert_openbook_error(
+        &send_tx(
+            solana,
+            EditMarketInstruction {
+                fee_tiers_opt: Some(vec![FeeTierParams {
+                    volume_threshold: 1,
+                    maker_fee: -0.0005,
+                    taker_fee: 0.0006,
+                }]),
+                ..EditMarketInstruction::new(market_admin, market)
+            },
+        )
+        .await,
+        OpenBookError::InvalidFeeTiers.into(),
+        "maker rebate is larger than the base taker fee".to_string(),
+    );
+
+    // Any account with volume gets the tier
+    send_tx(
+        solana,
+        EditMarketInstruction {
+            fee_tiers_opt: Some(vec![FeeTierParams {
+                volume_threshold: 1,
+                maker_fee: -0.0001,
+                taker_fee: 0.0002,
+            }]),
+            ..EditMarketInstruction::new(market_admin, market)
+        },
+    )
+    .await
+    .unwrap();
+    {
+        let market = solana.get_account::<Market>(market).await;
+        assert_eq!(market.fee_tiers_count, 1);
+        assert_eq!(market.fee_tiers[0].volume_threshold, 1);
+    }
+
+    let place_order = |open_orders_account, side| PlaceOrderInstruction {
+        open_orders_account,
+        open_orders_admin: None,
+        market,
+        owner,
+        token_deposit_account: match side {
+            Side::Bid => owner_token_1,
+            Side::Ask => owner_token_0,
+        },
+        base_vault,
+        quote_vault,
+        side,
+        price_lots,
+        max_base_lots: 1,
+        max_quote_lots_including_fees: 10040,
+        client_order_id: 0,
+        expiry_timestamp: 0,
+        order_type: PlaceOrderType::Limit,
+        self_trade_behavior: SelfTradeBehavior::default(),
+        remainings: vec![],
+    };
+    let consume_events = ConsumeEventsInstruction {
+        consume_events_admin: None,
+        market,
+        open_orders_accounts: vec![account_0, account_1],
+    };
+
+    // TEST: Accounts without volume pay the market fees
+    send_tx(solana, place_order(account_0, Side::Bid))
+        .await
+        .unwrap();
+    send_tx(solana, place_order(account_1, Side::Ask))
+        .await
+        .unwrap();
+    send_tx(solana, consume_events.clone()).await.unwrap();
+
+    {
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        let open_orders_account_1 = solana.get_account::<OpenOrdersAccount>(account_1).await;
+        assert_eq!(open_orders_account_0.position.quote_free_native, 20);
+        assert_eq!(open_orders_account_1.position.quote_free_native, 99_960);
+        // Volume counts the quote traded, not the fees paid
+        assert_eq!(open_orders_account_0.position.maker_volume, 100_000);
+        assert_eq!(open_orders_account_1.position.taker_volume, 100_000);
+    }
+    let quote_fees_accrued = solana
+        .get_account::<Market>(market)
+        .await
+        .quote_fees_accrued;
+    let referrer_rebates_accrued = solana
+        .get_account::<OpenOrdersAccount>(account_1)
+        .await
+        .position
+        .referrer_rebates_accrued;
+
+    // TEST: With volume the accounts pay the fees of their tier
+    send_tx(solana, place_order(account_0, Side::Bid))
+        .await
+        .unwrap();
+    send_tx(solana, place_order(account_1, Side::Ask))
+        .await
+        .unwrap();
+    send_tx(solana, consume_events).await.unwrap();
+
+    {
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        let open_orders_account_1 = solana.get_account::<OpenOrdersAccount>(account_1).await;
+        // The rebate of 20 was used for the second bid
+        assert_eq!(open_orders_account_0.position.quote_free_native, 10);
+        assert_eq!(open_orders_account_1.position.quote_free_native, 199_940);
+        // Nothing is left for the referrer above the largest maker rebate
+        assert_eq!(
+            open_orders_account_1.position.referrer_rebates_accrued,
+            referrer_rebates_accrued
+        );
+
+        // The part of the taker fee kept back for a larger maker rebate goes to the market
+        let market = solana.get_account::<Market>(market).await;
+        assert_eq!(market.quote_fees_accrued, quote_fees_accrued + 10);
+    }
+
+    Ok(())
+}
@@ -117,6 +117,9 @@ async fn test_fees_accrued() -> Result<(), TransportError> {
         assert_eq!(open_orders_account_1.position.quote_free_native, 99980);
     }
 
+    let admin_token_0 = solana
+        .create_associated_token_account(&collect_fee_admin.pubkey(), mints[0].pubkey)
+        .await;
     let admin_token_1 = solana
         .create_associated_token_account(&collect_fee_admin.pubkey(), mints[1].pubkey)
         .await;
@@ -129,8 +132,8 @@ async fn test_fees_accrued() -> Result<(), TransportError> {
             open_orders_account: account_1,
             base_vault,
             quote_vault,
-            token_base_account: owner_token_0,
-            token_quote_account: owner_token_1,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
             referrer: None,
         },
     )
@@ -139,17 +142,32 @@ async fn test_fees_accrued() -> Result<(), TransportError> {
 
     {
         let market = solana.get_account::<Market>(market).await;
-        assert_eq!(market.quote_fees_accrued, 9);
+        assert_eq!(market.quote_fees_accrued, 10);
         assert_eq!(market.fees_accrued, 9);
         assert_eq!(market.fees_to_referrers, 0);
     }
 
+    // Fees can only be swept to accounts of the collect fee admin
+    assert!(send_tx(
+        solana,
+        SweepFeesInstruction {
+            collect_fee_admin,
+            market,
+            quote_vault,
+            token_base_receiver_account: admin_token_0,
+            token_receiver_account: owner_token_1,
+        },
+    )
+    .await
+    .is_err());
+
     send_tx(
         solana,
         SweepFeesInstruction {
             collect_fee_admin,
             market,
             quote_vault,
+            token_base_receiver_account: admin_token_0,
             token_receiver_account: admin_token_1,
         },
     )
@@ -161,7 +179,11 @@ async fn test_fees_accrued() -> Result<(), TransportError> {
         assert_eq!(market.quote_fees_accrued, 0);
         assert_eq!(market.fees_accrued, 9);
         assert_eq!(market.fees_to_referrers, 0);
+        assert_eq!(market.base_fees_swept, 0);
+        assert_eq!(market.quote_fees_swept, 10);
     }
+    assert_eq!(solana.token_account_balance(admin_token_1).await, 10);
+    assert_eq!(solana.token_account_balance(admin_token_0).await, 0);
 
     let balance_quote = solana.token_account_balance(owner_token_1).await;
 
@@ -210,6 +232,7 @@ async fn test_maker_fees() -> Result<(), TransportError> {
         context,
         collect_fee_admin,
         owner,
+        payer,
         mints,
         owner_token_0,
         owner_token_1,
@@ -373,6 +396,9 @@ async fn test_maker_fees() -> Result<(), TransportError> {
         assert_eq!(open_orders_account_1.position.quote_free_native, 99960);
     }
 
+    let admin_token_0 = solana
+        .create_associated_token_account(&collect_fee_admin.pubkey(), mints[0].pubkey)
+        .await;
     let admin_token_1 = solana
         .create_associated_token_account(&collect_fee_admin.pubkey(), mints[1].pubkey)
         .await;
@@ -385,8 +411,8 @@ async fn test_maker_fees() -> Result<(), TransportError> {
             open_orders_account: account_1,
             base_vault,
             quote_vault,
-            token_base_account: owner_token_0,
-            token_quote_account: owner_token_1,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
             referrer: None,
         },
     )
@@ -395,11 +421,24 @@ async fn test_maker_fees() -> Result<(), TransportError> {
 
     {
         let market = solana.get_account::<Market>(market).await;
-        assert_eq!(market.quote_fees_accrued, 39);
+        assert_eq!(market.quote_fees_accrued, 40);
         assert_eq!(market.fees_accrued, 58);
         assert_eq!(market.fees_to_referrers, 0);
     }
 
+    let referrer = send_tx(
+        solana,
+        CreateReferrerInstruction {
+            referrer_id: 0,
+            authority: owner,
+            token_account: owner_token_1,
+            payer,
+        },
+    )
+    .await
+    .unwrap()
+    .referrer;
+
     send_tx(
         solana,
         SettleFundsInstruction {
@@ -408,9 +447,9 @@ async fn test_maker_fees() -> Result<(), TransportError> {
             open_orders_account: account_0,
             base_vault,
             quote_vault,
-            token_base_account: owner_token_0,
-            token_quote_account: owner_token_1,
-            referrer: Some(owner_token_1),
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
+            referrer: Some(referrer),
         },
     )
     .await
@@ -418,7 +457,7 @@ async fn test_maker_fees() -> Result<(), TransportError> {
 
     {
         let market = solana.get_account::<Market>(market).await;
-        assert_eq!(market.quote_fees_accrued, 39);
+        assert_eq!(market.quote_fees_accrued, 40);
         assert_eq!(market.fees_accrued, 58);
         assert_eq!(market.fees_to_referrers, 19);
     }
@@ -429,6 +468,7 @@ async fn test_maker_fees() -> Result<(), TransportError> {
             collect_fee_admin,
             market,
             quote_vault,
+            token_base_receiver_account: admin_token_0,
             token_receiver_account: admin_token_1,
         },
     )
@@ -442,3 +482,290 @@ async fn test_maker_fees() -> Result<(), TransportError> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_deposit_totals_match_vaults() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        collect_fee_admin,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        tokens,
+        account_0,
+        account_1,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    // Set the initial oracle price
+    set_stub_oracle_price(solana, &tokens[1], collect_fee_admin, 1000.0).await;
+
+    let place_bid = PlaceOrderInstruction {
+        open_orders_account: account_0,
+        open_orders_admin: None,
+        market,
+        owner,
+        token_deposit_account: owner_token_1,
+        base_vault,
+        quote_vault,
+        side: Side::Bid,
+        price_lots,
+        max_base_lots: 1,
+        max_quote_lots_including_fees: 10000,
+        client_order_id: 0,
+        expiry_timestamp: 0,
+        order_type: PlaceOrderType::Limit,
+        self_trade_behavior: SelfTradeBehavior::default(),
+        remainings: vec![],
+    };
+
+    send_tx(solana, place_bid.clone()).await.unwrap();
+    assert_deposits_match_vaults(solana, market, base_vault, quote_vault).await;
+
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_1,
+            token_deposit_account: owner_token_0,
+            side: Side::Ask,
+            ..place_bid.clone()
+        },
+    )
+    .await
+    .unwrap();
+    assert_deposits_match_vaults(solana, market, base_vault, quote_vault).await;
+
+    send_tx(
+        solana,
+        ConsumeEventsInstruction {
+            consume_events_admin: None,
+            market,
+            open_orders_accounts: vec![account_0, account_1],
+        },
+    )
+    .await
+    .unwrap();
+    assert_deposits_match_vaults(solana, market, base_vault, quote_vault).await;
+
+    // TEST: Without an open orders account the referrer part of the taker fees
+    // leaves the deposits right away
+    send_tx(solana, place_bid.clone()).await.unwrap();
+    send_tx(
+        solana,
+        PlaceTakeOrderInstruction {
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_0,
+            token_receiver_account: Some(owner_token_1),
+            base_vault,
+            quote_vault,
+            side: Side::Ask,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 10000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::ImmediateOrCancel,
+            referrer: None,
+        },
+    )
+    .await
+    .unwrap();
+    assert_deposits_match_vaults(solana, market, base_vault, quote_vault).await;
+
+    {
+        let market = solana.get_account::<Market>(market).await;
+        assert_eq!(market.quote_fees_accrued, 21);
+    }
+
+    send_tx(
+        solana,
+        ConsumeEventsInstruction {
+            consume_events_admin: None,
+            market,
+            open_orders_accounts: vec![account_0],
+        },
+    )
+    .await
+    .unwrap();
+    assert_deposits_match_vaults(solana, market, base_vault, quote_vault).await;
+
+    // TEST: Settling moves the referrer rebates out of the deposits, which end at zero
+    for open_orders_account in [account_0, account_1] {
+        send_tx(
+            solana,
+            SettleFundsInstruction {
+                owner,
+                market,
+                open_orders_account,
+                base_vault,
+                quote_vault,
+                token_base_account: Some(owner_token_0),
+                token_quote_account: Some(owner_token_1),
+                referrer: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert_deposits_match_vaults(solana, market, base_vault, quote_vault).await;
+    }
+
+    {
+        let market = solana.get_account::<Market>(market).await;
+        assert_eq!(market.base_deposit_total, 0);
+        assert_eq!(market.quote_deposit_total, 0);
+        assert_eq!(market.quote_fees_accrued, 40);
+    }
+
+    Ok(())
+}
+
+async fn assert_deposits_match_vaults(
+    solana: &SolanaCookie,
+    market: Pubkey,
+    base_vault: Pubkey,
+    quote_vault: Pubkey,
+) {
+    let market = solana.get_account::<Market>(market).await;
+    assert_eq!(
+        solana.token_account_balance(base_vault).await,
+        market.base_deposit_total + market.base_fees_accrued
+    );
+    assert_eq!(
+        solana.token_account_balance(quote_vault).await,
+        market.quote_deposit_total + market.quote_fees_accrued
+    );
+}
+
+#[tokio::test]
+async fn test_fractional_fees_leave_no_deposits() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        collect_fee_admin,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        tokens,
+        account_0,
+        account_1,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize {
+        maker_fee: -0.000137,
+        taker_fee: 0.000311,
+        ..TestNewMarketInitialize::default()
+    })
+    .await?;
+    let solana = &context.solana.clone();
+
+    set_stub_oracle_price(solana, &tokens[1], collect_fee_admin, 1000.0).await;
+
+    let place_bid = |max_base_lots: i64| PlaceOrderInstruction {
+        open_orders_account: account_0,
+        open_orders_admin: None,
+        market,
+        owner,
+        token_deposit_account: owner_token_1,
+        base_vault,
+        quote_vault,
+        side: Side::Bid,
+        price_lots,
+        max_base_lots,
+        max_quote_lots_including_fees: 100000,
+        client_order_id: 0,
+        expiry_timestamp: 0,
+        order_type: PlaceOrderType::Limit,
+        self_trade_behavior: SelfTradeBehavior::default(),
+        remainings: vec![],
+    };
+    let consume_events = ConsumeEventsInstruction {
+        consume_events_admin: None,
+        market,
+        open_orders_accounts: vec![account_0, account_1],
+    };
+
+    // TEST: A taker order with an open orders account fills two makers
+    send_tx(solana, place_bid(1)).await.unwrap();
+    send_tx(solana, place_bid(2)).await.unwrap();
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_1,
+            token_deposit_account: owner_token_0,
+            side: Side::Ask,
+            ..place_bid(3)
+        },
+    )
+    .await
+    .unwrap();
+    assert_deposits_match_vaults(solana, market, base_vault, quote_vault).await;
+    send_tx(solana, consume_events.clone()).await.unwrap();
+    assert_deposits_match_vaults(solana, market, base_vault, quote_vault).await;
+
+    // TEST: A taker order without an open orders account
+    send_tx(solana, place_bid(1)).await.unwrap();
+    send_tx(
+        solana,
+        PlaceTakeOrderInstruction {
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_0,
+            token_receiver_account: Some(owner_token_1),
+            base_vault,
+            quote_vault,
+            side: Side::Ask,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 100000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::ImmediateOrCancel,
+            referrer: None,
+        },
+    )
+    .await
+    .unwrap();
+    assert_deposits_match_vaults(solana, market, base_vault, quote_vault).await;
+    send_tx(solana, consume_events).await.unwrap();
+    assert_deposits_match_vaults(solana, market, base_vault, quote_vault).await;
+
+    // TEST: Once all is settled the rounding remainders are market fees, not deposits
+    for open_orders_account in [account_0, account_1] {
+        send_tx(
+            solana,
+            SettleFundsInstruction {
+                owner,
+                market,
+                open_orders_account,
+                base_vault,
+                quote_vault,
+                token_base_account: Some(owner_token_0),
+                token_quote_account: Some(owner_token_1),
+                referrer: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert_deposits_match_vaults(solana, market, base_vault, quote_vault).await;
+    }
+
+    {
+        let market = solana.get_account::<Market>(market).await;
+        assert_eq!(market.base_deposit_total, 0);
+        assert_eq!(market.quote_deposit_total, 0);
+        assert_eq!(market.referrer_rebates_accrued, 0);
+        assert!(market.quote_fees_accrued > 0);
+    }
+
+    Ok(())
+}
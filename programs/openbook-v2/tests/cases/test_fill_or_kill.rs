@@ -0,0 +1,109 @@
+use super::*;
+
+#[tokio::test]
+async fn test_fill_or_kill() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        account_0,
+        account_1,
+        asks,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_1,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_0,
+            base_vault,
+            quote_vault,
+            side: Side::Ask,
+            price_lots,
+            max_base_lots: 2,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 1,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    // TEST: A FillOrKill order that can't be filled completely fails
+    assert!(send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_0,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 3,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 2,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::FillOrKill,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .is_err());
+
+    let take_order = |max_base_lots| PlaceTakeOrderInstruction {
+        open_orders_admin: None,
+        market,
+        owner,
+        token_deposit_account: owner_token_1,
+        token_receiver_account: Some(owner_token_0),
+        base_vault,
+        quote_vault,
+        side: Side::Bid,
+        price_lots,
+        max_base_lots,
+        max_quote_lots_including_fees: 100_000,
+        client_order_id: 3,
+        expiry_timestamp: 0,
+        order_type: PlaceOrderType::FillOrKill,
+        referrer: None,
+    };
+
+    assert!(send_tx(solana, take_order(3)).await.is_err());
+
+    {
+        let asks_data = solana.get_account_boxed::<BookSide>(asks).await;
+        assert_eq!(asks_data.roots[0].leaf_count, 1);
+        let (_, leaf) = asks_data.nodes.iter(&asks_data.roots[0]).next().unwrap();
+        assert_eq!(leaf.quantity, 2);
+    }
+
+    // TEST: A FillOrKill order that can be filled completely succeeds
+    let balance_base = solana.token_account_balance(owner_token_0).await;
+    send_tx(solana, take_order(2)).await.unwrap();
+
+    assert_eq!(
+        solana.token_account_balance(owner_token_0).await,
+        balance_base + 200
+    );
+    let asks_data = solana.get_account_boxed::<BookSide>(asks).await;
+    assert_eq!(asks_data.roots[0].leaf_count, 0);
+
+    Ok(())
+}
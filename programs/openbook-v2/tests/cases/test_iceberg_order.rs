@@ -0,0 +1,299 @@
+use super::*;
+
+#[tokio::test]
+async fn test_iceberg_order() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        account_0,
+        asks,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    // Only 2 of the 5 lots are shown on the book
+    send_tx(
+        solana,
+        PlaceIcebergOrderInstruction {
+            open_orders_account: account_0,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_0,
+            base_vault,
+            quote_vault,
+            side: Side::Ask,
+            price_lots,
+            max_base_lots: 5,
+            display_base_lots: 2,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 1,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+        },
+    )
+    .await
+    .unwrap();
+
+    let order_id = {
+        let asks_data = solana.get_account_boxed::<BookSide>(asks).await;
+        assert_eq!(asks_data.roots[0].leaf_count, 1);
+        let (_, leaf) = asks_data.nodes.iter(&asks_data.roots[0]).next().unwrap();
+        assert_eq!(leaf.quantity, 2);
+        assert_eq!(leaf.hidden_quantity, 3);
+        assert_eq!(leaf.display_quantity, 2);
+
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account_0.position.asks_base_lots, 5);
+        assert_eq!(open_orders_account_0.open_orders[0].id, leaf.key);
+        leaf.key
+    };
+
+    // TEST: Taking the visible part replenishes the order with a new id
+    send_tx(
+        solana,
+        PlaceTakeOrderInstruction {
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            token_receiver_account: Some(owner_token_0),
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 2,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 2,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::ImmediateOrCancel,
+            referrer: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    let replenished_order_id = {
+        let asks_data = solana.get_account_boxed::<BookSide>(asks).await;
+        assert_eq!(asks_data.roots[0].leaf_count, 1);
+        let (_, leaf) = asks_data.nodes.iter(&asks_data.roots[0]).next().unwrap();
+        assert_eq!(leaf.quantity, 2);
+        assert_eq!(leaf.hidden_quantity, 1);
+        assert_ne!(leaf.key, order_id);
+        leaf.key
+    };
+
+    send_tx(
+        solana,
+        ConsumeEventsInstruction {
+            consume_events_admin: None,
+            market,
+            open_orders_accounts: vec![account_0],
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account_0.position.asks_base_lots, 3);
+        assert_eq!(
+            open_orders_account_0.open_orders[0].id,
+            replenished_order_id
+        );
+    }
+
+    // TEST: Cancelling the replenished order releases the hidden quantity too
+    send_tx(
+        solana,
+        CancelOrderInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            order_id: replenished_order_id,
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let asks_data = solana.get_account_boxed::<BookSide>(asks).await;
+        assert_eq!(asks_data.roots[0].leaf_count, 0);
+
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account_0.position.asks_base_lots, 0);
+        assert_eq!(open_orders_account_0.position.base_free_native, 300);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_iceberg_order_replenished_while_matching() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        account_0,
+        account_1,
+        bids,
+        asks,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    let iceberg_ask = |client_order_id| PlaceIcebergOrderInstruction {
+        open_orders_account: account_0,
+        open_orders_admin: None,
+        market,
+        owner,
+        token_deposit_account: owner_token_0,
+        base_vault,
+        quote_vault,
+        side: Side::Ask,
+        price_lots,
+        max_base_lots: 5,
+        display_base_lots: 2,
+        max_quote_lots_including_fees: 100_000,
+        client_order_id,
+        expiry_timestamp: 0,
+        order_type: PlaceOrderType::Limit,
+        self_trade_behavior: SelfTradeBehavior::default(),
+    };
+    send_tx(solana, iceberg_ask(1)).await.unwrap();
+
+    // TEST: A limit bid keeps matching the replenished iceberg order and posts the rest
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_1,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 7,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 2,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let asks_data = solana.get_account_boxed::<BookSide>(asks).await;
+        assert_eq!(asks_data.roots[0].leaf_count, 0);
+        let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+        assert_eq!(bids_data.roots[0].leaf_count, 1);
+        let (_, leaf) = bids_data.nodes.iter(&bids_data.roots[0]).next().unwrap();
+        assert_eq!(leaf.quantity, 2);
+
+        let open_orders_account_1 = solana.get_account::<OpenOrdersAccount>(account_1).await;
+        assert_eq!(open_orders_account_1.position.bids_base_lots, 2);
+        assert_eq!(open_orders_account_1.position.base_free_native, 500);
+    }
+
+    send_tx(
+        solana,
+        ConsumeEventsInstruction {
+            consume_events_admin: None,
+            market,
+            open_orders_accounts: vec![account_0, account_1],
+        },
+    )
+    .await
+    .unwrap();
+    send_tx(
+        solana,
+        CancelOrderByClientOrderIdInstruction {
+            open_orders_account: account_1,
+            market,
+            owner,
+            client_order_id: 2,
+        },
+    )
+    .await
+    .unwrap();
+
+    // TEST: A replenished order can be cancelled before its fill event is processed
+    send_tx(solana, iceberg_ask(3)).await.unwrap();
+    send_tx(
+        solana,
+        PlaceTakeOrderInstruction {
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            token_receiver_account: Some(owner_token_0),
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 2,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 4,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::ImmediateOrCancel,
+            referrer: None,
+        },
+    )
+    .await
+    .unwrap();
+    send_tx(
+        solana,
+        CancelOrderByClientOrderIdInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            client_order_id: 3,
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let asks_data = solana.get_account_boxed::<BookSide>(asks).await;
+        assert_eq!(asks_data.roots[0].leaf_count, 0);
+
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account_0.open_orders[0].id, 0);
+        assert_eq!(open_orders_account_0.position.asks_base_lots, 2);
+    }
+
+    send_tx(
+        solana,
+        ConsumeEventsInstruction {
+            consume_events_admin: None,
+            market,
+            open_orders_accounts: vec![account_0],
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account_0.position.asks_base_lots, 0);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use super::*;
+
+#[tokio::test]
+async fn test_native_sol() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        collect_fee_admin,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        tokens,
+        account_0,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize {
+        native_base_mint: true,
+        ..TestNewMarketInitialize::default()
+    })
+    .await?;
+    let solana = &context.solana.clone();
+
+    set_stub_oracle_price(solana, &tokens[1], collect_fee_admin, 1000.0).await;
+
+    // TEST: Deposit native SOL without a wrapped SOL account
+    let owner_lamports = solana.get_account_lamports(owner.pubkey()).await;
+    send_tx(
+        solana,
+        DepositInstruction {
+            open_orders_account: account_0,
+            market,
+            base_vault,
+            quote_vault,
+            token_base_account: None,
+            token_quote_account: Some(owner_token_1),
+            owner,
+            base_amount_lots: 10,
+            quote_amount_lots: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        solana.get_account_lamports(owner.pubkey()).await,
+        owner_lamports - 1000
+    );
+    assert_eq!(solana.token_account_balance(base_vault).await, 1000);
+    {
+        let market = solana.get_account::<Market>(market).await;
+        assert_eq!(market.base_deposit_total, 1000);
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account_0.position.base_free_native, 1000);
+    }
+
+    // The quote side isn't wrapped SOL
+    assert_openbook_error(
+        &send_tx(
+            solana,
+            DepositInstruction {
+                open_orders_account: account_0,
+                market,
+                base_vault,
+                quote_vault,
+                token_base_account: None,
+                token_quote_account: None,
+                owner,
+                base_amount_lots: 0,
+                quote_amount_lots: 10,
+            },
+        )
+        .await,
+        OpenBookError::NativeSolNotSupported.into(),
+        "quote mint is not wrapped SOL".to_string(),
+    );
+
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_0,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_0,
+            base_vault,
+            quote_vault,
+            side: Side::Ask,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 10000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    // TEST: A taker receives native SOL
+    let owner_lamports = solana.get_account_lamports(owner.pubkey()).await;
+    send_tx(
+        solana,
+        PlaceTakeOrderInstruction {
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            token_receiver_account: None,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 10040,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::ImmediateOrCancel,
+            referrer: None,
+            open_orders_admin: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        solana.get_account_lamports(owner.pubkey()).await,
+        owner_lamports + 100
+    );
+    assert_eq!(solana.token_account_balance(base_vault).await, 900);
+    assert!(solana
+        .get_account_data(native_unwrap_address(&owner.pubkey()).0)
+        .await
+        .is_none());
+
+    send_tx(
+        solana,
+        ConsumeEventsInstruction {
+            consume_events_admin: None,
+            market,
+            open_orders_accounts: vec![account_0],
+        },
+    )
+    .await
+    .unwrap();
+
+    // TEST: Settle the remaining base as native SOL
+    let owner_lamports = solana.get_account_lamports(owner.pubkey()).await;
+    send_tx(
+        solana,
+        SettleFundsInstruction {
+            owner,
+            market,
+            open_orders_account: account_0,
+            base_vault,
+            quote_vault,
+            token_base_account: None,
+            token_quote_account: Some(owner_token_1),
+            referrer: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        solana.get_account_lamports(owner.pubkey()).await,
+        owner_lamports + 900
+    );
+    assert_eq!(solana.token_account_balance(base_vault).await, 0);
+    {
+        let market = solana.get_account::<Market>(market).await;
+        assert_eq!(market.base_deposit_total, 0);
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account_0.position.base_free_native, 0);
+        assert_eq!(open_orders_account_0.position.quote_free_native, 0);
+    }
+
+    Ok(())
+}
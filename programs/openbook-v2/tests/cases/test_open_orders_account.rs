@@ -0,0 +1,607 @@
+use super::*;
+
+#[tokio::test]
+async fn test_close_open_orders_account() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        collect_fee_admin,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        tokens,
+        account_0,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    // Set the initial oracle price
+    set_stub_oracle_price(solana, &tokens[1], collect_fee_admin, 1000.0).await;
+
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_0,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 10000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    // Can't close an account with open orders
+    assert!(send_tx(
+        solana,
+        CloseOpenOrdersAccountInstruction {
+            open_orders_account: account_0,
+            owner,
+            sol_destination: owner.pubkey(),
+        },
+    )
+    .await
+    .is_err());
+
+    send_tx(
+        solana,
+        CancelAllOrdersInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+        },
+    )
+    .await
+    .unwrap();
+
+    // Nor with unsettled funds
+    assert!(send_tx(
+        solana,
+        CloseOpenOrdersAccountInstruction {
+            open_orders_account: account_0,
+            owner,
+            sol_destination: owner.pubkey(),
+        },
+    )
+    .await
+    .is_err());
+
+    send_tx(
+        solana,
+        SettleFundsInstruction {
+            owner,
+            market,
+            open_orders_account: account_0,
+            base_vault,
+            quote_vault,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
+            referrer: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(
+        solana,
+        CloseOpenOrdersAccountInstruction {
+            open_orders_account: account_0,
+            owner,
+            sol_destination: owner.pubkey(),
+        },
+    )
+    .await
+    .unwrap();
+
+    assert!(solana.get_account_data(account_0).await.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_delegate() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        collect_fee_admin,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        tokens,
+        account_0,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    let delegate = context.users[2].key;
+    let delegate_token_0 = context.users[2].token_accounts[0];
+    let delegate_token_1 = context.users[2].token_accounts[1];
+
+    // Set the initial oracle price
+    set_stub_oracle_price(solana, &tokens[1], collect_fee_admin, 1000.0).await;
+
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_0,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 10000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    // Only the owner can set a delegate
+    assert!(send_tx(
+        solana,
+        SetDelegateInstruction {
+            delegate_account: Some(delegate.pubkey()),
+            owner: delegate,
+            open_orders_account: account_0,
+        },
+    )
+    .await
+    .is_err());
+
+    send_tx(
+        solana,
+        SetDelegateInstruction {
+            delegate_account: Some(delegate.pubkey()),
+            owner,
+            open_orders_account: account_0,
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account_0.delegate, delegate.pubkey());
+    }
+
+    send_tx(
+        solana,
+        CancelAllOrdersInstruction {
+            open_orders_account: account_0,
+            market,
+            owner: delegate,
+        },
+    )
+    .await
+    .unwrap();
+
+    // The delegate can't settle funds to its own token accounts
+    assert!(send_tx(
+        solana,
+        SettleFundsInstruction {
+            owner: delegate,
+            market,
+            open_orders_account: account_0,
+            base_vault,
+            quote_vault,
+            token_base_account: Some(delegate_token_0),
+            token_quote_account: Some(delegate_token_1),
+            referrer: None,
+        },
+    )
+    .await
+    .is_err());
+
+    send_tx(
+        solana,
+        SettleFundsInstruction {
+            owner: delegate,
+            market,
+            open_orders_account: account_0,
+            base_vault,
+            quote_vault,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
+            referrer: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account_0.position.quote_free_native, 0);
+    }
+
+    // Revoke the delegate
+    send_tx(
+        solana,
+        SetDelegateInstruction {
+            delegate_account: None,
+            owner,
+            open_orders_account: account_0,
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account_0.delegate, Pubkey::default());
+    }
+
+    assert!(send_tx(
+        solana,
+        CancelAllOrdersInstruction {
+            open_orders_account: account_0,
+            market,
+            owner: delegate,
+        },
+    )
+    .await
+    .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_expand_open_orders() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        payer,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        account_0,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    let resize = |open_orders_count| ExpandOpenOrdersInstruction {
+        open_orders_account: account_0,
+        owner,
+        payer,
+        open_orders_count,
+    };
+
+    // TEST: Growing the account adds free slots and charges the rent
+    let lamports_before = solana.get_account_lamports(account_0).await;
+    send_tx(solana, resize(16)).await.unwrap();
+    {
+        let data = solana.get_account_data(account_0).await.unwrap();
+        assert_eq!(data.len(), OpenOrdersAccount::space(16).unwrap());
+        let open_orders_account = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account.open_orders.len(), 16);
+        assert!(open_orders_account
+            .open_orders
+            .iter()
+            .all(|oo| oo.is_free()));
+        assert!(solana.get_account_lamports(account_0).await > lamports_before);
+    }
+
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_0,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 10000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    // TEST: Slots in use can't be removed
+    assert_openbook_error(
+        &send_tx(solana, resize(0)).await,
+        OpenBookError::OpenOrdersSlotInUse.into(),
+        "the first slot holds an order".to_string(),
+    );
+
+    // TEST: Shrinking keeps the orders and refunds the rent
+    send_tx(solana, resize(4)).await.unwrap();
+    {
+        let data = solana.get_account_data(account_0).await.unwrap();
+        assert_eq!(data.len(), OpenOrdersAccount::space(4).unwrap());
+        let open_orders_account = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account.open_orders.len(), 4);
+        assert!(!open_orders_account.open_orders[0].is_free());
+        assert!(solana.get_account_lamports(account_0).await < lamports_before);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_open_orders_account_market() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        collect_fee_admin,
+        owner,
+        payer,
+        mints,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        tokens,
+        account_0,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    let open_orders_account = solana.get_account::<OpenOrdersAccount>(account_0).await;
+    assert_eq!(open_orders_account.market, market);
+
+    let market_2 = get_market_address(2);
+    let base_vault_2 = solana
+        .create_associated_token_account(&market_2, mints[0].pubkey)
+        .await;
+    let quote_vault_2 = solana
+        .create_associated_token_account(&market_2, mints[1].pubkey)
+        .await;
+
+    send_tx(
+        solana,
+        CreateMarketInstruction {
+            collect_fee_admin: collect_fee_admin.pubkey(),
+            open_orders_admin: None,
+            close_market_admin: None,
+            payer,
+            market_index: 2,
+            quote_lot_size: 10,
+            base_lot_size: 100,
+            maker_fee: -0.0002,
+            taker_fee: 0.0004,
+            base_mint: mints[0].pubkey,
+            quote_mint: mints[1].pubkey,
+            base_vault: base_vault_2,
+            quote_vault: quote_vault_2,
+            ..CreateMarketInstruction::with_new_book_and_queue(solana, &tokens[2]).await
+        },
+    )
+    .await
+    .unwrap();
+
+    // TEST: The account can't trade on another market
+    assert!(send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_0,
+            open_orders_admin: None,
+            market: market_2,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault: base_vault_2,
+            quote_vault: quote_vault_2,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 10000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .is_err());
+
+    // TEST: Funds only move through the vaults of the account's market
+    let deposit = DepositInstruction {
+        open_orders_account: account_0,
+        market,
+        base_vault: base_vault_2,
+        quote_vault: quote_vault_2,
+        token_base_account: Some(owner_token_0),
+        token_quote_account: Some(owner_token_1),
+        owner,
+        base_amount_lots: 1,
+        quote_amount_lots: 1,
+    };
+    assert!(send_tx(solana, deposit.clone()).await.is_err());
+
+    send_tx(
+        solana,
+        DepositInstruction {
+            base_vault,
+            quote_vault,
+            ..deposit
+        },
+    )
+    .await
+    .unwrap();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_migrate_v1_open_orders_account() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        collect_fee_admin,
+        owner,
+        payer,
+        mints,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        tokens,
+        account_0,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    send_tx(
+        solana,
+        DepositInstruction {
+            open_orders_account: account_0,
+            market,
+            base_vault,
+            quote_vault,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
+            owner,
+            base_amount_lots: 1,
+            quote_amount_lots: 1,
+        },
+    )
+    .await
+    .unwrap();
+
+    // Turn the account into one created by version 1, without a market
+    {
+        let mut data = solana.get_account_data(account_0).await.unwrap();
+        let fixed_end = 8 + std::mem::size_of::<OpenOrdersAccountFixed>();
+        let fixed: &mut OpenOrdersAccountFixed = bytemuck::from_bytes_mut(&mut data[8..fixed_end]);
+        fixed.market = Pubkey::default();
+        data[fixed_end] = 1;
+        solana.set_account_data(account_0, &data).await;
+    }
+
+    let settle_funds = SettleFundsInstruction {
+        owner,
+        market,
+        open_orders_account: account_0,
+        base_vault,
+        quote_vault,
+        token_base_account: Some(owner_token_0),
+        token_quote_account: Some(owner_token_1),
+        referrer: None,
+    };
+
+    // TEST: A version 1 account is rejected until it is migrated
+    assert!(send_tx(solana, settle_funds.clone()).await.is_err());
+
+    let market_2 = get_market_address(2);
+    let base_vault_2 = solana
+        .create_associated_token_account(&market_2, mints[0].pubkey)
+        .await;
+    let quote_vault_2 = solana
+        .create_associated_token_account(&market_2, mints[1].pubkey)
+        .await;
+    send_tx(
+        solana,
+        CreateMarketInstruction {
+            collect_fee_admin: collect_fee_admin.pubkey(),
+            open_orders_admin: None,
+            close_market_admin: None,
+            payer,
+            market_index: 2,
+            quote_lot_size: 10,
+            base_lot_size: 100,
+            maker_fee: -0.0002,
+            taker_fee: 0.0004,
+            base_mint: mints[0].pubkey,
+            quote_mint: mints[1].pubkey,
+            base_vault: base_vault_2,
+            quote_vault: quote_vault_2,
+            ..CreateMarketInstruction::with_new_book_and_queue(solana, &tokens[2]).await
+        },
+    )
+    .await
+    .unwrap();
+
+    // TEST: The market has to match the address seeds of the account
+    assert_openbook_error(
+        &send_tx(
+            solana,
+            MigrateOpenOrdersAccountInstruction {
+                open_orders_account: account_0,
+                market: market_2,
+            },
+        )
+        .await,
+        OpenBookError::OpenOrdersAccountNotMigratable.into(),
+        "the account was not derived from market_2".to_string(),
+    );
+
+    send_tx(
+        solana,
+        MigrateOpenOrdersAccountInstruction {
+            open_orders_account: account_0,
+            market,
+        },
+    )
+    .await
+    .unwrap();
+    {
+        let open_orders_account = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account.market, market);
+        assert_eq!(open_orders_account.header_version, 2);
+    }
+
+    // TEST: Migrated accounts can't be migrated again
+    assert_openbook_error(
+        &send_tx(
+            solana,
+            MigrateOpenOrdersAccountInstruction {
+                open_orders_account: account_0,
+                market,
+            },
+        )
+        .await,
+        OpenBookError::OpenOrdersAccountNotMigratable.into(),
+        "the account already has version 2".to_string(),
+    );
+
+    // TEST: The migrated account settles its funds
+    let base_before = solana.token_account_balance(owner_token_0).await;
+    let quote_before = solana.token_account_balance(owner_token_1).await;
+    send_tx(solana, settle_funds).await.unwrap();
+    assert_eq!(
+        solana.token_account_balance(owner_token_0).await - base_before,
+        100
+    );
+    assert_eq!(
+        solana.token_account_balance(owner_token_1).await - quote_before,
+        10
+    );
+
+    Ok(())
+}
@@ -51,6 +51,7 @@ async fn test_oracle_peg() -> Result<(), TransportError> {
             max_base_lots: 1,
             max_quote_lots_including_fees: 100_000,
             client_order_id: 0,
+            max_oracle_staleness_slots: -1,
         },
     )
     .await
@@ -98,6 +99,7 @@ async fn test_oracle_peg() -> Result<(), TransportError> {
             max_base_lots: 2,
             max_quote_lots_including_fees,
             client_order_id: 5,
+            max_oracle_staleness_slots: -1,
         },
     )
     .await
@@ -153,6 +155,7 @@ async fn test_oracle_peg() -> Result<(), TransportError> {
             max_base_lots: 1,
             max_quote_lots_including_fees: 100_000,
             client_order_id: 7,
+            max_oracle_staleness_slots: -1,
         },
     )
     .await
@@ -187,6 +190,7 @@ async fn test_oracle_peg() -> Result<(), TransportError> {
             max_base_lots: 2,
             max_quote_lots_including_fees: 100_000,
             client_order_id: 5,
+            max_oracle_staleness_slots: -1,
         },
     )
     .await
@@ -286,6 +290,7 @@ async fn test_oracle_peg() -> Result<(), TransportError> {
             max_base_lots: 2,
             max_quote_lots_including_fees: 100_000,
             client_order_id: 5,
+            max_oracle_staleness_slots: -1,
         },
     )
     .await
@@ -441,6 +446,7 @@ async fn test_oracle_peg_limit() -> Result<(), TransportError> {
             max_base_lots: 2,
             max_quote_lots_including_fees,
             client_order_id: 5,
+            max_oracle_staleness_slots: -1,
         },
     )
     .await
@@ -465,6 +471,7 @@ async fn test_oracle_peg_limit() -> Result<(), TransportError> {
             max_base_lots: 2,
             max_quote_lots_including_fees,
             client_order_id: 5,
+            max_oracle_staleness_slots: -1,
         },
     )
     .await
@@ -485,3 +492,259 @@ async fn test_oracle_peg_limit() -> Result<(), TransportError> {
     }
     Ok(())
 }
+
+#[tokio::test]
+async fn test_oracle_peg_staleness() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        collect_fee_admin,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        tokens,
+        account_0,
+        account_1,
+        bids,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize {
+        quote_lot_size: 10,
+        base_lot_size: 10000,
+        maker_fee: -0.0,
+        taker_fee: 0.0,
+        ..TestNewMarketInitialize::default()
+    })
+    .await?;
+    let solana = &context.solana.clone();
+
+    let (price_lots, asks) = {
+        let market = solana.get_account::<Market>(market).await;
+        (market.native_price_to_lot(I80F48::ONE), market.asks)
+    };
+
+    set_stub_oracle_price(solana, &tokens[0], collect_fee_admin, 1.0).await;
+
+    send_tx(
+        solana,
+        PlaceOrderPeggedInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_offset: 0,
+            peg_limit: price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 5,
+            max_oracle_staleness_slots: 100,
+        },
+    )
+    .await
+    .unwrap();
+
+    solana.advance_by_slots(200).await;
+
+    // TEST: Can't place a pegged order with a stale oracle
+    assert!(send_tx(
+        solana,
+        PlaceOrderPeggedInstruction {
+            open_orders_account: account_1,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_offset: 0,
+            peg_limit: price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 6,
+            max_oracle_staleness_slots: 100,
+        },
+    )
+    .await
+    .is_err());
+
+    // TEST: The stale pegged bid is skipped, the ask doesn't match and is posted
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_1,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_0,
+            base_vault,
+            quote_vault,
+            side: Side::Ask,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+    assert_eq!(bids_data.roots[1].leaf_count, 1);
+    let asks_data = solana.get_account_boxed::<BookSide>(asks).await;
+    assert_eq!(asks_data.roots[0].leaf_count, 1);
+
+    // TEST: Once the oracle is updated, the pegged bid matches again
+    set_stub_oracle_price(solana, &tokens[0], collect_fee_admin, 1.0).await;
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_1,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_0,
+            base_vault,
+            quote_vault,
+            side: Side::Ask,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+    assert_eq!(bids_data.roots[1].leaf_count, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_market_without_oracle() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        account_0,
+        account_1,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize {
+        oracle_bool: false,
+        ..TestNewMarketInitialize::default()
+    })
+    .await?;
+    let solana = &context.solana.clone();
+
+    {
+        let market = solana.get_account::<Market>(market).await;
+        assert_eq!(market.oracle(), None);
+    }
+
+    // TEST: Oracle pegged orders can't be placed
+    assert!(send_tx(
+        solana,
+        PlaceOrderPeggedInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_offset: -1,
+            peg_limit: price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 0,
+            max_oracle_staleness_slots: -1,
+        },
+    )
+    .await
+    .is_err());
+
+    // TEST: Fixed price orders are placed and matched as usual
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_0,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 10000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_1,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_0,
+            base_vault,
+            quote_vault,
+            side: Side::Ask,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 10000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(
+        solana,
+        ConsumeEventsInstruction {
+            consume_events_admin: None,
+            market,
+            open_orders_accounts: vec![account_0, account_1],
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account_0.position.base_free_native, 100);
+    }
+
+    Ok(())
+}
@@ -130,8 +130,8 @@ async fn test_inmediate_order() -> Result<(), TransportError> {
             open_orders_account: account_0,
             base_vault,
             quote_vault,
-            token_base_account: owner_token_0,
-            token_quote_account: owner_token_1,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
             referrer: None,
         },
     )
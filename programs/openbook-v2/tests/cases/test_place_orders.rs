@@ -0,0 +1,89 @@
+use super::*;
+
+#[tokio::test]
+async fn test_place_orders() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        account_0,
+        bids,
+        asks,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    let balance_base = solana.token_account_balance(owner_token_0).await;
+    let balance_quote = solana.token_account_balance(owner_token_1).await;
+
+    let order = |side, price_lots, client_order_id, expiry_timestamp| PlaceOrderArgs {
+        side,
+        price_lots,
+        max_base_lots: 1,
+        max_quote_lots_including_fees: 100_000,
+        client_order_id,
+        order_type: PlaceOrderType::Limit,
+        self_trade_behavior: SelfTradeBehavior::default(),
+        expiry_timestamp,
+    };
+
+    // A bid ladder, an ask and an already expired order
+    send_tx(
+        solana,
+        PlaceOrdersInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            base_vault,
+            quote_vault,
+            base_deposit_account: owner_token_0,
+            quote_deposit_account: owner_token_1,
+            orders: vec![
+                order(Side::Bid, price_lots, 1, 0),
+                order(Side::Bid, price_lots - 1000, 2, 0),
+                order(Side::Bid, price_lots - 2000, 3, 1),
+                order(Side::Ask, price_lots + 1000, 4, 0),
+            ],
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let open_orders_account = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        let mut client_ids = open_orders_account
+            .open_orders
+            .iter()
+            .filter(|oo| oo.id != 0)
+            .map(|oo| oo.client_id)
+            .collect::<Vec<_>>();
+        client_ids.sort();
+        assert_eq!(client_ids, vec![1, 2, 4]);
+        assert_eq!(open_orders_account.position.bids_base_lots, 2);
+        assert_eq!(open_orders_account.position.asks_base_lots, 1);
+
+        let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+        assert_eq!(bids_data.roots[0].leaf_count, 2);
+        let asks_data = solana.get_account_boxed::<BookSide>(asks).await;
+        assert_eq!(asks_data.roots[0].leaf_count, 1);
+    }
+
+    // Funds for all orders were deposited at once
+    assert_eq!(
+        solana.token_account_balance(owner_token_0).await,
+        balance_base - 100
+    );
+    assert_eq!(
+        solana.token_account_balance(owner_token_1).await,
+        balance_quote - 100_000 - 90_000
+    );
+    assert_eq!(solana.token_account_balance(base_vault).await, 100);
+    assert_eq!(solana.token_account_balance(quote_vault).await, 190_000);
+
+    Ok(())
+}
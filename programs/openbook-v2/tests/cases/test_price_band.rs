@@ -0,0 +1,169 @@
+use super::*;
+
+#[tokio::test]
+async fn test_price_band() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        market,
+        market_admin,
+        base_vault,
+        quote_vault,
+        account_0,
+        account_1,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize {
+        quote_lot_size: 10,
+        base_lot_size: 10000,
+        market_admin_bool: true,
+        ..TestNewMarketInitialize::default()
+    })
+    .await?;
+    let solana = &context.solana.clone();
+
+    // Lot price at the oracle price
+    let price_lots = {
+        let market = solana.get_account::<Market>(market).await;
+        market.native_price_to_lot(I80F48::ONE)
+    };
+    assert_eq!(price_lots, 1000);
+
+    // Bands can't be wider than 100%
+    for edit in [
+        EditMarketInstruction {
+            taker_price_band_bps_opt: Some(10_001),
+            ..EditMarketInstruction::new(market_admin, market)
+        },
+        EditMarketInstruction {
+            maker_price_band_bps_opt: Some(10_001),
+            ..EditMarketInstruction::new(market_admin, market)
+        },
+    ] {
+        assert!(send_tx(solana, edit).await.is_err());
+    }
+
+    // Takers may fill up to 1% and orders be posted up to 5% away from the oracle
+    send_tx(
+        solana,
+        EditMarketInstruction {
+            taker_price_band_bps_opt: Some(100),
+            maker_price_band_bps_opt: Some(500),
+            ..EditMarketInstruction::new(market_admin, market)
+        },
+    )
+    .await
+    .unwrap();
+
+    for price_lots in [price_lots, price_lots * 102 / 100] {
+        send_tx(
+            solana,
+            PlaceOrderInstruction {
+                open_orders_account: account_0,
+                open_orders_admin: None,
+                market,
+                owner,
+                token_deposit_account: owner_token_0,
+                base_vault,
+                quote_vault,
+                side: Side::Ask,
+                price_lots,
+                max_base_lots: 1,
+                max_quote_lots_including_fees: 100_000,
+                client_order_id: 0,
+                expiry_timestamp: 0,
+                order_type: PlaceOrderType::Limit,
+                self_trade_behavior: SelfTradeBehavior::default(),
+                remainings: vec![],
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    // TEST: A bid crossing both asks only fills the one within the taker band
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_1,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots: price_lots * 104 / 100,
+            max_base_lots: 2,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let market = solana.get_account::<Market>(market).await;
+        let asks = solana.get_account_boxed::<BookSide>(market.asks).await;
+        let bids = solana.get_account_boxed::<BookSide>(market.bids).await;
+        assert_eq!(asks.roots[0].leaf_count, 1);
+        // The remainder would cross the book and isn't posted
+        assert_eq!(bids.roots[0].leaf_count, 0);
+    }
+
+    // TEST: Orders can't be posted outside of the maker band
+    assert!(send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_1,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_0,
+            base_vault,
+            quote_vault,
+            side: Side::Ask,
+            price_lots: price_lots * 94 / 100,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .is_err());
+
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_1,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots: price_lots * 96 / 100,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 100_000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use super::*;
+
+#[tokio::test]
+async fn test_prune_orders() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        market,
+        market_admin,
+        base_vault,
+        quote_vault,
+        price_lots,
+        account_0,
+        account_1,
+        bids,
+        asks,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize {
+        market_admin_bool: true,
+        ..TestNewMarketInitialize::default()
+    })
+    .await?;
+    let solana = &context.solana.clone();
+
+    let place_order = |open_orders_account, side, price_lots| PlaceOrderInstruction {
+        open_orders_account,
+        open_orders_admin: None,
+        market,
+        owner,
+        token_deposit_account: match side {
+            Side::Bid => owner_token_1,
+            Side::Ask => owner_token_0,
+        },
+        base_vault,
+        quote_vault,
+        side,
+        price_lots,
+        max_base_lots: 1,
+        max_quote_lots_including_fees: 100_000,
+        client_order_id: 0,
+        expiry_timestamp: 0,
+        order_type: PlaceOrderType::Limit,
+        self_trade_behavior: SelfTradeBehavior::default(),
+        remainings: vec![],
+    };
+
+    send_tx(solana, place_order(account_0, Side::Bid, price_lots - 100))
+        .await
+        .unwrap();
+    send_tx(solana, place_order(account_1, Side::Ask, price_lots))
+        .await
+        .unwrap();
+
+    let now = solana.get_clock().await.unix_timestamp;
+    assert!(send_tx(
+        solana,
+        EditMarketInstruction {
+            time_expiry_opt: Some(now - 1),
+            ..EditMarketInstruction::new(market_admin, market)
+        },
+    )
+    .await
+    .is_err());
+    send_tx(
+        solana,
+        EditMarketInstruction {
+            time_expiry_opt: Some(now + 10),
+            ..EditMarketInstruction::new(market_admin, market)
+        },
+    )
+    .await
+    .unwrap();
+
+    // TEST: Orders can't be pruned before the market expires
+    assert_openbook_error(
+        &send_tx(
+            solana,
+            PruneOrdersInstruction {
+                open_orders_account: account_0,
+                market,
+            },
+        )
+        .await,
+        OpenBookError::MarketHasNotExpired.into(),
+        "market has not expired".to_string(),
+    );
+
+    solana.advance_clock(20).await;
+
+    // TEST: New orders are rejected after the expiry
+    assert_openbook_error(
+        &send_tx(solana, place_order(account_0, Side::Bid, price_lots - 100)).await,
+        OpenBookError::MarketHasExpired.into(),
+        "market has expired".to_string(),
+    );
+
+    // TEST: Anyone can prune the resting orders, funds are credited back
+    for open_orders_account in [account_0, account_1] {
+        send_tx(
+            solana,
+            PruneOrdersInstruction {
+                open_orders_account,
+                market,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    {
+        let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+        assert_eq!(bids_data.roots[0].leaf_count, 0);
+        let asks_data = solana.get_account_boxed::<BookSide>(asks).await;
+        assert_eq!(asks_data.roots[0].leaf_count, 0);
+
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        let open_orders_account_1 = solana.get_account::<OpenOrdersAccount>(account_1).await;
+        assert_eq!(open_orders_account_0.position.bids_base_lots, 0);
+        assert_eq!(open_orders_account_1.position.asks_base_lots, 0);
+        assert_eq!(open_orders_account_0.position.quote_free_native, 99_000);
+        assert_eq!(open_orders_account_1.position.base_free_native, 100);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use super::*;
+
+#[tokio::test]
+async fn test_referrer() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        collect_fee_admin,
+        owner,
+        payer,
+        mints,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        account_0,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    let referrer = send_tx(
+        solana,
+        CreateReferrerInstruction {
+            referrer_id: 7,
+            authority: owner,
+            token_account: owner_token_0,
+            payer,
+        },
+    )
+    .await
+    .unwrap()
+    .referrer;
+
+    assert_eq!(referrer, Referrer::address(7).0);
+    {
+        let referrer = solana.get_account::<Referrer>(referrer).await;
+        assert_eq!(referrer.referrer_id, 7);
+        assert_eq!(referrer.authority, owner.pubkey());
+        assert_eq!(referrer.token_account, owner_token_0);
+        assert_eq!(referrer.mint, mints[0].pubkey);
+    }
+
+    let settle_funds = SettleFundsInstruction {
+        owner,
+        market,
+        open_orders_account: account_0,
+        base_vault,
+        quote_vault,
+        token_base_account: Some(owner_token_0),
+        token_quote_account: Some(owner_token_1),
+        referrer: Some(referrer),
+    };
+
+    // TEST: Rebates can only go to a token account of the quote mint
+    assert_openbook_error(
+        &send_tx(solana, settle_funds.clone()).await,
+        OpenBookError::InvalidReferrer.into(),
+        "referrer token account is not a quote token account".to_string(),
+    );
+
+    // TEST: Only the authority can change the token account
+    assert!(send_tx(
+        solana,
+        EditReferrerInstruction {
+            referrer,
+            authority: collect_fee_admin,
+            token_account: owner_token_1,
+        },
+    )
+    .await
+    .is_err());
+
+    send_tx(
+        solana,
+        EditReferrerInstruction {
+            referrer,
+            authority: owner,
+            token_account: owner_token_1,
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(solana, settle_funds).await.unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,259 @@
+use super::*;
+
+#[tokio::test]
+async fn test_settle_funds_permissionless() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        mints,
+        market,
+        market_admin,
+        base_vault,
+        quote_vault,
+        account_0,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize {
+        market_admin_bool: true,
+        ..TestNewMarketInitialize::default()
+    })
+    .await?;
+    let solana = &context.solana.clone();
+
+    let caller = context.users[2].key;
+    let caller_token_1 = context.users[2].token_accounts[1];
+
+    let owner_base_ata = solana
+        .create_associated_token_account(&owner.pubkey(), mints[0].pubkey)
+        .await;
+    let owner_quote_ata = solana
+        .create_associated_token_account(&owner.pubkey(), mints[1].pubkey)
+        .await;
+
+    send_tx(
+        solana,
+        DepositInstruction {
+            open_orders_account: account_0,
+            market,
+            base_vault,
+            quote_vault,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
+            owner,
+            base_amount_lots: 10,
+            quote_amount_lots: 200,
+        },
+    )
+    .await
+    .unwrap();
+
+    let now = solana.get_clock().await.unix_timestamp;
+    send_tx(
+        solana,
+        EditMarketInstruction {
+            time_expiry_opt: Some(now + 10),
+            ..EditMarketInstruction::new(market_admin, market)
+        },
+    )
+    .await
+    .unwrap();
+
+    let settle = SettleFundsPermissionlessInstruction {
+        caller,
+        open_orders_account: account_0,
+        market,
+        caller_quote_account: caller_token_1,
+    };
+
+    // TEST: Active accounts of live markets can't be settled by others
+    assert_openbook_error(
+        &send_tx(solana, settle.clone()).await,
+        OpenBookError::OpenOrdersAccountNotIdle.into(),
+        "market has not expired and the account is active".to_string(),
+    );
+
+    solana.advance_clock(20).await;
+
+    // TEST: Once the market expired, anyone settles into the owner's ATAs without a bounty
+    let caller_balance_before = solana.token_account_balance(caller_token_1).await;
+    send_tx(solana, settle).await.unwrap();
+
+    assert_eq!(solana.token_account_balance(owner_base_ata).await, 1000);
+    assert_eq!(solana.token_account_balance(owner_quote_ata).await, 2000);
+    assert_eq!(
+        solana.token_account_balance(caller_token_1).await,
+        caller_balance_before
+    );
+    {
+        let market = solana.get_account::<Market>(market).await;
+        assert_eq!(market.base_deposit_total, 0);
+        assert_eq!(market.quote_deposit_total, 0);
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account_0.position.base_free_native, 0);
+        assert_eq!(open_orders_account_0.position.quote_free_native, 0);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_settle_funds_permissionless_idle() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        owner_token_0,
+        owner_token_1,
+        mints,
+        market,
+        base_vault,
+        quote_vault,
+        account_0,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    let caller = context.users[2].key;
+    let caller_token_1 = context.users[2].token_accounts[1];
+
+    solana
+        .create_associated_token_account(&owner.pubkey(), mints[0].pubkey)
+        .await;
+    let owner_quote_ata = solana
+        .create_associated_token_account(&owner.pubkey(), mints[1].pubkey)
+        .await;
+
+    send_tx(
+        solana,
+        DepositInstruction {
+            open_orders_account: account_0,
+            market,
+            base_vault,
+            quote_vault,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
+            owner,
+            base_amount_lots: 10,
+            quote_amount_lots: 200,
+        },
+    )
+    .await
+    .unwrap();
+
+    let settle = SettleFundsPermissionlessInstruction {
+        caller,
+        open_orders_account: account_0,
+        market,
+        caller_quote_account: caller_token_1,
+    };
+
+    let last_activity_timestamp = solana
+        .get_account::<OpenOrdersAccount>(account_0)
+        .await
+        .last_activity_timestamp;
+    solana
+        .set_clock_unix_timestamp(
+            (last_activity_timestamp + OPEN_ORDERS_ACCOUNT_IDLE_SECONDS) as i64,
+        )
+        .await;
+
+    // TEST: Settling an idle account pays the caller a bounty out of the quote funds
+    let caller_balance_before = solana.token_account_balance(caller_token_1).await;
+    send_tx(solana, settle).await.unwrap();
+
+    let bounty = SETTLE_FUNDS_BOUNTY_QUOTE_LOTS * 10;
+    assert_eq!(
+        solana.token_account_balance(owner_quote_ata).await,
+        2000 - bounty
+    );
+    assert_eq!(
+        solana.token_account_balance(caller_token_1).await,
+        caller_balance_before + bounty
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_owner_instructions_refresh_activity() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        account_0,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_0,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 10000,
+            client_order_id: 1,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    // TEST: Cancelling an order refreshes the last activity
+    solana.advance_clock(100).await;
+    let before_ts = solana.get_clock().await.unix_timestamp as u64;
+    send_tx(
+        solana,
+        CancelOrderByClientOrderIdInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            client_order_id: 1,
+        },
+    )
+    .await
+    .unwrap();
+    {
+        // The clock of the test validator can move on after the transaction
+        let now_ts = solana.get_clock().await.unix_timestamp as u64;
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert!(open_orders_account_0.last_activity_timestamp >= before_ts);
+        assert!(open_orders_account_0.last_activity_timestamp <= now_ts);
+    }
+
+    // TEST: Setting a delegate refreshes the last activity
+    solana.advance_clock(100).await;
+    let before_ts = solana.get_clock().await.unix_timestamp as u64;
+    send_tx(
+        solana,
+        SetDelegateInstruction {
+            delegate_account: Some(context.users[2].key.pubkey()),
+            owner,
+            open_orders_account: account_0,
+        },
+    )
+    .await
+    .unwrap();
+    {
+        let now_ts = solana.get_clock().await.unix_timestamp as u64;
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert!(open_orders_account_0.last_activity_timestamp >= before_ts);
+        assert!(open_orders_account_0.last_activity_timestamp <= now_ts);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use super::*;
+
+#[tokio::test]
+async fn test_stable_price() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        collect_fee_admin,
+        tokens,
+        account_0,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    // The stable price starts out at the oracle price
+    let last_update_timestamp = {
+        let market = solana.get_account::<Market>(market).await;
+        assert_eq!(market.stable_price_model.stable_price, 1.0);
+        market.stable_price_model.last_update_timestamp
+    };
+
+    solana.advance_clock(60).await;
+    set_stub_oracle_price(solana, &tokens[0], collect_fee_admin, 1.1).await;
+
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_0,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 10000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    // The stable price moves towards the new oracle price, but its growth is limited
+    {
+        let market = solana.get_account::<Market>(market).await;
+        let stable_price = market.stable_price_model.stable_price;
+        assert!(stable_price > 1.0 && stable_price < 1.1);
+        assert!(market.stable_price_model.last_update_timestamp > last_update_timestamp);
+    }
+
+    Ok(())
+}
@@ -56,7 +56,7 @@ async fn test_take_ask_order() -> Result<(), TransportError> {
             market,
             owner,
             token_deposit_account: owner_token_0,
-            token_receiver_account: owner_token_1,
+            token_receiver_account: Some(owner_token_1),
             base_vault,
             quote_vault,
             side: Side::Ask,
@@ -66,6 +66,7 @@ async fn test_take_ask_order() -> Result<(), TransportError> {
 
             client_order_id: 0,
             expiry_timestamp: 0,
+            order_type: PlaceOrderType::ImmediateOrCancel,
             referrer: None,
             open_orders_admin: None,
         },
@@ -128,8 +129,8 @@ async fn test_take_ask_order() -> Result<(), TransportError> {
             open_orders_account: account_0,
             base_vault,
             quote_vault,
-            token_base_account: owner_token_0,
-            token_quote_account: owner_token_1,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
             referrer: None,
         },
     )
@@ -155,6 +156,7 @@ async fn test_take_bid_order() -> Result<(), TransportError> {
         context,
         collect_fee_admin,
         owner,
+        payer,
         mints,
         owner_token_0,
         owner_token_1,
@@ -204,6 +206,18 @@ async fn test_take_bid_order() -> Result<(), TransportError> {
         .create_associated_token_account(&collect_fee_admin.pubkey(), mints[1].pubkey)
         .await;
     let balance_referral = solana.token_account_balance(admin_token_1).await;
+    let referrer = send_tx(
+        solana,
+        CreateReferrerInstruction {
+            referrer_id: 0,
+            authority: collect_fee_admin,
+            token_account: admin_token_1,
+            payer,
+        },
+    )
+    .await
+    .unwrap()
+    .referrer;
 
     send_tx(
         solana,
@@ -212,7 +226,7 @@ async fn test_take_bid_order() -> Result<(), TransportError> {
             market,
             owner,
             token_deposit_account: owner_token_1,
-            token_receiver_account: owner_token_0,
+            token_receiver_account: Some(owner_token_0),
             base_vault,
             quote_vault,
             side: Side::Bid,
@@ -222,7 +236,8 @@ async fn test_take_bid_order() -> Result<(), TransportError> {
 
             client_order_id: 0,
             expiry_timestamp: 0,
-            referrer: Some(admin_token_1),
+            order_type: PlaceOrderType::ImmediateOrCancel,
+            referrer: Some(referrer),
         },
     )
     .await
@@ -287,8 +302,8 @@ async fn test_take_bid_order() -> Result<(), TransportError> {
             open_orders_account: account_0,
             base_vault,
             quote_vault,
-            token_base_account: owner_token_0,
-            token_quote_account: owner_token_1,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
             referrer: None,
         },
     )
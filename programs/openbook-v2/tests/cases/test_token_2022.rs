@@ -0,0 +1,229 @@
+use super::*;
+
+#[tokio::test]
+async fn test_transfer_fee_deposit_and_settle() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        collect_fee_admin,
+        close_market_admin,
+        owner,
+        mints,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        price_lots,
+        tokens,
+        account_0,
+        account_1,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize {
+        close_market_admin_bool: true,
+        transfer_fee_basis_points: Some(100),
+        ..TestNewMarketInitialize::default()
+    })
+    .await?;
+    let solana = &context.solana.clone();
+
+    // Set the initial oracle price
+    set_stub_oracle_price(solana, &tokens[1], collect_fee_admin, 1000.0).await;
+
+    //
+    // TEST: The owner pays the transfer fee on top, the vaults receive the full deposit
+    //
+    let owner_base_before = solana.token_account_balance(owner_token_0).await;
+    let owner_quote_before = solana.token_account_balance(owner_token_1).await;
+
+    send_tx(
+        solana,
+        DepositInstruction {
+            open_orders_account: account_0,
+            market,
+            base_vault,
+            quote_vault,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
+            owner,
+            base_amount_lots: 10,
+            quote_amount_lots: 10000,
+        },
+    )
+    .await
+    .unwrap();
+
+    // 1% of 1011 rounds up to 11, of 101011 to 1011
+    assert_eq!(
+        owner_base_before - solana.token_account_balance(owner_token_0).await,
+        1011
+    );
+    assert_eq!(
+        owner_quote_before - solana.token_account_balance(owner_token_1).await,
+        101011
+    );
+    {
+        let market = solana.get_account::<Market>(market).await;
+        assert_eq!(market.base_deposit_total, 1000);
+        assert_eq!(market.quote_deposit_total, 100000);
+        assert_eq!(solana.token_account_balance(base_vault).await, 1000);
+        assert_eq!(solana.token_account_balance(quote_vault).await, 100000);
+
+        let open_orders_account_0 = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account_0.position.base_free_native, 1000);
+        assert_eq!(open_orders_account_0.position.quote_free_native, 100000);
+    }
+
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_0,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 10000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_1,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_0,
+            base_vault,
+            quote_vault,
+            side: Side::Ask,
+            price_lots,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 10000,
+            client_order_id: 0,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(
+        solana,
+        ConsumeEventsInstruction {
+            consume_events_admin: None,
+            market,
+            open_orders_accounts: vec![account_0, account_1],
+        },
+    )
+    .await
+    .unwrap();
+
+    {
+        let market = solana.get_account::<Market>(market).await;
+        assert_eq!(market.base_deposit_total, 1100);
+        assert_eq!(
+            solana.token_account_balance(base_vault).await,
+            market.base_deposit_total + market.base_fees_accrued
+        );
+        assert_eq!(
+            solana.token_account_balance(quote_vault).await,
+            market.quote_deposit_total + market.quote_fees_accrued
+        );
+    }
+
+    //
+    // TEST: Settling pays out the free funds, the receiver bears the transfer fee
+    //
+    let owner_base_before = solana.token_account_balance(owner_token_0).await;
+    let owner_quote_before = solana.token_account_balance(owner_token_1).await;
+
+    for open_orders_account in [account_0, account_1] {
+        send_tx(
+            solana,
+            SettleFundsInstruction {
+                owner,
+                market,
+                open_orders_account,
+                base_vault,
+                quote_vault,
+                token_base_account: Some(owner_token_0),
+                token_quote_account: Some(owner_token_1),
+                referrer: None,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    // account_0 settles 1100 base and the 20 quote rebate, account_1 the 99960 quote
+    assert_eq!(
+        solana.token_account_balance(owner_token_0).await - owner_base_before,
+        1100 - 11
+    );
+    assert_eq!(
+        solana.token_account_balance(owner_token_1).await - owner_quote_before,
+        20 + 99960 - 1 - 1000
+    );
+    {
+        let market = solana.get_account::<Market>(market).await;
+        assert_eq!(market.base_deposit_total, 0);
+        assert_eq!(market.quote_deposit_total, 0);
+        assert_eq!(market.quote_fees_accrued, 20);
+        assert_eq!(solana.token_account_balance(base_vault).await, 0);
+        assert_eq!(solana.token_account_balance(quote_vault).await, 20);
+    }
+
+    //
+    // TEST: The market closes although the vaults hold withheld transfer fees
+    //
+    let admin_token_0 = solana
+        .create_associated_token_account(&collect_fee_admin.pubkey(), mints[0].pubkey)
+        .await;
+    let admin_token_1 = solana
+        .create_associated_token_account(&collect_fee_admin.pubkey(), mints[1].pubkey)
+        .await;
+    send_tx(
+        solana,
+        SweepFeesInstruction {
+            collect_fee_admin,
+            market,
+            quote_vault,
+            token_base_receiver_account: admin_token_0,
+            token_receiver_account: admin_token_1,
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(
+        solana,
+        CloseMarketInstruction {
+            close_market_admin,
+            market,
+            sol_destination: owner.pubkey(),
+            token_base_receiver_account: owner_token_0,
+            token_quote_receiver_account: owner_token_1,
+        },
+    )
+    .await
+    .unwrap();
+
+    assert!(solana.get_account_data(base_vault).await.is_none());
+    assert!(solana.get_account_data(quote_vault).await.is_none());
+
+    Ok(())
+}
@@ -0,0 +1,521 @@
+use super::*;
+
+#[tokio::test]
+async fn test_trigger_order() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        collect_fee_admin,
+        owner,
+        payer,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        tokens,
+        account_0,
+        account_1,
+        bids,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+    let keeper = context.users[2].key;
+
+    // The oracle price is 1.0, 10 lots
+    send_tx(
+        solana,
+        PlaceOrderInstruction {
+            open_orders_account: account_1,
+            open_orders_admin: None,
+            market,
+            owner,
+            token_deposit_account: owner_token_1,
+            base_vault,
+            quote_vault,
+            side: Side::Bid,
+            price_lots: 8,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 100,
+            client_order_id: 1,
+            expiry_timestamp: 0,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            remainings: vec![],
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(
+        solana,
+        CreateTriggerOrdersAccountInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            payer,
+        },
+    )
+    .await
+    .unwrap();
+    let trigger_orders_account = get_trigger_orders_address(account_0);
+
+    let stop_loss = PlaceTriggerOrderInstruction {
+        open_orders_account: account_0,
+        market,
+        owner,
+        open_orders_admin: None,
+        token_deposit_account: owner_token_0,
+        base_vault,
+        quote_vault,
+        condition: TriggerCondition::OraclePriceBelow,
+        trigger_price_lots: 8,
+        order: PlaceOrderArgs {
+            side: Side::Ask,
+            price_lots: 1,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 100,
+            client_order_id: 2,
+            order_type: PlaceOrderType::ImmediateOrCancel,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            expiry_timestamp: 0,
+        },
+        limit: 10,
+    };
+
+    // TEST: Placing a trigger order locks its funds off the book
+    let balance_base = solana.token_account_balance(owner_token_0).await;
+    send_tx(solana, stop_loss.clone()).await.unwrap();
+    assert_eq!(
+        solana.token_account_balance(owner_token_0).await,
+        balance_base - 100
+    );
+    {
+        let trigger_orders = solana
+            .get_account::<TriggerOrdersAccount>(trigger_orders_account)
+            .await;
+        assert_eq!(trigger_orders.trigger_orders[0].is_active, 1);
+        assert_eq!(trigger_orders.trigger_orders[0].locked_native, 100);
+
+        let open_orders_account = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account.position.asks_base_lots, 0);
+    }
+
+    let execute = ExecuteTriggerOrderInstruction {
+        open_orders_account: account_0,
+        market,
+        keeper,
+        slot: 0,
+    };
+
+    // TEST: The order can't be executed before the oracle price crosses the trigger
+    assert!(send_tx(solana, execute.clone()).await.is_err());
+
+    // TEST: Once triggered, the order is placed and the keeper receives the bounty
+    set_stub_oracle_price(solana, &tokens[0], collect_fee_admin, 0.5).await;
+    let keeper_lamports = solana.get_account_lamports(keeper.pubkey()).await;
+    send_tx(solana, execute.clone()).await.unwrap();
+    assert_eq!(
+        solana.get_account_lamports(keeper.pubkey()).await,
+        keeper_lamports + TRIGGER_ORDER_BOUNTY_LAMPORTS
+    );
+    {
+        let trigger_orders = solana
+            .get_account::<TriggerOrdersAccount>(trigger_orders_account)
+            .await;
+        assert_eq!(trigger_orders.trigger_orders[0].is_active, 0);
+
+        let open_orders_account = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account.position.base_free_native, 0);
+        assert!(open_orders_account.position.quote_free_native > 0);
+
+        let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+        assert_eq!(bids_data.roots[0].leaf_count, 0);
+    }
+    assert!(send_tx(solana, execute.clone()).await.is_err());
+
+    // TEST: Triggered orders that neither match nor rest on the book aren't executed
+    send_tx(solana, stop_loss.clone()).await.unwrap();
+    assert_openbook_error(
+        &send_tx(solana, execute).await,
+        OpenBookError::TriggerOrderNotExecuted.into(),
+        "trigger order not executed".to_string(),
+    );
+
+    // TEST: Cancelling a trigger order frees its funds
+    send_tx(
+        solana,
+        CancelTriggerOrderInstruction {
+            open_orders_account: account_0,
+            owner,
+            slot: 0,
+        },
+    )
+    .await
+    .unwrap();
+    {
+        let trigger_orders = solana
+            .get_account::<TriggerOrdersAccount>(trigger_orders_account)
+            .await;
+        assert_eq!(trigger_orders.trigger_orders[0].is_active, 0);
+
+        let open_orders_account = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account.position.base_free_native, 100);
+    }
+
+    // TEST: Bids without a quote limit lock their price and the highest fee
+    let take_profit = PlaceTriggerOrderInstruction {
+        token_deposit_account: owner_token_1,
+        condition: TriggerCondition::OraclePriceAbove,
+        trigger_price_lots: 20,
+        order: PlaceOrderArgs {
+            side: Side::Bid,
+            price_lots: 10,
+            max_quote_lots_including_fees: i64::MAX,
+            ..stop_loss.order
+        },
+        ..stop_loss
+    };
+    send_tx(solana, take_profit).await.unwrap();
+    {
+        let trigger_orders = solana
+            .get_account::<TriggerOrdersAccount>(trigger_orders_account)
+            .await;
+        // 10 lots, 1 lot of taker fees and 1 lot for rounding
+        assert_eq!(trigger_orders.trigger_orders[0].locked_native, 120);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_trigger_order_market_expiry() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        payer,
+        owner_token_0,
+        market,
+        market_admin,
+        base_vault,
+        quote_vault,
+        account_0,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize {
+        market_admin_bool: true,
+        ..TestNewMarketInitialize::default()
+    })
+    .await?;
+    let solana = &context.solana.clone();
+    let keeper = context.users[2].key;
+
+    send_tx(
+        solana,
+        CreateTriggerOrdersAccountInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            payer,
+        },
+    )
+    .await
+    .unwrap();
+    let trigger_orders_account = get_trigger_orders_address(account_0);
+
+    send_tx(
+        solana,
+        PlaceTriggerOrderInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            open_orders_admin: None,
+            token_deposit_account: owner_token_0,
+            base_vault,
+            quote_vault,
+            condition: TriggerCondition::OraclePriceBelow,
+            trigger_price_lots: 8,
+            order: PlaceOrderArgs {
+                side: Side::Ask,
+                price_lots: 1,
+                max_base_lots: 1,
+                max_quote_lots_including_fees: 100,
+                client_order_id: 2,
+                order_type: PlaceOrderType::ImmediateOrCancel,
+                self_trade_behavior: SelfTradeBehavior::default(),
+                expiry_timestamp: 0,
+            },
+            limit: 10,
+        },
+    )
+    .await
+    .unwrap();
+
+    let execute = ExecuteTriggerOrderInstruction {
+        open_orders_account: account_0,
+        market,
+        keeper,
+        slot: 0,
+    };
+    assert_openbook_error(
+        &send_tx(solana, execute.clone()).await,
+        OpenBookError::TriggerConditionNotMet.into(),
+        "trigger condition not met".to_string(),
+    );
+
+    let now = solana.get_clock().await.unix_timestamp;
+    send_tx(
+        solana,
+        EditMarketInstruction {
+            time_expiry_opt: Some(now + 10),
+            ..EditMarketInstruction::new(market_admin, market)
+        },
+    )
+    .await
+    .unwrap();
+    solana.advance_clock(20).await;
+
+    // TEST: Once the market expired anyone can remove trigger orders, freeing their funds
+    let keeper_lamports = solana.get_account_lamports(keeper.pubkey()).await;
+    send_tx(solana, execute).await.unwrap();
+    assert_eq!(
+        solana.get_account_lamports(keeper.pubkey()).await,
+        keeper_lamports + TRIGGER_ORDER_BOUNTY_LAMPORTS
+    );
+    {
+        let trigger_orders = solana
+            .get_account::<TriggerOrdersAccount>(trigger_orders_account)
+            .await;
+        assert_eq!(trigger_orders.trigger_orders[0].is_active, 0);
+
+        let open_orders_account = solana.get_account::<OpenOrdersAccount>(account_0).await;
+        assert_eq!(open_orders_account.position.base_free_native, 100);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_close_with_trigger_orders() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        payer,
+        owner_token_0,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        account_0,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize::default()).await?;
+    let solana = &context.solana.clone();
+
+    send_tx(
+        solana,
+        CreateTriggerOrdersAccountInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            payer,
+        },
+    )
+    .await
+    .unwrap();
+    let trigger_orders_account = get_trigger_orders_address(account_0);
+
+    send_tx(
+        solana,
+        PlaceTriggerOrderInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            open_orders_admin: None,
+            token_deposit_account: owner_token_0,
+            base_vault,
+            quote_vault,
+            condition: TriggerCondition::OraclePriceBelow,
+            trigger_price_lots: 8,
+            order: PlaceOrderArgs {
+                side: Side::Ask,
+                price_lots: 1,
+                max_base_lots: 1,
+                max_quote_lots_including_fees: 100,
+                client_order_id: 1,
+                order_type: PlaceOrderType::ImmediateOrCancel,
+                self_trade_behavior: SelfTradeBehavior::default(),
+                expiry_timestamp: 0,
+            },
+            limit: 10,
+        },
+    )
+    .await
+    .unwrap();
+
+    let close_open_orders_account = CloseOpenOrdersAccountInstruction {
+        open_orders_account: account_0,
+        owner,
+        sol_destination: owner.pubkey(),
+    };
+    let close_trigger_orders_account = CloseTriggerOrdersAccountInstruction {
+        open_orders_account: account_0,
+        owner,
+        sol_destination: owner.pubkey(),
+    };
+
+    // TEST: Neither account can be closed while a trigger order holds funds
+    assert_openbook_error(
+        &send_tx(solana, close_open_orders_account.clone()).await,
+        OpenBookError::ActiveTriggerOrders.into(),
+        "open orders account with active trigger orders".to_string(),
+    );
+    assert_openbook_error(
+        &send_tx(solana, close_trigger_orders_account.clone()).await,
+        OpenBookError::ActiveTriggerOrders.into(),
+        "trigger orders account with active trigger orders".to_string(),
+    );
+
+    send_tx(
+        solana,
+        CancelTriggerOrderInstruction {
+            open_orders_account: account_0,
+            owner,
+            slot: 0,
+        },
+    )
+    .await
+    .unwrap();
+    send_tx(
+        solana,
+        SettleFundsInstruction {
+            owner,
+            market,
+            open_orders_account: account_0,
+            base_vault,
+            quote_vault,
+            token_base_account: Some(owner_token_0),
+            token_quote_account: Some(owner_token_1),
+            referrer: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    // TEST: Once the trigger orders are gone both accounts close and return their lamports
+    let trigger_orders_lamports = solana.get_account_lamports(trigger_orders_account).await;
+    let owner_lamports = solana.get_account_lamports(owner.pubkey()).await;
+    send_tx(solana, close_trigger_orders_account).await.unwrap();
+    assert!(solana
+        .get_account_data(trigger_orders_account)
+        .await
+        .is_none());
+    assert_eq!(
+        solana.get_account_lamports(owner.pubkey()).await,
+        owner_lamports + trigger_orders_lamports
+    );
+
+    send_tx(solana, close_open_orders_account).await.unwrap();
+    assert!(solana.get_account_data(account_0).await.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_permissioned_trigger_order() -> Result<(), TransportError> {
+    let TestInitialize {
+        context,
+        owner,
+        payer,
+        owner_token_1,
+        market,
+        base_vault,
+        quote_vault,
+        account_0,
+        open_orders_admin,
+        bids,
+        ..
+    } = TestContext::new_with_market(TestNewMarketInitialize {
+        open_orders_admin_bool: true,
+        ..TestNewMarketInitialize::default()
+    })
+    .await?;
+    let solana = &context.solana.clone();
+    let keeper = context.users[2].key;
+
+    send_tx(
+        solana,
+        CreateTriggerOrdersAccountInstruction {
+            open_orders_account: account_0,
+            market,
+            owner,
+            payer,
+        },
+    )
+    .await
+    .unwrap();
+    let trigger_orders_account = get_trigger_orders_address(account_0);
+
+    // The oracle price is already above the trigger, the bid rests on the book
+    let place = PlaceTriggerOrderInstruction {
+        open_orders_account: account_0,
+        market,
+        owner,
+        open_orders_admin: None,
+        token_deposit_account: owner_token_1,
+        base_vault,
+        quote_vault,
+        condition: TriggerCondition::OraclePriceAbove,
+        trigger_price_lots: 5,
+        order: PlaceOrderArgs {
+            side: Side::Bid,
+            price_lots: 1,
+            max_base_lots: 1,
+            max_quote_lots_including_fees: 100,
+            client_order_id: 1,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            expiry_timestamp: 0,
+        },
+        limit: 10,
+    };
+
+    // TEST: The open orders admin has to sign the placement
+    assert_openbook_error(
+        &send_tx(solana, place.clone()).await,
+        OpenBookError::MissingOpenOrdersAdmin.into(),
+        "trigger order without the open orders admin".to_string(),
+    );
+    send_tx(
+        solana,
+        PlaceTriggerOrderInstruction {
+            open_orders_admin: Some(open_orders_admin),
+            ..place
+        },
+    )
+    .await
+    .unwrap();
+    {
+        let trigger_orders = solana
+            .get_account::<TriggerOrdersAccount>(trigger_orders_account)
+            .await;
+        assert_eq!(
+            trigger_orders.trigger_orders[0].open_orders_admin(),
+            Some(open_orders_admin.pubkey())
+        );
+    }
+
+    // TEST: The admin who signed the placement lets the keeper execute it
+    send_tx(
+        solana,
+        ExecuteTriggerOrderInstruction {
+            open_orders_account: account_0,
+            market,
+            keeper,
+            slot: 0,
+        },
+    )
+    .await
+    .unwrap();
+    let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+    assert_eq!(bids_data.roots[0].leaf_count, 1);
+
+    Ok(())
+}
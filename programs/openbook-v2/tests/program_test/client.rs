@@ -13,10 +13,12 @@ use std::sync::Arc;
 use super::solana::SolanaCookie;
 use super::utils::TestKeypair;
 use openbook_v2::state::*;
+use openbook_v2::token_utils::native_unwrap_address;
 
 #[async_trait::async_trait(?Send)]
 pub trait ClientAccountLoader {
     async fn load_bytes(&self, pubkey: &Pubkey) -> Option<Vec<u8>>;
+    async fn load_owner(&self, pubkey: &Pubkey) -> Option<Pubkey>;
     async fn load<T: AccountDeserialize>(&self, pubkey: &Pubkey) -> Option<T> {
         let bytes = self.load_bytes(pubkey).await?;
         AccountDeserialize::try_deserialize(&mut &bytes[..]).ok()
@@ -26,6 +28,10 @@ pub trait ClientAccountLoader {
             .await
             .map(|v| OpenOrdersAccountValue::from_bytes(&v[8..]).unwrap())
     }
+    /// Token program of a mint, either SPL Token or Token-2022
+    async fn token_program(&self, mint: &Pubkey) -> Pubkey {
+        self.load_owner(mint).await.unwrap()
+    }
 }
 
 #[async_trait::async_trait(?Send)]
@@ -33,6 +39,19 @@ impl ClientAccountLoader for &SolanaCookie {
     async fn load_bytes(&self, pubkey: &Pubkey) -> Option<Vec<u8>> {
         self.get_account_data(*pubkey).await
     }
+    async fn load_owner(&self, pubkey: &Pubkey) -> Option<Pubkey> {
+        self.get_account_owner(*pubkey).await
+    }
+}
+
+/// Token account registered by a referrer, which receives its rebates
+async fn referrer_account(
+    account_loader: &impl ClientAccountLoader,
+    referrer: Option<Pubkey>,
+) -> Option<Pubkey> {
+    let referrer = referrer?;
+    let referrer: Referrer = account_loader.load(&referrer).await.unwrap();
+    Some(referrer.token_account)
 }
 
 // TODO: report error outwards etc
@@ -120,12 +139,20 @@ pub fn get_market_address(market_index: MarketIndex) -> Pubkey {
     .0
 }
 
+pub fn get_trigger_orders_address(open_orders_account: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"TriggerOrders".as_ref(), open_orders_account.as_ref()],
+        &openbook_v2::id(),
+    )
+    .0
+}
+
 async fn get_oracle_address_from_market_address(
     account_loader: &impl ClientAccountLoader,
     market_address: &Pubkey,
-) -> Pubkey {
+) -> Option<Pubkey> {
     let market: Market = account_loader.load(market_address).await.unwrap();
-    market.oracle
+    market.oracle()
 }
 
 pub async fn get_open_orders_account(
@@ -203,13 +230,143 @@ impl ClientInstruction for InitOpenOrdersInstruction {
     }
 }
 
+pub struct MigrateOpenOrdersAccountInstruction {
+    pub open_orders_account: Pubkey,
+    pub market: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for MigrateOpenOrdersAccountInstruction {
+    type Accounts = openbook_v2::accounts::MigrateOpenOrdersAccount;
+    type Instruction = openbook_v2::instruction::MigrateOpenOrdersAccount;
+    async fn to_instruction(
+        &self,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction {};
+
+        let accounts = Self::Accounts {
+            open_orders_account: self.open_orders_account,
+            market: self.market,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![]
+    }
+}
+
+#[derive(Clone)]
+pub struct CloseOpenOrdersAccountInstruction {
+    pub open_orders_account: Pubkey,
+    pub owner: TestKeypair,
+    pub sol_destination: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for CloseOpenOrdersAccountInstruction {
+    type Accounts = openbook_v2::accounts::CloseOpenOrdersAccount;
+    type Instruction = openbook_v2::instruction::CloseOpenOrdersAccount;
+    async fn to_instruction(
+        &self,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction {};
+
+        let accounts = Self::Accounts {
+            owner: self.owner.pubkey(),
+            open_orders_account: self.open_orders_account,
+            trigger_orders_account: get_trigger_orders_address(self.open_orders_account),
+            sol_destination: self.sol_destination,
+            system_program: System::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+pub struct ExpandOpenOrdersInstruction {
+    pub open_orders_account: Pubkey,
+    pub owner: TestKeypair,
+    pub payer: TestKeypair,
+    pub open_orders_count: u8,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for ExpandOpenOrdersInstruction {
+    type Accounts = openbook_v2::accounts::ExpandOpenOrders;
+    type Instruction = openbook_v2::instruction::ExpandOpenOrders;
+    async fn to_instruction(
+        &self,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction {
+            open_orders_count: self.open_orders_count,
+        };
+
+        let accounts = Self::Accounts {
+            owner: self.owner.pubkey(),
+            payer: self.payer.pubkey(),
+            open_orders_account: self.open_orders_account,
+            system_program: System::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner, self.payer]
+    }
+}
+
+pub struct SetDelegateInstruction {
+    pub delegate_account: Option<Pubkey>,
+    pub owner: TestKeypair,
+    pub open_orders_account: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for SetDelegateInstruction {
+    type Accounts = openbook_v2::accounts::SetDelegate;
+    type Instruction = openbook_v2::instruction::SetDelegate;
+    async fn to_instruction(
+        &self,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction {};
+
+        let accounts = Self::Accounts {
+            owner: self.owner.pubkey(),
+            open_orders_account: self.open_orders_account,
+            delegate_account: self.delegate_account,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
 #[derive(Default)]
 pub struct CreateMarketInstruction {
     pub collect_fee_admin: Pubkey,
     pub open_orders_admin: Option<Pubkey>,
     pub consume_events_admin: Option<Pubkey>,
     pub close_market_admin: Option<Pubkey>,
-    pub oracle: Pubkey,
+    pub market_admin: Option<Pubkey>,
+    pub oracle: Option<Pubkey>,
     pub base_mint: Pubkey,
     pub quote_mint: Pubkey,
     pub base_vault: Pubkey,
@@ -227,6 +384,7 @@ pub struct CreateMarketInstruction {
     pub fee_penalty: u64,
     pub settle_fee_flat: f32,
     pub settle_fee_amount_threshold: f32,
+    pub time_expiry: i64,
 }
 impl CreateMarketInstruction {
     pub async fn with_new_book_and_queue(
@@ -243,7 +401,7 @@ impl CreateMarketInstruction {
             event_queue: solana
                 .create_account_for_type::<EventQueue>(&openbook_v2::id())
                 .await,
-            oracle: base.oracle,
+            oracle: Some(base.oracle),
             ..CreateMarketInstruction::default()
         }
     }
@@ -255,7 +413,7 @@ impl ClientInstruction for CreateMarketInstruction {
     type Instruction = openbook_v2::instruction::CreateMarket;
     async fn to_instruction(
         &self,
-        _loader: impl ClientAccountLoader + 'async_trait,
+        loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = openbook_v2::id();
         let instruction = Self::Instruction {
@@ -263,6 +421,7 @@ impl ClientInstruction for CreateMarketInstruction {
             open_orders_admin: self.open_orders_admin,
             consume_events_admin: self.consume_events_admin,
             close_market_admin: self.close_market_admin,
+            market_admin: self.market_admin,
             name: "ONE-TWO".to_string(),
             market_index: self.market_index,
             oracle_config: OracleConfigParams {
@@ -274,6 +433,7 @@ impl ClientInstruction for CreateMarketInstruction {
             maker_fee: self.maker_fee,
             taker_fee: self.taker_fee,
             fee_penalty: self.fee_penalty,
+            time_expiry: self.time_expiry,
         };
 
         let market = Pubkey::find_program_address(
@@ -282,10 +442,18 @@ impl ClientInstruction for CreateMarketInstruction {
         )
         .0;
 
-        let base_vault =
-            spl_associated_token_account::get_associated_token_address(&market, &self.base_mint);
+        let token_program = loader.token_program(&self.base_mint).await;
+        let base_vault = spl_associated_token_account::get_associated_token_address_with_program_id(
+            &market,
+            &self.base_mint,
+            &token_program,
+        );
         let quote_vault =
-            spl_associated_token_account::get_associated_token_address(&market, &self.quote_mint);
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                &market,
+                &self.quote_mint,
+                &token_program,
+            );
 
         let accounts = Self::Accounts {
             oracle: self.oracle,
@@ -310,6 +478,7 @@ impl ClientInstruction for CreateMarketInstruction {
     }
 }
 
+#[derive(Clone)]
 pub struct PlaceOrderInstruction {
     pub open_orders_account: Pubkey,
     pub open_orders_admin: Option<TestKeypair>,
@@ -359,12 +528,14 @@ impl ClientInstruction for PlaceOrderInstruction {
             bids: market.bids,
             asks: market.asks,
             event_queue: market.event_queue,
-            oracle: market.oracle,
+            oracle: market.oracle(),
             owner: self.owner.pubkey(),
             token_deposit_account: self.token_deposit_account,
             base_vault: self.base_vault,
             quote_vault: self.quote_vault,
-            token_program: Token::id(),
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            token_program: account_loader.token_program(&market.base_mint).await,
             system_program: System::id(),
         };
         let mut instruction = make_instruction(program_id, &accounts, instruction);
@@ -390,24 +561,29 @@ impl ClientInstruction for PlaceOrderInstruction {
     }
 }
 
-pub struct PlaceOrderPeggedInstruction {
+pub struct PlaceIcebergOrderInstruction {
     pub open_orders_account: Pubkey,
+    pub open_orders_admin: Option<TestKeypair>,
     pub market: Pubkey,
     pub owner: TestKeypair,
-    pub token_deposit_account: Pubkey,
     pub base_vault: Pubkey,
     pub quote_vault: Pubkey,
+    pub token_deposit_account: Pubkey,
     pub side: Side,
-    pub price_offset: i64,
+    pub price_lots: i64,
     pub max_base_lots: i64,
+    pub display_base_lots: i64,
     pub max_quote_lots_including_fees: i64,
     pub client_order_id: u64,
-    pub peg_limit: i64,
+    pub expiry_timestamp: u64,
+    pub order_type: PlaceOrderType,
+    pub self_trade_behavior: SelfTradeBehavior,
 }
+
 #[async_trait::async_trait(?Send)]
-impl ClientInstruction for PlaceOrderPeggedInstruction {
+impl ClientInstruction for PlaceIcebergOrderInstruction {
     type Accounts = openbook_v2::accounts::PlaceOrder;
-    type Instruction = openbook_v2::instruction::PlaceOrderPegged;
+    type Instruction = openbook_v2::instruction::PlaceIcebergOrder;
     async fn to_instruction(
         &self,
         account_loader: impl ClientAccountLoader + 'async_trait,
@@ -415,148 +591,164 @@ impl ClientInstruction for PlaceOrderPeggedInstruction {
         let program_id = openbook_v2::id();
         let instruction = Self::Instruction {
             side: self.side,
-            price_offset_lots: self.price_offset,
-            peg_limit: self.peg_limit,
+            price_lots: self.price_lots,
             max_base_lots: self.max_base_lots,
+            display_base_lots: self.display_base_lots,
             max_quote_lots_including_fees: self.max_quote_lots_including_fees,
             client_order_id: self.client_order_id,
-            order_type: PlaceOrderType::Limit,
-            self_trade_behavior: SelfTradeBehavior::default(),
-            expiry_timestamp: 0,
+            order_type: self.order_type,
+            self_trade_behavior: self.self_trade_behavior,
+            expiry_timestamp: self.expiry_timestamp,
             limit: 10,
-            max_oracle_staleness_slots: -1,
         };
 
         let market: Market = account_loader.load(&self.market).await.unwrap();
 
         let accounts = Self::Accounts {
             open_orders_account: self.open_orders_account,
-            open_orders_admin: None,
+            open_orders_admin: self.open_orders_admin.map(|kp| kp.pubkey()),
             market: self.market,
             bids: market.bids,
             asks: market.asks,
             event_queue: market.event_queue,
-            oracle: market.oracle,
+            oracle: market.oracle(),
             owner: self.owner.pubkey(),
             token_deposit_account: self.token_deposit_account,
             base_vault: self.base_vault,
             quote_vault: self.quote_vault,
-            token_program: Token::id(),
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            token_program: account_loader.token_program(&market.base_mint).await,
             system_program: System::id(),
         };
         let instruction = make_instruction(program_id, &accounts, instruction);
-
         (accounts, instruction)
     }
 
     fn signers(&self) -> Vec<TestKeypair> {
-        vec![self.owner]
+        let mut signers = vec![self.owner];
+        if let Some(open_orders_admin) = self.open_orders_admin {
+            signers.push(open_orders_admin);
+        }
+
+        signers
     }
 }
 
-pub struct PlaceTakeOrderInstruction {
-    pub open_orders_admin: Option<TestKeypair>,
+pub struct EditOrderInstruction {
+    pub open_orders_account: Pubkey,
     pub market: Pubkey,
     pub owner: TestKeypair,
     pub base_vault: Pubkey,
     pub quote_vault: Pubkey,
     pub token_deposit_account: Pubkey,
-    pub token_receiver_account: Pubkey,
+    pub cancel_order_id: Option<u128>,
+    pub cancel_client_order_id: Option<u64>,
     pub side: Side,
     pub price_lots: i64,
     pub max_base_lots: i64,
     pub max_quote_lots_including_fees: i64,
     pub client_order_id: u64,
+    pub order_type: PlaceOrderType,
+    pub self_trade_behavior: SelfTradeBehavior,
     pub expiry_timestamp: u64,
-    pub referrer: Option<Pubkey>,
 }
+
 #[async_trait::async_trait(?Send)]
-impl ClientInstruction for PlaceTakeOrderInstruction {
-    type Accounts = openbook_v2::accounts::PlaceTakeOrder;
-    type Instruction = openbook_v2::instruction::PlaceTakeOrder;
+impl ClientInstruction for EditOrderInstruction {
+    type Accounts = openbook_v2::accounts::PlaceOrder;
+    type Instruction = openbook_v2::instruction::EditOrder;
     async fn to_instruction(
         &self,
         account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = openbook_v2::id();
         let instruction = Self::Instruction {
+            cancel_order_id: self.cancel_order_id,
+            cancel_client_order_id: self.cancel_client_order_id,
             side: self.side,
             price_lots: self.price_lots,
             max_base_lots: self.max_base_lots,
             max_quote_lots_including_fees: self.max_quote_lots_including_fees,
             client_order_id: self.client_order_id,
-            order_type: PlaceOrderType::ImmediateOrCancel,
-            self_trade_behavior: SelfTradeBehavior::default(),
+            order_type: self.order_type,
+            self_trade_behavior: self.self_trade_behavior,
+            expiry_timestamp: self.expiry_timestamp,
             limit: 10,
         };
 
         let market: Market = account_loader.load(&self.market).await.unwrap();
 
         let accounts = Self::Accounts {
-            open_orders_admin: self.open_orders_admin.map(|kp| kp.pubkey()),
+            open_orders_account: self.open_orders_account,
+            open_orders_admin: None,
             market: self.market,
             bids: market.bids,
             asks: market.asks,
             event_queue: market.event_queue,
-            oracle: market.oracle,
+            oracle: market.oracle(),
             owner: self.owner.pubkey(),
             token_deposit_account: self.token_deposit_account,
-            token_receiver_account: self.token_receiver_account,
             base_vault: self.base_vault,
             quote_vault: self.quote_vault,
-            token_program: Token::id(),
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            token_program: account_loader.token_program(&market.base_mint).await,
             system_program: System::id(),
         };
-
-        let mut instruction = make_instruction(program_id, &accounts, instruction);
-        if let Some(ref3) = self.referrer {
-            let remaining = &mut vec![AccountMeta {
-                pubkey: ref3,
-                is_signer: false,
-                is_writable: true,
-            }];
-            instruction.accounts.append(remaining);
-        }
+        let instruction = make_instruction(program_id, &accounts, instruction);
         (accounts, instruction)
     }
 
     fn signers(&self) -> Vec<TestKeypair> {
-        let mut signers = vec![self.owner];
-        if let Some(open_orders_admin) = self.open_orders_admin {
-            signers.push(open_orders_admin);
-        }
-
-        signers
+        vec![self.owner]
     }
 }
 
-pub struct CancelOrderInstruction {
+pub struct PlaceOrdersInstruction {
     pub open_orders_account: Pubkey,
     pub market: Pubkey,
     pub owner: TestKeypair,
-    pub order_id: u128,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub base_deposit_account: Pubkey,
+    pub quote_deposit_account: Pubkey,
+    pub orders: Vec<PlaceOrderArgs>,
 }
+
 #[async_trait::async_trait(?Send)]
-impl ClientInstruction for CancelOrderInstruction {
-    type Accounts = openbook_v2::accounts::CancelOrder;
-    type Instruction = openbook_v2::instruction::CancelOrder;
+impl ClientInstruction for PlaceOrdersInstruction {
+    type Accounts = openbook_v2::accounts::PlaceOrders;
+    type Instruction = openbook_v2::instruction::PlaceOrders;
     async fn to_instruction(
         &self,
         account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = openbook_v2::id();
         let instruction = Self::Instruction {
-            order_id: self.order_id,
+            orders: self.orders.clone(),
+            limit: 10,
         };
+
         let market: Market = account_loader.load(&self.market).await.unwrap();
+
         let accounts = Self::Accounts {
             open_orders_account: self.open_orders_account,
+            open_orders_admin: None,
             market: self.market,
             bids: market.bids,
             asks: market.asks,
+            event_queue: market.event_queue,
+            oracle: market.oracle(),
             owner: self.owner.pubkey(),
+            base_deposit_account: self.base_deposit_account,
+            quote_deposit_account: self.quote_deposit_account,
+            base_vault: self.base_vault,
+            quote_vault: self.quote_vault,
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            token_program: account_loader.token_program(&market.base_mint).await,
         };
-
         let instruction = make_instruction(program_id, &accounts, instruction);
         (accounts, instruction)
     }
@@ -566,34 +758,65 @@ impl ClientInstruction for CancelOrderInstruction {
     }
 }
 
-pub struct CancelOrderByClientOrderIdInstruction {
+pub struct PlaceOrderPeggedInstruction {
     pub open_orders_account: Pubkey,
     pub market: Pubkey,
     pub owner: TestKeypair,
+    pub token_deposit_account: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub side: Side,
+    pub price_offset: i64,
+    pub max_base_lots: i64,
+    pub max_quote_lots_including_fees: i64,
     pub client_order_id: u64,
+    pub peg_limit: i64,
+    pub max_oracle_staleness_slots: i32,
 }
 #[async_trait::async_trait(?Send)]
-impl ClientInstruction for CancelOrderByClientOrderIdInstruction {
-    type Accounts = openbook_v2::accounts::CancelOrderByClientOrderId;
-    type Instruction = openbook_v2::instruction::CancelOrderByClientOrderId;
+impl ClientInstruction for PlaceOrderPeggedInstruction {
+    type Accounts = openbook_v2::accounts::PlaceOrder;
+    type Instruction = openbook_v2::instruction::PlaceOrderPegged;
     async fn to_instruction(
         &self,
         account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = openbook_v2::id();
         let instruction = Self::Instruction {
+            side: self.side,
+            price_offset_lots: self.price_offset,
+            peg_limit: self.peg_limit,
+            max_base_lots: self.max_base_lots,
+            max_quote_lots_including_fees: self.max_quote_lots_including_fees,
             client_order_id: self.client_order_id,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            expiry_timestamp: 0,
+            limit: 10,
+            max_oracle_staleness_slots: self.max_oracle_staleness_slots,
         };
+
         let market: Market = account_loader.load(&self.market).await.unwrap();
+
         let accounts = Self::Accounts {
             open_orders_account: self.open_orders_account,
+            open_orders_admin: None,
             market: self.market,
             bids: market.bids,
             asks: market.asks,
+            event_queue: market.event_queue,
+            oracle: market.oracle(),
             owner: self.owner.pubkey(),
+            token_deposit_account: self.token_deposit_account,
+            base_vault: self.base_vault,
+            quote_vault: self.quote_vault,
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            token_program: account_loader.token_program(&market.base_mint).await,
+            system_program: System::id(),
         };
-
         let instruction = make_instruction(program_id, &accounts, instruction);
+
         (accounts, instruction)
     }
 
@@ -602,8 +825,196 @@ impl ClientInstruction for CancelOrderByClientOrderIdInstruction {
     }
 }
 
-pub struct CancelAllOrdersInstruction {
-    pub open_orders_account: Pubkey,
+#[derive(Clone)]
+pub struct PlaceTakeOrderInstruction {
+    pub open_orders_admin: Option<TestKeypair>,
+    pub market: Pubkey,
+    pub owner: TestKeypair,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub token_deposit_account: Pubkey,
+    pub token_receiver_account: Option<Pubkey>,
+    pub side: Side,
+    pub price_lots: i64,
+    pub max_base_lots: i64,
+    pub max_quote_lots_including_fees: i64,
+    pub client_order_id: u64,
+    pub expiry_timestamp: u64,
+    pub order_type: PlaceOrderType,
+    pub referrer: Option<Pubkey>,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PlaceTakeOrderInstruction {
+    type Accounts = openbook_v2::accounts::PlaceTakeOrder;
+    type Instruction = openbook_v2::instruction::PlaceTakeOrder;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction {
+            side: self.side,
+            price_lots: self.price_lots,
+            max_base_lots: self.max_base_lots,
+            max_quote_lots_including_fees: self.max_quote_lots_including_fees,
+            client_order_id: self.client_order_id,
+            order_type: self.order_type,
+            self_trade_behavior: SelfTradeBehavior::default(),
+            limit: 10,
+        };
+
+        let market: Market = account_loader.load(&self.market).await.unwrap();
+
+        let accounts = Self::Accounts {
+            open_orders_admin: self.open_orders_admin.map(|kp| kp.pubkey()),
+            market: self.market,
+            bids: market.bids,
+            asks: market.asks,
+            event_queue: market.event_queue,
+            oracle: market.oracle(),
+            owner: self.owner.pubkey(),
+            token_deposit_account: self.token_deposit_account,
+            token_receiver_account: self.token_receiver_account,
+            native_unwrap_account: self
+                .token_receiver_account
+                .is_none()
+                .then(|| native_unwrap_address(&self.owner.pubkey()).0),
+            base_vault: self.base_vault,
+            quote_vault: self.quote_vault,
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            referrer: self.referrer,
+            referrer_account: referrer_account(&account_loader, self.referrer).await,
+            token_program: account_loader.token_program(&market.base_mint).await,
+            system_program: System::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        let mut signers = vec![self.owner];
+        if let Some(open_orders_admin) = self.open_orders_admin {
+            signers.push(open_orders_admin);
+        }
+
+        signers
+    }
+}
+
+pub struct CancelOrderInstruction {
+    pub open_orders_account: Pubkey,
+    pub market: Pubkey,
+    pub owner: TestKeypair,
+    pub order_id: u128,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for CancelOrderInstruction {
+    type Accounts = openbook_v2::accounts::CancelOrder;
+    type Instruction = openbook_v2::instruction::CancelOrder;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction {
+            order_id: self.order_id,
+        };
+        let market: Market = account_loader.load(&self.market).await.unwrap();
+        let accounts = Self::Accounts {
+            open_orders_account: self.open_orders_account,
+            market: self.market,
+            bids: market.bids,
+            asks: market.asks,
+            owner: self.owner.pubkey(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+pub struct CancelOrdersInstruction {
+    pub open_orders_account: Pubkey,
+    pub market: Pubkey,
+    pub owner: TestKeypair,
+    pub order_ids: Vec<u128>,
+    pub client_order_ids: Vec<u64>,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for CancelOrdersInstruction {
+    type Accounts = openbook_v2::accounts::CancelOrder;
+    type Instruction = openbook_v2::instruction::CancelOrders;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction {
+            order_ids: self.order_ids.clone(),
+            client_order_ids: self.client_order_ids.clone(),
+        };
+        let market: Market = account_loader.load(&self.market).await.unwrap();
+        let accounts = Self::Accounts {
+            open_orders_account: self.open_orders_account,
+            market: self.market,
+            bids: market.bids,
+            asks: market.asks,
+            owner: self.owner.pubkey(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+pub struct CancelOrderByClientOrderIdInstruction {
+    pub open_orders_account: Pubkey,
+    pub market: Pubkey,
+    pub owner: TestKeypair,
+    pub client_order_id: u64,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for CancelOrderByClientOrderIdInstruction {
+    type Accounts = openbook_v2::accounts::CancelOrderByClientOrderId;
+    type Instruction = openbook_v2::instruction::CancelOrderByClientOrderId;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction {
+            client_order_id: self.client_order_id,
+        };
+        let market: Market = account_loader.load(&self.market).await.unwrap();
+        let accounts = Self::Accounts {
+            open_orders_account: self.open_orders_account,
+            market: self.market,
+            bids: market.bids,
+            asks: market.asks,
+            owner: self.owner.pubkey(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+pub struct CancelAllOrdersInstruction {
+    pub open_orders_account: Pubkey,
     pub market: Pubkey,
     pub owner: TestKeypair,
 }
@@ -635,6 +1046,245 @@ impl ClientInstruction for CancelAllOrdersInstruction {
     }
 }
 
+pub struct PruneOrdersInstruction {
+    pub open_orders_account: Pubkey,
+    pub market: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PruneOrdersInstruction {
+    type Accounts = openbook_v2::accounts::PruneOrders;
+    type Instruction = openbook_v2::instruction::PruneOrders;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction { limit: 5 };
+        let market: Market = account_loader.load(&self.market).await.unwrap();
+        let accounts = Self::Accounts {
+            open_orders_account: self.open_orders_account,
+            market: self.market,
+            bids: market.bids,
+            asks: market.asks,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![]
+    }
+}
+
+pub struct CreateTriggerOrdersAccountInstruction {
+    pub open_orders_account: Pubkey,
+    pub market: Pubkey,
+    pub owner: TestKeypair,
+    pub payer: TestKeypair,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for CreateTriggerOrdersAccountInstruction {
+    type Accounts = openbook_v2::accounts::CreateTriggerOrdersAccount;
+    type Instruction = openbook_v2::instruction::CreateTriggerOrdersAccount;
+    async fn to_instruction(
+        &self,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction {};
+
+        let accounts = Self::Accounts {
+            trigger_orders_account: get_trigger_orders_address(self.open_orders_account),
+            owner: self.owner.pubkey(),
+            payer: self.payer.pubkey(),
+            open_orders_account: self.open_orders_account,
+            market: self.market,
+            system_program: System::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner, self.payer]
+    }
+}
+
+#[derive(Clone)]
+pub struct PlaceTriggerOrderInstruction {
+    pub open_orders_account: Pubkey,
+    pub market: Pubkey,
+    pub owner: TestKeypair,
+    pub open_orders_admin: Option<TestKeypair>,
+    pub token_deposit_account: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub condition: TriggerCondition,
+    pub trigger_price_lots: i64,
+    pub order: PlaceOrderArgs,
+    pub limit: u8,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PlaceTriggerOrderInstruction {
+    type Accounts = openbook_v2::accounts::PlaceTriggerOrder;
+    type Instruction = openbook_v2::instruction::PlaceTriggerOrder;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction {
+            condition: self.condition,
+            trigger_price_lots: self.trigger_price_lots,
+            order: self.order,
+            limit: self.limit,
+        };
+
+        let market: Market = account_loader.load(&self.market).await.unwrap();
+
+        let accounts = Self::Accounts {
+            trigger_orders_account: get_trigger_orders_address(self.open_orders_account),
+            open_orders_account: self.open_orders_account,
+            owner: self.owner.pubkey(),
+            open_orders_admin: self.open_orders_admin.map(|kp| kp.pubkey()),
+            market: self.market,
+            token_deposit_account: self.token_deposit_account,
+            base_vault: self.base_vault,
+            quote_vault: self.quote_vault,
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            token_program: account_loader.token_program(&market.base_mint).await,
+            system_program: System::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        let mut signers = vec![self.owner];
+        if let Some(open_orders_admin) = self.open_orders_admin {
+            signers.push(open_orders_admin);
+        }
+        signers
+    }
+}
+
+pub struct CancelTriggerOrderInstruction {
+    pub open_orders_account: Pubkey,
+    pub owner: TestKeypair,
+    pub slot: u8,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for CancelTriggerOrderInstruction {
+    type Accounts = openbook_v2::accounts::CancelTriggerOrder;
+    type Instruction = openbook_v2::instruction::CancelTriggerOrder;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction { slot: self.slot };
+
+        let owner = account_loader
+            .load_open_orders_account(&self.open_orders_account)
+            .await
+            .unwrap()
+            .fixed
+            .owner;
+
+        let accounts = Self::Accounts {
+            trigger_orders_account: get_trigger_orders_address(self.open_orders_account),
+            open_orders_account: self.open_orders_account,
+            owner: self.owner.pubkey(),
+            sol_destination: owner,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+#[derive(Clone)]
+pub struct CloseTriggerOrdersAccountInstruction {
+    pub open_orders_account: Pubkey,
+    pub owner: TestKeypair,
+    pub sol_destination: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for CloseTriggerOrdersAccountInstruction {
+    type Accounts = openbook_v2::accounts::CloseTriggerOrdersAccount;
+    type Instruction = openbook_v2::instruction::CloseTriggerOrdersAccount;
+    async fn to_instruction(
+        &self,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction {};
+
+        let accounts = Self::Accounts {
+            owner: self.owner.pubkey(),
+            trigger_orders_account: get_trigger_orders_address(self.open_orders_account),
+            open_orders_account: self.open_orders_account,
+            sol_destination: self.sol_destination,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+#[derive(Clone)]
+pub struct ExecuteTriggerOrderInstruction {
+    pub open_orders_account: Pubkey,
+    pub market: Pubkey,
+    pub keeper: TestKeypair,
+    pub slot: u8,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for ExecuteTriggerOrderInstruction {
+    type Accounts = openbook_v2::accounts::ExecuteTriggerOrder;
+    type Instruction = openbook_v2::instruction::ExecuteTriggerOrder;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction { slot: self.slot };
+
+        let market: Market = account_loader.load(&self.market).await.unwrap();
+
+        let accounts = Self::Accounts {
+            keeper: self.keeper.pubkey(),
+            trigger_orders_account: get_trigger_orders_address(self.open_orders_account),
+            open_orders_account: self.open_orders_account,
+            market: self.market,
+            bids: market.bids,
+            asks: market.asks,
+            event_queue: market.event_queue,
+            oracle: market.oracle(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.keeper]
+    }
+}
+
+#[derive(Clone)]
 pub struct ConsumeEventsInstruction {
     pub consume_events_admin: Option<TestKeypair>,
     pub market: Pubkey,
@@ -656,6 +1306,7 @@ impl ClientInstruction for ConsumeEventsInstruction {
             consume_events_admin: self.consume_events_admin.map(|kp| kp.pubkey()),
             market: self.market,
             event_queue: market.event_queue,
+            oracle: market.oracle(),
         };
 
         let mut instruction = make_instruction(program_id, &accounts, instruction);
@@ -677,14 +1328,15 @@ impl ClientInstruction for ConsumeEventsInstruction {
     }
 }
 
+#[derive(Clone)]
 pub struct SettleFundsInstruction {
     pub owner: TestKeypair,
     pub open_orders_account: Pubkey,
     pub market: Pubkey,
     pub base_vault: Pubkey,
     pub quote_vault: Pubkey,
-    pub token_base_account: Pubkey,
-    pub token_quote_account: Pubkey,
+    pub token_base_account: Option<Pubkey>,
+    pub token_quote_account: Option<Pubkey>,
     pub referrer: Option<Pubkey>,
 }
 #[async_trait::async_trait(?Send)]
@@ -693,11 +1345,13 @@ impl ClientInstruction for SettleFundsInstruction {
     type Instruction = openbook_v2::instruction::SettleFunds;
     async fn to_instruction(
         &self,
-        _account_loader: impl ClientAccountLoader + 'async_trait,
+        account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = openbook_v2::id();
         let instruction = Self::Instruction {};
 
+        let market: Market = account_loader.load(&self.market).await.unwrap();
+
         let accounts = Self::Accounts {
             owner: self.owner.pubkey(),
             open_orders_account: self.open_orders_account,
@@ -706,18 +1360,17 @@ impl ClientInstruction for SettleFundsInstruction {
             quote_vault: self.quote_vault,
             token_base_account: self.token_base_account,
             token_quote_account: self.token_quote_account,
-            token_program: Token::id(),
+            native_unwrap_account: (self.token_base_account.is_none()
+                || self.token_quote_account.is_none())
+            .then(|| native_unwrap_address(&self.owner.pubkey()).0),
+            referrer: self.referrer,
+            referrer_account: referrer_account(&account_loader, self.referrer).await,
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            token_program: account_loader.token_program(&market.base_mint).await,
             system_program: System::id(),
         };
-        let mut instruction = make_instruction(program_id, &accounts, instruction);
-        if let Some(ref3) = self.referrer {
-            let remaining = &mut vec![AccountMeta {
-                pubkey: ref3,
-                is_signer: false,
-                is_writable: true,
-            }];
-            instruction.accounts.append(remaining);
-        }
+        let instruction = make_instruction(program_id, &accounts, instruction);
 
         (accounts, instruction)
     }
@@ -727,10 +1380,66 @@ impl ClientInstruction for SettleFundsInstruction {
     }
 }
 
+#[derive(Clone)]
+pub struct SettleFundsPermissionlessInstruction {
+    pub caller: TestKeypair,
+    pub open_orders_account: Pubkey,
+    pub market: Pubkey,
+    pub caller_quote_account: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for SettleFundsPermissionlessInstruction {
+    type Accounts = openbook_v2::accounts::SettleFundsPermissionless;
+    type Instruction = openbook_v2::instruction::SettleFundsPermissionless;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction {};
+
+        let market: Market = account_loader.load(&self.market).await.unwrap();
+        let owner = account_loader
+            .load_open_orders_account(&self.open_orders_account)
+            .await
+            .unwrap()
+            .fixed
+            .owner;
+
+        let accounts = Self::Accounts {
+            caller: self.caller.pubkey(),
+            open_orders_account: self.open_orders_account,
+            market: self.market,
+            base_vault: market.base_vault,
+            quote_vault: market.quote_vault,
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            owner_base_account: spl_associated_token_account::get_associated_token_address(
+                &owner,
+                &market.base_mint,
+            ),
+            owner_quote_account: spl_associated_token_account::get_associated_token_address(
+                &owner,
+                &market.quote_mint,
+            ),
+            caller_quote_account: self.caller_quote_account,
+            token_program: account_loader.token_program(&market.base_mint).await,
+        };
+        let instruction = make_instruction(program_id, &accounts, instruction);
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.caller]
+    }
+}
+
 pub struct SweepFeesInstruction {
     pub collect_fee_admin: TestKeypair,
     pub market: Pubkey,
     pub quote_vault: Pubkey,
+    pub token_base_receiver_account: Pubkey,
     pub token_receiver_account: Pubkey,
 }
 #[async_trait::async_trait(?Send)]
@@ -739,17 +1448,23 @@ impl ClientInstruction for SweepFeesInstruction {
     type Instruction = openbook_v2::instruction::SweepFees;
     async fn to_instruction(
         &self,
-        _account_loader: impl ClientAccountLoader + 'async_trait,
+        account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = openbook_v2::id();
         let instruction = Self::Instruction {};
 
+        let market: Market = account_loader.load(&self.market).await.unwrap();
+
         let accounts = Self::Accounts {
             collect_fee_admin: self.collect_fee_admin.pubkey(),
             market: self.market,
-            quote_vault: self.quote_vault,
+            token_base_receiver_account: self.token_base_receiver_account,
             token_receiver_account: self.token_receiver_account,
-            token_program: Token::id(),
+            base_vault: market.base_vault,
+            quote_vault: self.quote_vault,
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            token_program: account_loader.token_program(&market.base_mint).await,
             system_program: System::id(),
         };
         let instruction = make_instruction(program_id, &accounts, instruction);
@@ -762,13 +1477,61 @@ impl ClientInstruction for SweepFeesInstruction {
     }
 }
 
+pub struct BuybackFeesInstruction {
+    pub owner: TestKeypair,
+    pub open_orders_account: Pubkey,
+    pub market: Pubkey,
+    pub buyback_token_account: Pubkey,
+    pub buyback_receiver_account: Pubkey,
+    pub token_quote_account: Pubkey,
+    pub max_buyback_fees: u64,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for BuybackFeesInstruction {
+    type Accounts = openbook_v2::accounts::BuybackFees;
+    type Instruction = openbook_v2::instruction::BuybackFees;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction {
+            max_buyback_fees: self.max_buyback_fees,
+        };
+
+        let market: Market = account_loader.load(&self.market).await.unwrap();
+
+        let accounts = Self::Accounts {
+            owner: self.owner.pubkey(),
+            open_orders_account: self.open_orders_account,
+            market: self.market,
+            quote_vault: market.quote_vault,
+            quote_mint: market.quote_mint,
+            buyback_mint: market.buyback_mint,
+            buyback_oracle: market.buyback_oracle,
+            buyback_token_account: self.buyback_token_account,
+            buyback_receiver_account: self.buyback_receiver_account,
+            token_quote_account: self.token_quote_account,
+            token_program: account_loader.token_program(&market.base_mint).await,
+        };
+        let instruction = make_instruction(program_id, &accounts, instruction);
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+#[derive(Clone)]
 pub struct DepositInstruction {
     pub open_orders_account: Pubkey,
     pub market: Pubkey,
     pub base_vault: Pubkey,
     pub quote_vault: Pubkey,
-    pub token_base_account: Pubkey,
-    pub token_quote_account: Pubkey,
+    pub token_base_account: Option<Pubkey>,
+    pub token_quote_account: Option<Pubkey>,
     pub owner: TestKeypair,
     pub base_amount_lots: u64,
     pub quote_amount_lots: u64,
@@ -779,7 +1542,7 @@ impl ClientInstruction for DepositInstruction {
     type Instruction = openbook_v2::instruction::Deposit;
     async fn to_instruction(
         &self,
-        _account_loader: impl ClientAccountLoader + 'async_trait,
+        account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = openbook_v2::id();
         let instruction = Self::Instruction {
@@ -787,6 +1550,8 @@ impl ClientInstruction for DepositInstruction {
             quote_amount_lots: self.quote_amount_lots,
         };
 
+        let market: Market = account_loader.load(&self.market).await.unwrap();
+
         let accounts = Self::Accounts {
             owner: self.owner.pubkey(),
             open_orders_account: self.open_orders_account,
@@ -795,7 +1560,9 @@ impl ClientInstruction for DepositInstruction {
             quote_vault: self.quote_vault,
             token_base_account: self.token_base_account,
             token_quote_account: self.token_quote_account,
-            token_program: Token::id(),
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            token_program: account_loader.token_program(&market.base_mint).await,
             system_program: System::id(),
         };
         let instruction = make_instruction(program_id, &accounts, instruction);
@@ -847,6 +1614,73 @@ impl ClientInstruction for StubOracleSetInstruction {
     }
 }
 
+pub struct CreateReferrerInstruction {
+    pub referrer_id: u64,
+    pub authority: TestKeypair,
+    pub token_account: Pubkey,
+    pub payer: TestKeypair,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for CreateReferrerInstruction {
+    type Accounts = openbook_v2::accounts::CreateReferrer;
+    type Instruction = openbook_v2::instruction::CreateReferrer;
+    async fn to_instruction(
+        &self,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction {
+            referrer_id: self.referrer_id,
+        };
+
+        let accounts = Self::Accounts {
+            referrer: Referrer::address(self.referrer_id).0,
+            authority: self.authority.pubkey(),
+            token_account: self.token_account,
+            payer: self.payer.pubkey(),
+            system_program: System::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.authority, self.payer]
+    }
+}
+
+pub struct EditReferrerInstruction {
+    pub referrer: Pubkey,
+    pub authority: TestKeypair,
+    pub token_account: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for EditReferrerInstruction {
+    type Accounts = openbook_v2::accounts::EditReferrer;
+    type Instruction = openbook_v2::instruction::EditReferrer;
+    async fn to_instruction(
+        &self,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction {};
+
+        let accounts = Self::Accounts {
+            referrer: self.referrer,
+            authority: self.authority.pubkey(),
+            token_account: self.token_account,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.authority]
+    }
+}
+
 pub struct StubOracleCreate {
     pub mint: Pubkey,
     pub admin: TestKeypair,
@@ -932,6 +1766,8 @@ pub struct CloseMarketInstruction {
     pub close_market_admin: TestKeypair,
     pub market: Pubkey,
     pub sol_destination: Pubkey,
+    pub token_base_receiver_account: Pubkey,
+    pub token_quote_receiver_account: Pubkey,
 }
 #[async_trait::async_trait(?Send)]
 impl ClientInstruction for CloseMarketInstruction {
@@ -951,8 +1787,14 @@ impl ClientInstruction for CloseMarketInstruction {
             bids: market.bids,
             asks: market.asks,
             event_queue: market.event_queue,
-            token_program: Token::id(),
+            base_vault: market.base_vault,
+            quote_vault: market.quote_vault,
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            token_program: account_loader.token_program(&market.base_mint).await,
             sol_destination: self.sol_destination,
+            token_base_receiver_account: self.token_base_receiver_account,
+            token_quote_receiver_account: self.token_quote_receiver_account,
         };
 
         let instruction = make_instruction(program_id, &accounts, instruction);
@@ -963,3 +1805,92 @@ impl ClientInstruction for CloseMarketInstruction {
         vec![self.close_market_admin]
     }
 }
+
+pub struct EditMarketInstruction {
+    pub market_admin: TestKeypair,
+    pub market: Pubkey,
+    pub maker_fee_opt: Option<f32>,
+    pub taker_fee_opt: Option<f32>,
+    pub fee_penalty_opt: Option<u64>,
+    pub taker_price_band_bps_opt: Option<u16>,
+    pub maker_price_band_bps_opt: Option<u16>,
+    pub collect_fee_admin_opt: Option<Pubkey>,
+    pub open_orders_admin_opt: Option<Pubkey>,
+    pub consume_events_admin_opt: Option<Pubkey>,
+    pub close_market_admin_opt: Option<Pubkey>,
+    pub market_admin_opt: Option<Pubkey>,
+    pub disabled_instructions_opt: Option<u8>,
+    pub time_expiry_opt: Option<i64>,
+    pub fee_tiers_opt: Option<Vec<FeeTierParams>>,
+    pub fee_tier_volume_interval_opt: Option<u64>,
+    pub buyback_opt: Option<BuybackParams>,
+}
+impl EditMarketInstruction {
+    pub fn new(market_admin: TestKeypair, market: Pubkey) -> Self {
+        EditMarketInstruction {
+            market_admin,
+            market,
+            maker_fee_opt: None,
+            taker_fee_opt: None,
+            fee_penalty_opt: None,
+            taker_price_band_bps_opt: None,
+            maker_price_band_bps_opt: None,
+            collect_fee_admin_opt: None,
+            open_orders_admin_opt: None,
+            consume_events_admin_opt: None,
+            close_market_admin_opt: None,
+            market_admin_opt: None,
+            disabled_instructions_opt: None,
+            time_expiry_opt: None,
+            fee_tiers_opt: None,
+            fee_tier_volume_interval_opt: None,
+            buyback_opt: None,
+        }
+    }
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for EditMarketInstruction {
+    type Accounts = openbook_v2::accounts::EditMarket;
+    type Instruction = openbook_v2::instruction::EditMarket;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = openbook_v2::id();
+        let instruction = Self::Instruction {
+            maker_fee_opt: self.maker_fee_opt,
+            taker_fee_opt: self.taker_fee_opt,
+            fee_penalty_opt: self.fee_penalty_opt,
+            oracle_config_opt: None,
+            taker_price_band_bps_opt: self.taker_price_band_bps_opt,
+            maker_price_band_bps_opt: self.maker_price_band_bps_opt,
+            collect_fee_admin_opt: self.collect_fee_admin_opt,
+            open_orders_admin_opt: self.open_orders_admin_opt,
+            consume_events_admin_opt: self.consume_events_admin_opt,
+            close_market_admin_opt: self.close_market_admin_opt,
+            market_admin_opt: self.market_admin_opt,
+            disabled_instructions_opt: self.disabled_instructions_opt,
+            time_expiry_opt: self.time_expiry_opt,
+            fee_tiers_opt: self.fee_tiers_opt.clone(),
+            fee_tier_volume_interval_opt: self.fee_tier_volume_interval_opt,
+            buyback_opt: self.buyback_opt.clone(),
+        };
+
+        let market: Market = account_loader.load(&self.market).await.unwrap();
+
+        let accounts = Self::Accounts {
+            market_admin: self.market_admin.pubkey(),
+            market: self.market,
+            bids: market.bids,
+            asks: market.asks,
+            event_queue: market.event_queue,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.market_admin]
+    }
+}
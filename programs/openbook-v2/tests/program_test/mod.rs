@@ -3,6 +3,14 @@
 use std::cell::RefCell;
 use std::{sync::Arc, sync::RwLock};
 
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::{TransferFee, TransferFeeAmount, TransferFeeConfig},
+        ExtensionType, StateWithExtensionsMut,
+    },
+    pod::OptionalNonZeroPubkey,
+};
 use fixed::types::I80F48;
 use log::*;
 use openbook_v2::state::Market;
@@ -31,6 +39,7 @@ pub struct TestInitialize {
     pub open_orders_admin: TestKeypair,
     pub close_market_admin: TestKeypair,
     pub consume_events_admin: TestKeypair,
+    pub market_admin: TestKeypair,
     pub owner: TestKeypair,
     pub payer: TestKeypair,
     pub mints: Vec<MintCookie>,
@@ -44,6 +53,7 @@ pub struct TestInitialize {
     pub account_0: Pubkey,
     pub account_1: Pubkey,
     pub bids: Pubkey,
+    pub asks: Pubkey,
 }
 
 trait AddPacked {
@@ -98,11 +108,75 @@ impl Log for LoggerWrapper {
     fn flush(&self) {}
 }
 
+/// Token-2022 mint charging `transfer_fee_basis_points` on every transfer
+fn token_2022_mint_account(
+    mint: &MintCookie,
+    transfer_fee_basis_points: u16,
+) -> solana_sdk::account::Account {
+    let space = ExtensionType::get_account_len::<spl_token_2022::state::Mint>(&[
+        ExtensionType::TransferFeeConfig,
+    ]);
+    let mut account =
+        solana_sdk::account::Account::new(u32::MAX as u64, space, &spl_token_2022::id());
+    let mut state = StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(
+        &mut account.data,
+    )
+    .unwrap();
+
+    let transfer_fee = TransferFee {
+        epoch: 0.into(),
+        maximum_fee: u64::MAX.into(),
+        transfer_fee_basis_points: transfer_fee_basis_points.into(),
+    };
+    let transfer_fee_config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+    transfer_fee_config.withdraw_withheld_authority =
+        OptionalNonZeroPubkey::try_from(Some(mint.authority.pubkey())).unwrap();
+    transfer_fee_config.older_transfer_fee = transfer_fee;
+    transfer_fee_config.newer_transfer_fee = transfer_fee;
+
+    state.base = spl_token_2022::state::Mint {
+        mint_authority: COption::Some(mint.authority.pubkey()),
+        decimals: mint.decimals,
+        is_initialized: true,
+        ..spl_token_2022::state::Mint::default()
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+    account
+}
+
+/// Token-2022 account of a mint with a transfer fee
+fn token_2022_account(mint: Pubkey, owner: Pubkey, amount: u64) -> solana_sdk::account::Account {
+    let space = ExtensionType::get_account_len::<spl_token_2022::state::Account>(&[
+        ExtensionType::TransferFeeAmount,
+    ]);
+    let mut account =
+        solana_sdk::account::Account::new(u32::MAX as u64, space, &spl_token_2022::id());
+    let mut state = StateWithExtensionsMut::<spl_token_2022::state::Account>::unpack_uninitialized(
+        &mut account.data,
+    )
+    .unwrap();
+
+    state.init_extension::<TransferFeeAmount>(true).unwrap();
+    state.base = spl_token_2022::state::Account {
+        mint,
+        owner,
+        amount,
+        state: spl_token_2022::state::AccountState::Initialized,
+        ..spl_token_2022::state::Account::default()
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+    account
+}
+
 #[derive(Default)]
 pub struct TestContextBuilder {
     test: ProgramTest,
     logger_capture: Arc<RwLock<Vec<String>>>,
     mint0: Pubkey,
+    // The first two mints are Token-2022 mints with this transfer fee when set
+    transfer_fee_basis_points: Option<u16>,
 }
 
 lazy_static::lazy_static! {
@@ -139,6 +213,7 @@ impl TestContextBuilder {
             test,
             logger_capture: LOGGER_CAPTURE.clone(),
             mint0: Pubkey::new_unique(),
+            transfer_fee_basis_points: None,
         }
     }
 
@@ -178,6 +253,16 @@ impl TestContextBuilder {
             };
             mint.pubkey = mint_pk;
 
+            if let Some(transfer_fee_basis_points) = self.transfer_fee_basis_points {
+                if mint.index < 2 {
+                    self.test.add_account(
+                        mint_pk,
+                        token_2022_mint_account(mint, transfer_fee_basis_points),
+                    );
+                    continue;
+                }
+            }
+
             self.test.add_packable_account(
                 mint_pk,
                 u32::MAX as u64,
@@ -213,6 +298,19 @@ impl TestContextBuilder {
             let mut token_accounts = Vec::new();
             for mint in mints {
                 let token_key = Pubkey::new_unique();
+                if self.transfer_fee_basis_points.is_some() && mint.index < 2 {
+                    self.test.add_account(
+                        token_key,
+                        token_2022_account(
+                            mint.pubkey,
+                            user_key.pubkey(),
+                            1_000_000_000_000_000_000,
+                        ),
+                    );
+                    token_accounts.push(token_key);
+                    continue;
+                }
+
                 self.test.add_packable_account(
                     token_key,
                     u32::MAX as u64,
@@ -279,6 +377,10 @@ pub struct TestNewMarketInitialize {
     pub open_orders_admin_bool: bool,
     pub close_market_admin_bool: bool,
     pub consume_events_admin_bool: bool,
+    pub market_admin_bool: bool,
+    pub oracle_bool: bool,
+    pub native_base_mint: bool,
+    pub transfer_fee_basis_points: Option<u16>,
 }
 
 impl Default for TestNewMarketInitialize {
@@ -292,6 +394,10 @@ impl Default for TestNewMarketInitialize {
             open_orders_admin_bool: false,
             close_market_admin_bool: false,
             consume_events_admin_bool: false,
+            market_admin_bool: false,
+            oracle_bool: true,
+            native_base_mint: false,
+            transfer_fee_basis_points: None,
         }
     }
 }
@@ -303,7 +409,12 @@ impl TestContext {
     pub async fn new_with_market(
         args: TestNewMarketInitialize,
     ) -> Result<TestInitialize, TransportError> {
-        let context = TestContextBuilder::new().start_default().await;
+        let mut builder = TestContextBuilder::new();
+        if args.native_base_mint {
+            builder.mint0 = spl_token::native_mint::id();
+        }
+        builder.transfer_fee_basis_points = args.transfer_fee_basis_points;
+        let context = builder.start_default().await;
         let solana = &context.solana.clone();
 
         let collect_fee_admin_acc = TestKeypair::new();
@@ -325,6 +436,12 @@ impl TestContext {
         } else {
             None
         };
+        let market_admin_acc = TestKeypair::new();
+        let market_admin = if args.market_admin_bool {
+            Some(market_admin_acc.pubkey())
+        } else {
+            None
+        };
 
         let owner = context.users[0].key;
         let payer = context.users[1].key;
@@ -350,6 +467,7 @@ impl TestContext {
             base_vault,
             quote_vault,
             bids,
+            asks,
             ..
         } = send_tx(
             solana,
@@ -358,6 +476,7 @@ impl TestContext {
                 open_orders_admin,
                 close_market_admin,
                 consume_events_admin,
+                market_admin,
                 payer,
                 market_index: 1,
                 quote_lot_size: args.quote_lot_size,
@@ -369,6 +488,7 @@ impl TestContext {
                 base_vault,
                 quote_vault,
                 fee_penalty: args.fee_penalty,
+                oracle: args.oracle_bool.then_some(tokens[0].oracle),
                 ..CreateMarketInstruction::with_new_book_and_queue(solana, &tokens[0]).await
             },
         )
@@ -393,6 +513,7 @@ impl TestContext {
             open_orders_admin: open_orders_admin_acc,
             close_market_admin: close_market_admin_acc,
             consume_events_admin: consume_events_admin_acc,
+            market_admin: market_admin_acc,
             owner,
             payer,
             mints,
@@ -406,6 +527,7 @@ impl TestContext {
             account_0,
             account_1,
             bids,
+            asks,
         })
     }
 }
@@ -6,11 +6,11 @@ use std::sync::{Arc, RwLock};
 
 use super::utils::TestKeypair;
 use anchor_lang::AccountDeserialize;
-use anchor_spl::token::TokenAccount;
+use anchor_spl::token_2022::spl_token_2022::{self, extension::StateWithExtensions};
 use solana_program::{program_pack::Pack, rent::*, system_instruction};
 use solana_program_test::*;
 use solana_sdk::{
-    account::ReadableAccount,
+    account::{AccountSharedData, ReadableAccount},
     instruction::Instruction,
     pubkey::Pubkey,
     signature::{Keypair, Signer},
@@ -125,6 +125,13 @@ impl SolanaCookie {
         self.advance_clock_to(clock.unix_timestamp + seconds).await
     }
 
+    /// Move the unix timestamp of the clock without warping through the slots in between
+    pub async fn set_clock_unix_timestamp(&self, unix_timestamp: i64) {
+        let mut clock = self.get_clock().await;
+        clock.unix_timestamp = unix_timestamp;
+        self.context.borrow_mut().set_sysvar(&clock);
+    }
+
     pub async fn get_newest_slot_from_history(&self) -> u64 {
         self.context
             .borrow_mut()
@@ -196,19 +203,24 @@ impl SolanaCookie {
     }
 
     pub async fn create_associated_token_account(&self, owner: &Pubkey, mint: Pubkey) -> Pubkey {
+        let token_program = self.get_account_owner(mint).await.unwrap();
         let instruction =
             spl_associated_token_account::instruction::create_associated_token_account(
                 &self.context.borrow().payer.pubkey(),
                 owner,
                 &mint,
-                &spl_token::id(),
+                &token_program,
             );
 
         self.process_transaction(&[instruction], None)
             .await
             .unwrap();
 
-        spl_associated_token_account::get_associated_token_address(owner, &mint)
+        spl_associated_token_account::get_associated_token_address_with_program_id(
+            owner,
+            &mint,
+            &token_program,
+        )
     }
 
     // Note: Only one table can be created per authority per slot!
@@ -242,6 +254,31 @@ impl SolanaCookie {
         )
     }
 
+    pub async fn set_account_data(&self, address: Pubkey, data: &[u8]) {
+        let mut context = self.context.borrow_mut();
+        let mut account: AccountSharedData = context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap()
+            .into();
+        account.set_data_from_slice(data);
+        context.set_account(&address, &account);
+    }
+
+    pub async fn get_account_owner(&self, address: Pubkey) -> Option<Pubkey> {
+        Some(
+            self.context
+                .borrow_mut()
+                .banks_client
+                .get_account(address)
+                .await
+                .unwrap()?
+                .owner,
+        )
+    }
+
     pub async fn get_account_opt<T: AccountDeserialize>(&self, address: Pubkey) -> Option<T> {
         let data = self.get_account_data(address).await?;
         let mut data_slice: &[u8] = &data;
@@ -259,8 +296,22 @@ impl SolanaCookie {
         self.get_account_opt(address).await.unwrap()
     }
 
+    pub async fn get_account_lamports(&self, address: Pubkey) -> u64 {
+        self.context
+            .borrow_mut()
+            .banks_client
+            .get_balance(address)
+            .await
+            .unwrap()
+    }
+
     pub async fn token_account_balance(&self, address: Pubkey) -> u64 {
-        self.get_account::<TokenAccount>(address).await.amount
+        // Token-2022 accounts carry extensions after the base account
+        let data = self.get_account_data(address).await.unwrap();
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)
+            .unwrap()
+            .base
+            .amount
     }
 
     pub fn program_log(&self) -> Vec<String> {